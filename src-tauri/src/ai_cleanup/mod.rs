@@ -9,6 +9,16 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::disk_usage::{format_size, path_size};
+use crate::error::DevJanitorError;
+use crate::journal;
+use crate::operation::OperationResult;
+use crate::plan::PlannedRemoval;
+use crate::quarantine::{removal_description, remove_cleanup_target};
+use crate::rules;
+use crate::scan_job::ScanReporter;
+use crate::settings::active_settings;
+
 /// Represents an AI junk file detected
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiJunkFile {
@@ -123,38 +133,6 @@ const WHITELIST_DIRECTORIES: &[&str] = &[
 /// Clamp depth when the user tries to scan the filesystem root
 const ROOT_SCAN_MAX_DEPTH: usize = 8;
 
-/// Format bytes to human readable string
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
-}
-
-/// Get directory size
-fn get_size(path: &PathBuf) -> u64 {
-    if path.is_file() {
-        path.metadata().map(|m| m.len()).unwrap_or(0)
-    } else {
-        WalkDir::new(path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter_map(|e| e.metadata().ok())
-            .filter(|m| m.is_file())
-            .map(|m| m.len())
-            .sum()
-    }
-}
-
 /// Check if a file/directory should be whitelisted
 fn is_whitelisted(path: &Path) -> bool {
     let name = path
@@ -182,16 +160,37 @@ fn is_whitelisted(path: &Path) -> bool {
     false
 }
 
-/// Check if a file matches AI tool patterns
-fn check_ai_tool_pattern(path: &Path) -> Option<(&'static str, &'static str)> {
+/// Built-in AI tool junk patterns, which user rules can disable
+pub(crate) fn builtin_ai_tool_patterns() -> Vec<&'static str> {
+    AI_TOOL_PATTERNS
+        .iter()
+        .map(|(pattern, _)| *pattern)
+        .collect()
+}
+
+/// Built-in temporary file patterns, which user rules can disable
+pub(crate) fn builtin_temp_file_patterns() -> Vec<&'static str> {
+    TEMP_FILE_PATTERNS
+        .iter()
+        .map(|(pattern, _)| *pattern)
+        .collect()
+}
+
+/// Check if a file matches AI tool patterns, with user rules applied
+fn check_ai_tool_pattern(path: &Path) -> Option<(String, String)> {
+    let rules = rules::active_rules();
+    let overlay = &rules.ai_tools;
     let name = path
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_default();
 
     for (pattern, reason) in AI_TOOL_PATTERNS {
-        if !pattern.contains('/') && (name == *pattern || name.starts_with(pattern)) {
-            return Some((pattern, reason));
+        if !pattern.contains('/')
+            && (name == *pattern || name.starts_with(pattern))
+            && !overlay.is_disabled(pattern)
+        {
+            return Some((pattern.to_string(), reason.to_string()));
         }
     }
 
@@ -204,31 +203,42 @@ fn check_ai_tool_pattern(path: &Path) -> Option<(&'static str, &'static str)> {
         }
 
         let pattern_lower = pattern.to_ascii_lowercase();
-        if path_str == pattern_lower
+        if (path_str == pattern_lower
             || path_str.ends_with(&format!("/{pattern_lower}"))
-            || path_str.contains(&format!("/{pattern_lower}/"))
+            || path_str.contains(&format!("/{pattern_lower}/")))
+            && !overlay.is_disabled(pattern)
         {
-            return Some((pattern, reason));
+            return Some((pattern.to_string(), reason.to_string()));
         }
     }
 
-    None
+    overlay
+        .added
+        .iter()
+        .find(|rule| rule.pattern.matches_path(path))
+        .map(|rule| (rule.pattern.as_str().to_string(), rule.label.clone()))
 }
 
-/// Check if a file matches temp file patterns
-fn check_temp_pattern(path: &Path) -> Option<(&'static str, &'static str)> {
+/// Check if a file matches temp file patterns, with user rules applied
+fn check_temp_pattern(path: &Path) -> Option<(String, String)> {
+    let rules = rules::active_rules();
+    let overlay = &rules.temp_files;
     let name = path
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_default();
 
     for (pattern, reason) in TEMP_FILE_PATTERNS {
-        if name == *pattern || name.ends_with(pattern) {
-            return Some((pattern, reason));
+        if (name == *pattern || name.ends_with(pattern)) && !overlay.is_disabled(pattern) {
+            return Some((pattern.to_string(), reason.to_string()));
         }
     }
 
-    None
+    overlay
+        .added
+        .iter()
+        .find(|rule| rule.pattern.matches_name(&name))
+        .map(|rule| (rule.pattern.as_str().to_string(), rule.label.clone()))
 }
 
 /// Check for anomalous files (zero-byte, very short names, etc.)
@@ -295,18 +305,20 @@ fn is_root_path(path: &Path) -> bool {
     path.parent().is_none()
 }
 
-fn canonicalize_existing_path(path: &Path) -> Result<PathBuf, String> {
-    let metadata = fs::symlink_metadata(path)
-        .map_err(|error| format!("Failed to inspect {}: {}", path.display(), error))?;
+fn canonicalize_existing_path(path: &Path) -> Result<PathBuf, DevJanitorError> {
+    let metadata = fs::symlink_metadata(path).map_err(|error| {
+        DevJanitorError::io(format!("Failed to inspect {}", path.display()), error)
+    })?;
     if metadata.file_type().is_symlink() {
-        return Err(format!(
+        return Err(DevJanitorError::UnsafePath(format!(
             "Refusing to delete symlink path: {}",
             path.display()
-        ));
+        )));
     }
 
-    path.canonicalize()
-        .map_err(|error| format!("Failed to resolve {}: {}", path.display(), error))
+    path.canonicalize().map_err(|error| {
+        DevJanitorError::io(format!("Failed to resolve {}", path.display()), error)
+    })
 }
 
 fn is_root_or_home_path(path: &Path) -> bool {
@@ -320,21 +332,21 @@ fn is_root_or_home_path(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn validate_ai_junk_delete_target(path: &Path) -> Result<PathBuf, String> {
+fn validate_ai_junk_delete_target(path: &Path) -> Result<PathBuf, DevJanitorError> {
     let canonical = canonicalize_existing_path(path)?;
 
     if is_root_or_home_path(&canonical) {
-        return Err(format!(
+        return Err(DevJanitorError::UnsafePath(format!(
             "Refusing to delete unsafe path: {}",
             canonical.display()
-        ));
+        )));
     }
 
     if is_whitelisted(&canonical) {
-        return Err(format!(
+        return Err(DevJanitorError::UnsafePath(format!(
             "Refusing to delete whitelisted path: {}",
             canonical.display()
-        ));
+        )));
     }
 
     let looks_like_junk = check_ai_tool_pattern(&canonical).is_some()
@@ -344,10 +356,10 @@ fn validate_ai_junk_delete_target(path: &Path) -> Result<PathBuf, String> {
     if looks_like_junk {
         Ok(canonical)
     } else {
-        Err(format!(
+        Err(DevJanitorError::NotATarget(format!(
             "Path is not a recognized AI junk target: {}",
             canonical.display()
-        ))
+        )))
     }
 }
 
@@ -374,17 +386,25 @@ fn make_junk_file(
     }
 }
 
-fn scan_target(root: &Path, max_depth: usize) -> Vec<AiJunkFile> {
+fn scan_target(root: &Path, max_depth: usize, progress: &ScanReporter) -> Vec<AiJunkFile> {
     let mut junk_files = Vec::new();
+    let settings = active_settings();
+    let exclusions = settings.exclusions();
     let mut entries = WalkDir::new(root).max_depth(max_depth).into_iter();
 
     while let Some(entry_result) = entries.next() {
+        if progress.is_cancelled() {
+            break;
+        }
         let Ok(entry) = entry_result else {
             continue;
         };
         let path = entry.path();
+        if entry.file_type().is_dir() {
+            progress.visit_dir(path);
+        }
 
-        if is_whitelisted(path) {
+        if is_whitelisted(path) || exclusions.is_excluded(path) {
             if entry.file_type().is_dir() {
                 entries.skip_current_dir();
             }
@@ -393,54 +413,34 @@ fn scan_target(root: &Path, max_depth: usize) -> Vec<AiJunkFile> {
 
         let name = entry.file_name().to_string_lossy().to_string();
 
-        if let Some((pattern, reason)) = check_ai_tool_pattern(path) {
-            let path_buf = path.to_path_buf();
-            let size = get_size(&path_buf);
-            junk_files.push(make_junk_file(
+        let matched = if let Some((pattern, reason)) = check_ai_tool_pattern(path) {
+            Some((
                 "ai",
-                path,
-                name,
-                size,
                 "ai_tool",
                 format!("AI Tool: {} - {}", pattern, reason),
-            ));
-            if entry.file_type().is_dir() {
-                entries.skip_current_dir();
-            }
-            continue;
-        }
-
-        if let Some((pattern, reason)) = check_temp_pattern(path) {
-            let path_buf = path.to_path_buf();
-            let size = get_size(&path_buf);
-            junk_files.push(make_junk_file(
+            ))
+        } else if let Some((pattern, reason)) = check_temp_pattern(path) {
+            Some((
                 "temp",
-                path,
-                name,
-                size,
                 "temp_file",
                 format!("Temp: {} - {}", pattern, reason),
-            ));
-            if entry.file_type().is_dir() {
-                entries.skip_current_dir();
-            }
+            ))
+        } else {
+            check_anomalous(path).map(|reason| ("anomaly", "anomalous", reason))
+        };
+        let Some((id_prefix, junk_type, reason)) = matched else {
             continue;
-        }
+        };
 
-        if let Some(reason) = check_anomalous(path) {
-            let path_buf = path.to_path_buf();
-            let size = get_size(&path_buf);
+        let size = path_size(path);
+        if size >= settings.min_ai_junk_size {
+            progress.found(size);
             junk_files.push(make_junk_file(
-                "anomaly",
-                path,
-                name,
-                size,
-                "anomalous",
-                reason,
+                id_prefix, path, name, size, junk_type, reason,
             ));
-            if entry.file_type().is_dir() {
-                entries.skip_current_dir();
-            }
+        }
+        if entry.file_type().is_dir() {
+            entries.skip_current_dir();
         }
     }
 
@@ -449,6 +449,15 @@ fn scan_target(root: &Path, max_depth: usize) -> Vec<AiJunkFile> {
 
 /// Scan a directory for AI junk files
 pub fn scan_ai_junk(root_path: &str, max_depth: usize) -> Vec<AiJunkFile> {
+    scan_ai_junk_with_progress(root_path, max_depth, &ScanReporter::silent())
+}
+
+/// `scan_ai_junk`, reporting progress and stopping early when cancelled
+pub fn scan_ai_junk_with_progress(
+    root_path: &str,
+    max_depth: usize,
+    progress: &ScanReporter,
+) -> Vec<AiJunkFile> {
     let root = PathBuf::from(root_path);
     if !root.exists() {
         return Vec::new();
@@ -468,7 +477,7 @@ pub fn scan_ai_junk(root_path: &str, max_depth: usize) -> Vec<AiJunkFile> {
 
     let mut junk_files: Vec<AiJunkFile> = targets
         .par_iter()
-        .flat_map(|target| scan_target(target, effective_depth))
+        .flat_map(|target| scan_target(target, effective_depth, progress))
         .collect();
 
     // De-duplicate by full path to avoid repeats when targets overlap
@@ -489,12 +498,14 @@ pub fn scan_ai_junk(root_path: &str, max_depth: usize) -> Vec<AiJunkFile> {
     junk_files
 }
 
-/// Delete an AI junk file with permission retry
-pub fn delete_ai_junk(path: &str) -> Result<String, String> {
+fn resolve_ai_junk_delete_target(path: &str) -> Result<PathBuf, DevJanitorError> {
     let file_path = PathBuf::from(path);
 
     if !file_path.exists() {
-        return Err(format!("File does not exist: {}", path));
+        return Err(DevJanitorError::NotFound(format!(
+            "File does not exist: {}",
+            path
+        )));
     }
 
     let file_path = validate_ai_junk_delete_target(&file_path)?;
@@ -512,102 +523,45 @@ pub fn delete_ai_junk(path: &str) -> Result<String, String> {
         ];
         let name_without_ext = name.split('.').next().unwrap_or(&name);
         if reserved.contains(&name_without_ext) {
-            return Err(format!("Cannot delete Windows reserved name: {}", path));
+            return Err(DevJanitorError::UnsafePath(format!(
+                "Cannot delete Windows reserved name: {}",
+                path
+            )));
         }
     }
 
-    // Get size before deletion
-    let size = get_size(&file_path);
-
-    // Try to delete
-    let result = if file_path.is_dir() {
-        fs::remove_dir_all(&file_path)
-    } else {
-        fs::remove_file(&file_path)
-    };
-
-    match result {
-        Ok(_) => Ok(format!(
-            "Successfully deleted {} (freed {})",
-            path,
-            format_size(size)
-        )),
-        Err(e) => {
-            // Try with permission fix
-            #[cfg(target_os = "windows")]
-            {
-                if fix_permissions_and_delete(&file_path).is_ok() {
-                    return Ok(format!(
-                        "Successfully deleted {} (freed {})",
-                        path,
-                        format_size(size)
-                    ));
-                }
-            }
-
-            #[cfg(unix)]
-            {
-                if chmod_and_delete(&file_path).is_ok() {
-                    return Ok(format!(
-                        "Successfully deleted {} (freed {})",
-                        path,
-                        format_size(size)
-                    ));
-                }
-            }
-
-            Err(format!("Failed to delete {}: {}", path, e))
-        }
-    }
+    Ok(file_path)
 }
 
-#[cfg(target_os = "windows")]
-#[allow(clippy::permissions_set_readonly_false)]
-fn fix_permissions_and_delete(path: &PathBuf) -> std::io::Result<()> {
-    use std::os::windows::fs::MetadataExt;
-
-    // Remove readonly attribute
-    if let Ok(metadata) = fs::metadata(path) {
-        if metadata.file_attributes() & 1 != 0 {
-            let mut perms = metadata.permissions();
-            perms.set_readonly(false);
-            fs::set_permissions(path, perms)?;
-        }
-    }
-
-    if path.is_dir() {
-        // Recursively fix permissions
-        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-            if let Ok(metadata) = fs::metadata(entry.path()) {
-                if metadata.file_attributes() & 1 != 0 {
-                    let mut perms = metadata.permissions();
-                    perms.set_readonly(false);
-                    let _ = fs::set_permissions(entry.path(), perms);
-                }
-            }
-        }
-        fs::remove_dir_all(path)
-    } else {
-        fs::remove_file(path)
-    }
+/// Report what `delete_ai_junk` would remove without touching it
+pub fn plan_delete_ai_junk(path: &str) -> Result<PlannedRemoval, DevJanitorError> {
+    let file_path = resolve_ai_junk_delete_target(path)?;
+    Ok(PlannedRemoval::new(&file_path, path_size(&file_path)))
 }
 
-#[cfg(unix)]
-fn chmod_and_delete(path: &PathBuf) -> std::io::Result<()> {
-    use std::os::unix::fs::PermissionsExt;
+/// Delete an AI junk file by moving it into quarantine (or deleting it, per settings)
+pub fn delete_ai_junk(path: &str) -> Result<OperationResult, DevJanitorError> {
+    let file_path = resolve_ai_junk_delete_target(path)?;
 
-    // Set write permission
-    if let Ok(metadata) = fs::metadata(path) {
-        let mut perms = metadata.permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(path, perms)?;
-    }
+    // Get size before the move so the quarantine entry can report it
+    let size = path_size(&file_path);
 
-    if path.is_dir() {
-        fs::remove_dir_all(path)
-    } else {
-        fs::remove_file(path)
-    }
+    let result = remove_cleanup_target(&file_path, "ai_junk", size);
+    let journaled = journal::record_cleanup("delete_ai_junk", &file_path, size, &result);
+    let entry = result?;
+
+    Ok(OperationResult::cleaned(
+        path,
+        size,
+        entry.is_some(),
+        format!(
+            "Successfully deleted {} ({}, {})",
+            path,
+            format_size(size),
+            removal_description(&entry)
+        ),
+    )
+    .journaled(journaled))
 }
 
 #[cfg(test)]
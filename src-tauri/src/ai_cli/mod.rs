@@ -8,7 +8,13 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use crate::ai_tools::{ai_tools, find_ai_tool, normalize_ai_tool_id, AiToolMetadata};
-use crate::utils::command::{command_output_with_timeout, command_output_with_timeout_vec};
+use crate::error::DevJanitorError;
+use crate::operation::{run_first_success, OperationResult};
+use crate::plan::PlannedCommand;
+use crate::utils::runner::active_runner;
+
+/// Installers download and build, so they get far longer than detection probes
+const TOOL_ACTION_TIMEOUT: Duration = Duration::from_secs(300);
 
 /// Represents an AI CLI tool
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -458,7 +464,9 @@ fn version_probe_attempts(tool_id: &str) -> Vec<(&'static str, &'static [&'stati
 }
 
 fn run_command_capture(cmd: &str, args: &[&str]) -> Option<String> {
-    let output = command_output_with_timeout(cmd, args, Duration::from_secs(6)).ok()?;
+    let output = active_runner()
+        .output(cmd, args, Duration::from_secs(6))
+        .ok()?;
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -479,55 +487,50 @@ fn extract_semver_like(output: &str) -> Option<String> {
         .map(|version| version.as_str().to_string())
 }
 
-/// Install an AI CLI tool
-pub fn install_ai_tool(tool_id: &str) -> Result<String, String> {
-    let tool_id =
-        normalize_ai_tool_id(tool_id).ok_or_else(|| format!("Tool not found: {}", tool_id))?;
-    let metadata = find_ai_tool(tool_id).ok_or_else(|| format!("Tool not found: {}", tool_id))?;
+/// Resolve a lifecycle action to the commands it runs, refusing manual-only actions
+fn prepare_tool_action(
+    tool_id: &str,
+    action: ToolAction,
+) -> Result<Vec<PlannedCommand>, DevJanitorError> {
+    let tool_id = normalize_ai_tool_id(tool_id)
+        .ok_or_else(|| DevJanitorError::ToolNotFound(tool_id.to_string()))?;
+    let metadata =
+        find_ai_tool(tool_id).ok_or_else(|| DevJanitorError::ToolNotFound(tool_id.to_string()))?;
     let commands = lifecycle_commands(tool_id);
 
-    if is_manual_action(&commands.install) {
-        return Err(format!(
-            "{} requires manual installation. Visit: {}",
-            metadata.name, metadata.docs_url
-        ));
+    let (command, description) = match action {
+        ToolAction::Install => (&commands.install, "installation"),
+        ToolAction::Update => (&commands.update, "update"),
+        ToolAction::Uninstall => (&commands.uninstall, "uninstallation"),
+    };
+    if is_manual_action(command) {
+        return Err(DevJanitorError::ManualActionRequired(format!(
+            "{} requires manual {}. Visit: {}",
+            metadata.name, description, metadata.docs_url
+        )));
     }
 
-    execute_tool_action(tool_id, ToolAction::Install)
+    tool_action_commands(tool_id, action).map_err(DevJanitorError::ManualActionRequired)
 }
 
-/// Update an AI CLI tool
-pub fn update_ai_tool(tool_id: &str) -> Result<String, String> {
-    let tool_id =
-        normalize_ai_tool_id(tool_id).ok_or_else(|| format!("Tool not found: {}", tool_id))?;
-    let metadata = find_ai_tool(tool_id).ok_or_else(|| format!("Tool not found: {}", tool_id))?;
-    let commands = lifecycle_commands(tool_id);
-
-    if is_manual_action(&commands.update) {
-        return Err(format!(
-            "{} requires manual update. Visit: {}",
-            metadata.name, metadata.docs_url
-        ));
-    }
+/// Install an AI CLI tool
+pub fn install_ai_tool(tool_id: &str) -> Result<OperationResult, DevJanitorError> {
+    run_tool_action(tool_id, ToolAction::Install)
+}
 
-    execute_tool_action(tool_id, ToolAction::Update)
+/// Update an AI CLI tool
+pub fn update_ai_tool(tool_id: &str) -> Result<OperationResult, DevJanitorError> {
+    run_tool_action(tool_id, ToolAction::Update)
 }
 
 /// Uninstall an AI CLI tool
-pub fn uninstall_ai_tool(tool_id: &str) -> Result<String, String> {
-    let tool_id =
-        normalize_ai_tool_id(tool_id).ok_or_else(|| format!("Tool not found: {}", tool_id))?;
-    let metadata = find_ai_tool(tool_id).ok_or_else(|| format!("Tool not found: {}", tool_id))?;
-    let commands = lifecycle_commands(tool_id);
-
-    if is_manual_action(&commands.uninstall) {
-        return Err(format!(
-            "{} requires manual uninstallation. Visit: {}",
-            metadata.name, metadata.docs_url
-        ));
-    }
+pub fn uninstall_ai_tool(tool_id: &str) -> Result<OperationResult, DevJanitorError> {
+    run_tool_action(tool_id, ToolAction::Uninstall)
+}
 
-    execute_tool_action(tool_id, ToolAction::Uninstall)
+/// Commands `uninstall_ai_tool` would try in order
+pub fn plan_uninstall_ai_tool(tool_id: &str) -> Result<Vec<PlannedCommand>, DevJanitorError> {
+    prepare_tool_action(tool_id, ToolAction::Uninstall)
 }
 
 #[cfg(test)]
@@ -657,28 +660,40 @@ enum ToolAction {
     Uninstall,
 }
 
-fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, String> {
+impl ToolAction {
+    fn journal_action(self) -> &'static str {
+        match self {
+            ToolAction::Install => "install_ai_tool",
+            ToolAction::Update => "update_ai_tool",
+            ToolAction::Uninstall => "uninstall_ai_tool",
+        }
+    }
+}
+
+fn tool_action_commands(tool_id: &str, action: ToolAction) -> Result<Vec<PlannedCommand>, String> {
     match (tool_id, action) {
         ("claude", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
+                Ok(vec![PlannedCommand::owned(
                     "powershell",
-                    &[
+                    vec![
                         "-NoProfile".to_string(),
                         "-ExecutionPolicy".to_string(),
                         "Bypass".to_string(),
                         "-Command".to_string(),
                         "irm https://claude.ai/install.ps1 | iex".to_string(),
                     ],
-                )
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -fsSL https://claude.ai/install.sh | bash")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -fsSL https://claude.ai/install.sh | bash",
+                )])
             }
         }
-        ("claude", ToolAction::Update) => run_first_success(&[
+        ("claude", ToolAction::Update) => Ok(first_success(&[
             ("claude", vec!["update".to_string()]),
             (
                 "npm",
@@ -688,26 +703,26 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
                     "@anthropic-ai/claude-code@latest".to_string(),
                 ],
             ),
-        ]),
+        ])),
         ("claude", ToolAction::Uninstall) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
-                    "powershell",
-                    &[
+                Ok(vec![PlannedCommand::owned("powershell", vec![
                         "-NoProfile".to_string(),
                         "-Command".to_string(),
                         "Remove-Item -Path \"$env:USERPROFILE\\.local\\bin\\claude.exe\" -Force -ErrorAction SilentlyContinue; Remove-Item -Path \"$env:USERPROFILE\\.local\\share\\claude\" -Recurse -Force -ErrorAction SilentlyContinue; if (Get-Command npm -ErrorAction SilentlyContinue) { npm uninstall -g @anthropic-ai/claude-code }".to_string(),
-                    ],
-                )
+                    ])])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("rm -f ~/.local/bin/claude; rm -rf ~/.local/share/claude; if command -v npm >/dev/null 2>&1; then npm uninstall -g @anthropic-ai/claude-code; fi")
+                Ok(vec![PlannedCommand::shell("rm -f ~/.local/bin/claude; rm -rf ~/.local/share/claude; if command -v npm >/dev/null 2>&1; then npm uninstall -g @anthropic-ai/claude-code; fi")])
             }
         }
-        ("codex", ToolAction::Install) => run_command("npm", &["i", "-g", "@openai/codex"]),
-        ("codex", ToolAction::Update) => run_first_success(&[
+        ("codex", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["i", "-g", "@openai/codex"],
+        )]),
+        ("codex", ToolAction::Update) => Ok(first_success(&[
             ("codex", vec!["update".to_string()]),
             (
                 "npm",
@@ -717,21 +732,27 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
                     "@openai/codex@latest".to_string(),
                 ],
             ),
-        ]),
-        ("codex", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@openai/codex"])
-        }
+        ])),
+        ("codex", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@openai/codex"],
+        )]),
         ("opencode", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_command("npm", &["install", "-g", "opencode-ai"])
+                Ok(vec![PlannedCommand::new(
+                    "npm",
+                    &["install", "-g", "opencode-ai"],
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -fsSL https://opencode.ai/install | bash")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -fsSL https://opencode.ai/install | bash",
+                )])
             }
         }
-        ("opencode", ToolAction::Update) => run_first_success(&[
+        ("opencode", ToolAction::Update) => Ok(first_success(&[
             ("opencode", vec!["upgrade".to_string()]),
             (
                 "npm",
@@ -741,8 +762,8 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
                     "opencode-ai@latest".to_string(),
                 ],
             ),
-        ]),
-        ("opencode", ToolAction::Uninstall) => run_first_success(&[
+        ])),
+        ("opencode", ToolAction::Uninstall) => Ok(first_success(&[
             ("opencode", vec!["uninstall".to_string()]),
             (
                 "npm",
@@ -752,7 +773,7 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
                     "opencode-ai".to_string(),
                 ],
             ),
-        ]),
+        ])),
         ("goose", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
@@ -760,12 +781,10 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command(
-                    "curl -fsSL https://github.com/aaif-goose/goose/releases/download/stable/download_cli.sh | bash",
-                )
+                Ok(vec![PlannedCommand::shell("curl -fsSL https://github.com/aaif-goose/goose/releases/download/stable/download_cli.sh | bash")])
             }
         }
-        ("goose", ToolAction::Update) => run_command("goose", &["update"]),
+        ("goose", ToolAction::Update) => Ok(vec![PlannedCommand::new("goose", &["update"])]),
         ("goose", ToolAction::Uninstall) => {
             Err("Goose CLI requires manual uninstallation".to_string())
         }
@@ -776,7 +795,10 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_command("uv", &["tool", "install", "openhands", "--python", "3.12"])
+                Ok(vec![PlannedCommand::new(
+                    "uv",
+                    &["tool", "install", "openhands", "--python", "3.12"],
+                )])
             }
         }
         ("openhands", ToolAction::Update) => {
@@ -786,7 +808,10 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_command("uv", &["tool", "upgrade", "openhands", "--python", "3.12"])
+                Ok(vec![PlannedCommand::new(
+                    "uv",
+                    &["tool", "upgrade", "openhands", "--python", "3.12"],
+                )])
             }
         }
         ("openhands", ToolAction::Uninstall) => {
@@ -796,73 +821,89 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_command("uv", &["tool", "uninstall", "openhands"])
+                Ok(vec![PlannedCommand::new(
+                    "uv",
+                    &["tool", "uninstall", "openhands"],
+                )])
             }
         }
-        ("auggie", ToolAction::Install) => {
-            run_command("npm", &["install", "-g", "@augmentcode/auggie"])
-        }
-        ("auggie", ToolAction::Update) => {
-            run_command("auggie", &["upgrade", "--skip-confirmation"])
-        }
-        ("auggie", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@augmentcode/auggie"])
-        }
-        ("kilo", ToolAction::Install) => run_command("npm", &["install", "-g", "@kilocode/cli"]),
-        ("kilo", ToolAction::Update) => run_command("kilo", &["upgrade"]),
-        ("kilo", ToolAction::Uninstall) => run_command("kilo", &["uninstall"]),
+        ("auggie", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@augmentcode/auggie"],
+        )]),
+        ("auggie", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "auggie",
+            &["upgrade", "--skip-confirmation"],
+        )]),
+        ("auggie", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@augmentcode/auggie"],
+        )]),
+        ("kilo", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@kilocode/cli"],
+        )]),
+        ("kilo", ToolAction::Update) => Ok(vec![PlannedCommand::new("kilo", &["upgrade"])]),
+        ("kilo", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new("kilo", &["uninstall"])]),
         ("junie", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
+                Ok(vec![PlannedCommand::owned(
                     "powershell",
-                    &[
+                    vec![
                         "-NoProfile".to_string(),
                         "-ExecutionPolicy".to_string(),
                         "Bypass".to_string(),
                         "-Command".to_string(),
                         "iex (irm 'https://junie.jetbrains.com/install.ps1')".to_string(),
                     ],
-                )
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -fsSL https://junie.jetbrains.com/install.sh | bash")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -fsSL https://junie.jetbrains.com/install.sh | bash",
+                )])
             }
         }
         ("junie", ToolAction::Update) => Err("Junie CLI requires manual update".to_string()),
         ("junie", ToolAction::Uninstall) => {
             Err("Junie CLI requires manual uninstallation".to_string())
         }
-        ("gemini", ToolAction::Install) => {
-            run_command("npm", &["install", "-g", "@google/gemini-cli"])
-        }
-        ("gemini", ToolAction::Update) => {
-            run_command("npm", &["install", "-g", "@google/gemini-cli@latest"])
-        }
-        ("gemini", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@google/gemini-cli"])
-        }
+        ("gemini", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@google/gemini-cli"],
+        )]),
+        ("gemini", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@google/gemini-cli@latest"],
+        )]),
+        ("gemini", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@google/gemini-cli"],
+        )]),
         ("aider", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
+                Ok(vec![PlannedCommand::owned(
                     "powershell",
-                    &[
+                    vec![
                         "-ExecutionPolicy".to_string(),
                         "ByPass".to_string(),
                         "-c".to_string(),
                         "irm https://aider.chat/install.ps1 | iex".to_string(),
                     ],
-                )
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -LsSf https://aider.chat/install.sh | sh")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -LsSf https://aider.chat/install.sh | sh",
+                )])
             }
         }
-        ("aider", ToolAction::Update) => run_command("aider", &["--upgrade"]),
-        ("aider", ToolAction::Uninstall) => run_first_success(&[
+        ("aider", ToolAction::Update) => Ok(vec![PlannedCommand::new("aider", &["--upgrade"])]),
+        ("aider", ToolAction::Uninstall) => Ok(first_success(&[
             (
                 "uv",
                 vec![
@@ -875,53 +916,61 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
                 "pipx",
                 vec!["uninstall".to_string(), "aider-chat".to_string()],
             ),
-        ]),
+        ])),
         ("continue", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_command("npm", &["install", "-g", "@continuedev/cli"])
+                Ok(vec![PlannedCommand::new(
+                    "npm",
+                    &["install", "-g", "@continuedev/cli"],
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command(
-                    "curl -fsSL https://raw.githubusercontent.com/continuedev/continue/main/extensions/cli/scripts/install.sh | bash",
-                )
+                Ok(vec![PlannedCommand::shell("curl -fsSL https://raw.githubusercontent.com/continuedev/continue/main/extensions/cli/scripts/install.sh | bash")])
             }
         }
-        ("continue", ToolAction::Update) => {
-            run_command("npm", &["install", "-g", "@continuedev/cli@latest"])
-        }
-        ("continue", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@continuedev/cli"])
-        }
-        ("cody", ToolAction::Install) => {
-            run_command("npm", &["install", "-g", "@sourcegraph/cody"])
-        }
-        ("cody", ToolAction::Update) => {
-            run_command("npm", &["install", "-g", "@sourcegraph/cody@latest"])
-        }
-        ("cody", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@sourcegraph/cody"])
-        }
+        ("continue", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@continuedev/cli@latest"],
+        )]),
+        ("continue", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@continuedev/cli"],
+        )]),
+        ("cody", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@sourcegraph/cody"],
+        )]),
+        ("cody", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@sourcegraph/cody@latest"],
+        )]),
+        ("cody", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@sourcegraph/cody"],
+        )]),
         ("kiro", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
+                Ok(vec![PlannedCommand::owned(
                     "powershell",
-                    &[
+                    vec![
                         "-ExecutionPolicy".to_string(),
                         "ByPass".to_string(),
                         "-c".to_string(),
                         "irm https://kiro.dev/install.ps1 | iex".to_string(),
                     ],
-                )
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -fsSL https://kiro.dev/install | bash")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -fsSL https://kiro.dev/install | bash",
+                )])
             }
         }
-        ("kiro", ToolAction::Update) => run_first_success(&[
+        ("kiro", ToolAction::Update) => Ok(first_success(&[
             (
                 "kiro-cli",
                 vec!["update".to_string(), "--non-interactive".to_string()],
@@ -930,83 +979,105 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
                 "kiro",
                 vec!["update".to_string(), "--non-interactive".to_string()],
             ),
-        ]),
-        ("kiro", ToolAction::Uninstall) => run_first_success(&[
+        ])),
+        ("kiro", ToolAction::Uninstall) => Ok(first_success(&[
             ("kiro-cli", vec!["uninstall".to_string()]),
             ("kiro", vec!["uninstall".to_string()]),
-        ]),
+        ])),
         ("cursor", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
+                Ok(vec![PlannedCommand::owned(
                     "powershell",
-                    &[
+                    vec![
                         "-ExecutionPolicy".to_string(),
                         "ByPass".to_string(),
                         "-c".to_string(),
                         "irm https://cursor.com/install | iex".to_string(),
                     ],
-                )
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -fsS https://cursor.com/install | bash")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -fsS https://cursor.com/install | bash",
+                )])
             }
         }
-        ("cursor", ToolAction::Update) => run_first_success(&[
+        ("cursor", ToolAction::Update) => Ok(first_success(&[
             ("cursor-agent", vec!["upgrade".to_string()]),
             ("cursor-agent", vec!["update".to_string()]),
-        ]),
+        ])),
         ("cursor", ToolAction::Uninstall) => Err("Cursor CLI 需要手动卸载".to_string()),
-        ("iflow", ToolAction::Install) => {
-            run_command("npm", &["install", "-g", "@iflow-ai/iflow-cli"])
-        }
-        ("iflow", ToolAction::Update) => {
-            run_command("npm", &["install", "-g", "@iflow-ai/iflow-cli@latest"])
-        }
-        ("iflow", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@iflow-ai/iflow-cli"])
-        }
-        ("copilot", ToolAction::Install) => {
-            run_command("npm", &["install", "-g", "@github/copilot"])
-        }
-        ("copilot", ToolAction::Update) => {
-            run_command("npm", &["install", "-g", "@github/copilot@latest"])
-        }
-        ("copilot", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@github/copilot"])
-        }
-        ("qwen", ToolAction::Install) => {
-            run_command("npm", &["install", "-g", "@qwen-code/qwen-code"])
-        }
-        ("qwen", ToolAction::Update) => {
-            run_command("npm", &["install", "-g", "@qwen-code/qwen-code@latest"])
-        }
-        ("qwen", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@qwen-code/qwen-code"])
-        }
-        ("cline", ToolAction::Install) => run_command("npm", &["install", "-g", "cline"]),
-        ("cline", ToolAction::Update) => run_command("npm", &["install", "-g", "cline@latest"]),
-        ("cline", ToolAction::Uninstall) => run_command("npm", &["uninstall", "-g", "cline"]),
+        ("iflow", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@iflow-ai/iflow-cli"],
+        )]),
+        ("iflow", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@iflow-ai/iflow-cli@latest"],
+        )]),
+        ("iflow", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@iflow-ai/iflow-cli"],
+        )]),
+        ("copilot", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@github/copilot"],
+        )]),
+        ("copilot", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@github/copilot@latest"],
+        )]),
+        ("copilot", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@github/copilot"],
+        )]),
+        ("qwen", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@qwen-code/qwen-code"],
+        )]),
+        ("qwen", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@qwen-code/qwen-code@latest"],
+        )]),
+        ("qwen", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@qwen-code/qwen-code"],
+        )]),
+        ("cline", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "cline"],
+        )]),
+        ("cline", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "cline@latest"],
+        )]),
+        ("cline", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "cline"],
+        )]),
         ("amp", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
+                Ok(vec![PlannedCommand::owned(
                     "powershell",
-                    &[
+                    vec![
                         "-ExecutionPolicy".to_string(),
                         "ByPass".to_string(),
                         "-c".to_string(),
                         "irm https://ampcode.com/install.ps1 | iex".to_string(),
                     ],
-                )
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -fsSL https://ampcode.com/install.sh | bash")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -fsSL https://ampcode.com/install.sh | bash",
+                )])
             }
         }
-        ("amp", ToolAction::Update) => run_first_success(&[
+        ("amp", ToolAction::Update) => Ok(first_success(&[
             ("amp", vec!["update".to_string()]),
             (
                 "npm",
@@ -1016,140 +1087,128 @@ fn execute_tool_action(tool_id: &str, action: ToolAction) -> Result<String, Stri
                     "@ampcode/cli@latest".to_string(),
                 ],
             ),
-        ]),
-        ("amp", ToolAction::Uninstall) => run_command("npm", &["uninstall", "-g", "@ampcode/cli"]),
-        ("crush", ToolAction::Install) => {
-            run_command("npm", &["install", "-g", "@charmland/crush"])
-        }
-        ("crush", ToolAction::Update) => {
-            run_command("npm", &["install", "-g", "@charmland/crush@latest"])
-        }
-        ("crush", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@charmland/crush"])
-        }
+        ])),
+        ("amp", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@ampcode/cli"],
+        )]),
+        ("crush", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@charmland/crush"],
+        )]),
+        ("crush", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@charmland/crush@latest"],
+        )]),
+        ("crush", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@charmland/crush"],
+        )]),
         ("droid", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
+                Ok(vec![PlannedCommand::owned(
                     "powershell",
-                    &[
+                    vec![
                         "-NoProfile".to_string(),
                         "-ExecutionPolicy".to_string(),
                         "Bypass".to_string(),
                         "-Command".to_string(),
                         "irm https://app.factory.ai/cli/windows | iex".to_string(),
                     ],
-                )
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -fsSL https://app.factory.ai/cli | sh")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -fsSL https://app.factory.ai/cli | sh",
+                )])
             }
         }
-        ("droid", ToolAction::Update) => run_command("droid", &["update"]),
+        ("droid", ToolAction::Update) => Ok(vec![PlannedCommand::new("droid", &["update"])]),
         ("droid", ToolAction::Uninstall) => {
             Err("Factory Droid requires manual uninstallation".to_string())
         }
         ("vibe", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_command("uv", &["tool", "install", "mistral-vibe"])
+                Ok(vec![PlannedCommand::new(
+                    "uv",
+                    &["tool", "install", "mistral-vibe"],
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -LsSf https://mistral.ai/vibe/install.sh | bash")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -LsSf https://mistral.ai/vibe/install.sh | bash",
+                )])
             }
         }
-        ("vibe", ToolAction::Update) => run_command("uv", &["tool", "upgrade", "mistral-vibe"]),
-        ("vibe", ToolAction::Uninstall) => {
-            run_command("uv", &["tool", "uninstall", "mistral-vibe"])
-        }
+        ("vibe", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "uv",
+            &["tool", "upgrade", "mistral-vibe"],
+        )]),
+        ("vibe", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "uv",
+            &["tool", "uninstall", "mistral-vibe"],
+        )]),
         ("qoder", ToolAction::Install) => {
             #[cfg(target_os = "windows")]
             {
-                run_owned_command(
+                Ok(vec![PlannedCommand::owned(
                     "powershell",
-                    &[
+                    vec![
                         "-NoProfile".to_string(),
                         "-ExecutionPolicy".to_string(),
                         "Bypass".to_string(),
                         "-Command".to_string(),
                         "irm https://qoder.com/install.ps1 | iex".to_string(),
                     ],
-                )
+                )])
             }
             #[cfg(not(target_os = "windows"))]
             {
-                run_shell_command("curl -fsSL https://qoder.com/install | bash")
+                Ok(vec![PlannedCommand::shell(
+                    "curl -fsSL https://qoder.com/install | bash",
+                )])
             }
         }
-        ("qoder", ToolAction::Update) => run_command("qodercli", &["update"]),
+        ("qoder", ToolAction::Update) => Ok(vec![PlannedCommand::new("qodercli", &["update"])]),
         ("qoder", ToolAction::Uninstall) => {
             Err("Qoder CLI requires manual uninstallation".to_string())
         }
-        ("pi", ToolAction::Install) => {
-            run_command("npm", &["install", "-g", "@mariozechner/pi-coding-agent"])
-        }
-        ("pi", ToolAction::Update) => {
-            run_command("npm", &["update", "-g", "@mariozechner/pi-coding-agent"])
-        }
-        ("pi", ToolAction::Uninstall) => {
-            run_command("npm", &["uninstall", "-g", "@mariozechner/pi-coding-agent"])
-        }
-        ("amazonq", ToolAction::Update) => run_command("q", &["update"]),
+        ("pi", ToolAction::Install) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["install", "-g", "@mariozechner/pi-coding-agent"],
+        )]),
+        ("pi", ToolAction::Update) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["update", "-g", "@mariozechner/pi-coding-agent"],
+        )]),
+        ("pi", ToolAction::Uninstall) => Ok(vec![PlannedCommand::new(
+            "npm",
+            &["uninstall", "-g", "@mariozechner/pi-coding-agent"],
+        )]),
+        ("amazonq", ToolAction::Update) => Ok(vec![PlannedCommand::new("q", &["update"])]),
         _ => Err(format!("Unsupported action for tool: {}", tool_id)),
     }
 }
 
-fn run_command(program: &str, args: &[&str]) -> Result<String, String> {
-    match command_output_with_timeout(program, args, Duration::from_secs(300)) {
-        Ok(output) => format_command_result(program, &args.join(" "), output),
-        Err(error) => Err(format!("Failed to run command: {}", error)),
-    }
-}
-
-fn run_owned_command(program: &str, args: &[String]) -> Result<String, String> {
-    match command_output_with_timeout_vec(program, args, Duration::from_secs(300)) {
-        Ok(output) => format_command_result(program, &args.join(" "), output),
-        Err(error) => Err(format!("Failed to run command: {}", error)),
-    }
-}
-
-fn run_shell_command(script: &str) -> Result<String, String> {
-    run_owned_command("sh", &["-c".to_string(), script.to_string()])
-}
-
-fn run_first_success(attempts: &[(&str, Vec<String>)]) -> Result<String, String> {
-    let mut last_error = None;
-
-    for (program, args) in attempts {
-        match run_owned_command(program, args) {
-            Ok(result) => return Ok(result),
-            Err(error) => last_error = Some(error),
-        }
-    }
-
-    Err(last_error.unwrap_or_else(|| "No command attempts were provided".to_string()))
+fn first_success(attempts: &[(&str, Vec<String>)]) -> Vec<PlannedCommand> {
+    attempts
+        .iter()
+        .map(|(program, args)| PlannedCommand::owned(program, args.clone()))
+        .collect()
 }
 
-fn format_command_result(
-    program: &str,
-    joined_args: &str,
-    output: std::process::Output,
-) -> Result<String, String> {
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let combined = format!("{}{}", stdout, stderr).trim().to_string();
-
-    if output.status.success() {
-        if combined.is_empty() {
-            Ok(format!("Success: {} {}", program, joined_args))
-        } else {
-            Ok(format!("Success!\n{}", combined))
-        }
-    } else if combined.is_empty() {
-        Err(format!("Command failed: {} {}", program, joined_args))
-    } else {
-        Err(format!("Command failed:\n{}", combined))
-    }
+/// Run `action` for a tool, journaling every attempted command
+fn run_tool_action(tool_id: &str, action: ToolAction) -> Result<OperationResult, DevJanitorError> {
+    let commands = prepare_tool_action(tool_id, action)?;
+    run_first_success(
+        action.journal_action(),
+        tool_id,
+        None,
+        &commands,
+        TOOL_ACTION_TIMEOUT,
+    )
 }
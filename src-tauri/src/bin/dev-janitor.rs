@@ -0,0 +1,7 @@
+//! Headless Dev Janitor CLI, built without the Tauri desktop shell
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    dev_janitor_v2_lib::cli::run(std::env::args().skip(1))
+}
@@ -8,6 +8,16 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::disk_usage::{format_size, path_size, SizeIndex};
+use crate::error::DevJanitorError;
+use crate::journal;
+use crate::operation::OperationResult;
+use crate::plan::PlannedRemoval;
+use crate::quarantine::{removal_description, remove_cleanup_target};
+use crate::rules;
+use crate::scan_job::ScanReporter;
+use crate::settings::active_settings;
+
 /// Represents a cache entry that can be cleaned
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheInfo {
@@ -17,34 +27,9 @@ pub struct CacheInfo {
     pub size: u64,
     pub size_display: String,
     pub cache_type: String, // "package_manager" or "project"
-}
-
-/// Format bytes to human readable string
-pub fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
-}
-
-/// Calculate directory size recursively
-pub fn get_dir_size(path: &Path) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter_map(|e| e.metadata().ok())
-        .map(|metadata| metadata.len())
-        .sum()
+    /// Space the cache occupies on disk
+    #[serde(default)]
+    pub allocated_size: u64,
 }
 
 fn env_path(name: &str) -> Option<PathBuf> {
@@ -59,8 +44,8 @@ fn push_join(paths: &mut Vec<PathBuf>, base: &Option<PathBuf>, relative: &str) {
     }
 }
 
-/// Get package manager cache paths
-fn get_package_manager_caches() -> Vec<(&'static str, &'static str, Vec<PathBuf>)> {
+/// Built-in package manager cache paths
+fn builtin_package_manager_caches() -> Vec<(&'static str, &'static str, Vec<PathBuf>)> {
     let home = user_home_dir();
     let local_app_data = env_path("LOCALAPPDATA");
     let app_data = env_path("APPDATA");
@@ -164,22 +149,50 @@ fn get_package_manager_caches() -> Vec<(&'static str, &'static str, Vec<PathBuf>
     ]
 }
 
+/// Ids of the built-in package manager caches, which user rules can disable
+pub(crate) fn builtin_package_cache_ids() -> Vec<&'static str> {
+    builtin_package_manager_caches()
+        .into_iter()
+        .map(|(id, _, _)| id)
+        .collect()
+}
+
+/// Package manager cache paths, with user rules applied
+fn get_package_manager_caches() -> Vec<(String, String, Vec<PathBuf>)> {
+    let rules = rules::active_rules();
+    let overlay = &rules.package_caches;
+
+    builtin_package_manager_caches()
+        .into_iter()
+        .filter(|(id, _, _)| !overlay.is_disabled(id))
+        .map(|(id, name, paths)| (id.to_string(), name.to_string(), paths))
+        .chain(
+            overlay
+                .added
+                .iter()
+                .map(|rule| (rule.id.clone(), rule.name.clone(), rule.paths.clone())),
+        )
+        .collect()
+}
+
 fn user_home_dir() -> Option<PathBuf> {
     env_path("HOME").or_else(|| env_path("USERPROFILE"))
 }
 
-fn canonicalize_existing_path(path: &Path) -> Result<PathBuf, String> {
-    let metadata = fs::symlink_metadata(path)
-        .map_err(|error| format!("Failed to inspect {}: {}", path.display(), error))?;
+fn canonicalize_existing_path(path: &Path) -> Result<PathBuf, DevJanitorError> {
+    let metadata = fs::symlink_metadata(path).map_err(|error| {
+        DevJanitorError::io(format!("Failed to inspect {}", path.display()), error)
+    })?;
     if metadata.file_type().is_symlink() {
-        return Err(format!(
+        return Err(DevJanitorError::UnsafePath(format!(
             "Refusing to clean symlink path: {}",
             path.display()
-        ));
+        )));
     }
 
-    path.canonicalize()
-        .map_err(|error| format!("Failed to resolve {}: {}", path.display(), error))
+    path.canonicalize().map_err(|error| {
+        DevJanitorError::io(format!("Failed to resolve {}", path.display()), error)
+    })
 }
 
 fn is_root_or_home_path(path: &Path) -> bool {
@@ -213,12 +226,8 @@ fn is_known_project_cache(path: &Path) -> bool {
         && path
             .file_name()
             .and_then(|name| name.to_str())
-            .map(|name| {
-                PROJECT_CACHE_PATTERNS
-                    .iter()
-                    .any(|(pattern, _)| name == *pattern)
-            })
-            .unwrap_or(false)
+            .and_then(match_project_cache)
+            .is_some()
         && has_dev_project_ancestor(path)
 }
 
@@ -256,52 +265,62 @@ fn has_dev_project_ancestor(path: &Path) -> bool {
     false
 }
 
-fn validate_cache_cleanup_target(path: &Path) -> Result<PathBuf, String> {
+fn validate_cache_cleanup_target(path: &Path) -> Result<PathBuf, DevJanitorError> {
     let canonical = canonicalize_existing_path(path)?;
 
     if is_root_or_home_path(&canonical) {
-        return Err(format!(
+        return Err(DevJanitorError::UnsafePath(format!(
             "Refusing to clean unsafe path: {}",
             canonical.display()
-        ));
+        )));
     }
 
     if is_known_package_manager_cache(&canonical) || is_known_project_cache(&canonical) {
         Ok(canonical)
     } else {
-        Err(format!(
+        Err(DevJanitorError::NotATarget(format!(
             "Path is not a recognized cache target: {}",
             canonical.display()
-        ))
+        )))
     }
 }
 
 /// Scan all package manager caches
 pub fn scan_package_manager_caches() -> Vec<CacheInfo> {
     let caches_config = get_package_manager_caches();
+    let exclusions = active_settings().exclusions();
+    let index = SizeIndex::open();
 
-    caches_config
+    let caches = caches_config
         .par_iter()
         .filter_map(|(id, name, paths)| {
             // Find first existing path
             for path in paths {
-                if path.exists() {
-                    let size = get_dir_size(path);
-                    if size > 0 {
+                if path.exists() && !exclusions.is_excluded(path) {
+                    let usage = index.disk_usage(path);
+                    if usage.apparent > 0 {
                         return Some(CacheInfo {
                             id: id.to_string(),
                             name: name.to_string(),
                             path: path.to_string_lossy().to_string(),
-                            size,
-                            size_display: format_size(size),
+                            size: usage.apparent,
+                            size_display: format_size(usage.apparent),
                             cache_type: "package_manager".to_string(),
+                            allocated_size: usage.allocated,
                         });
                     }
                 }
             }
             None
         })
-        .collect()
+        .collect();
+    save_size_index(index);
+    caches
+}
+
+/// The index only speeds up the next scan, so a failed write leaves this scan's results intact
+fn save_size_index(index: SizeIndex) {
+    let _ = index.save();
 }
 
 /// Project cache patterns to look for
@@ -325,32 +344,79 @@ const PROJECT_CACHE_PATTERNS: &[(&str, &str)] = &[
     (".cache", "Generic Build Cache"),
 ];
 
+/// Built-in project cache directory names, which user rules can disable
+pub(crate) fn builtin_project_cache_patterns() -> Vec<&'static str> {
+    PROJECT_CACHE_PATTERNS
+        .iter()
+        .map(|(pattern, _)| *pattern)
+        .collect()
+}
+
+/// Project cache pattern and display name matching a directory name, with user rules applied
+fn match_project_cache(dir_name: &str) -> Option<(String, String)> {
+    let rules = rules::active_rules();
+    let overlay = &rules.project_caches;
+
+    PROJECT_CACHE_PATTERNS
+        .iter()
+        .find(|(pattern, _)| dir_name == *pattern && !overlay.is_disabled(pattern))
+        .map(|(pattern, name)| (pattern.to_string(), name.to_string()))
+        .or_else(|| {
+            overlay
+                .added
+                .iter()
+                .find(|rule| rule.pattern.matches_name(dir_name))
+                .map(|rule| (rule.pattern.as_str().to_string(), rule.label.clone()))
+        })
+}
+
 /// Scan a directory for project caches
 pub fn scan_project_caches(root_path: &str, max_depth: usize) -> Vec<CacheInfo> {
+    scan_project_caches_with_progress(root_path, max_depth, &ScanReporter::silent())
+}
+
+/// `scan_project_caches`, reporting progress and stopping early when cancelled
+pub fn scan_project_caches_with_progress(
+    root_path: &str,
+    max_depth: usize,
+    progress: &ScanReporter,
+) -> Vec<CacheInfo> {
     let root = PathBuf::from(root_path);
     if !root.exists() {
         return Vec::new();
     }
 
     let mut caches = Vec::new();
+    let settings = active_settings();
+    let exclusions = settings.exclusions();
+    let index = SizeIndex::open();
 
     let mut entries = WalkDir::new(&root).max_depth(max_depth).into_iter();
 
     while let Some(entry_result) = entries.next() {
+        if progress.is_cancelled() {
+            break;
+        }
         let Ok(entry) = entry_result else {
             continue;
         };
 
         if entry.file_type().is_dir() {
+            if exclusions.is_excluded(entry.path()) {
+                entries.skip_current_dir();
+                continue;
+            }
+            progress.visit_dir(entry.path());
             let dir_name = entry.file_name().to_string_lossy();
 
-            for (pattern, name) in PROJECT_CACHE_PATTERNS {
-                if dir_name == *pattern && has_dev_project_ancestor(entry.path()) {
+            if let Some((pattern, name)) = match_project_cache(&dir_name) {
+                if has_dev_project_ancestor(entry.path()) {
                     let path = entry.path().to_path_buf();
-                    let size = get_dir_size(&path);
+                    let usage = index.disk_usage(&path);
+                    let size = usage.apparent;
 
-                    if size > 1024 * 1024 {
-                        // Only include if > 1MB
+                    if size > settings.min_project_cache_size {
+                        progress.found(size);
                         caches.push(CacheInfo {
                             id: format!("{}_{}", pattern, caches.len()),
                             name: name.to_string(),
@@ -358,80 +424,64 @@ pub fn scan_project_caches(root_path: &str, max_depth: usize) -> Vec<CacheInfo>
                             size,
                             size_display: format_size(size),
                             cache_type: "project".to_string(),
+                            allocated_size: usage.allocated,
                         });
                     }
 
                     entries.skip_current_dir();
-                    break;
                 }
             }
         }
     }
+    save_size_index(index);
 
     // Sort by size descending
     caches.sort_by_key(|cache| Reverse(cache.size));
     caches
 }
 
-/// Clean a cache directory
-pub fn clean_cache(path: &str) -> Result<String, String> {
+fn resolve_cache_cleanup_target(path: &str) -> Result<PathBuf, DevJanitorError> {
     let cache_path = PathBuf::from(path);
 
     if !cache_path.exists() {
-        return Err(format!("Path does not exist: {}", path));
+        return Err(DevJanitorError::NotFound(format!(
+            "Path does not exist: {}",
+            path
+        )));
     }
 
-    let cache_path = validate_cache_cleanup_target(&cache_path)?;
+    validate_cache_cleanup_target(&cache_path)
+}
 
-    // Get size before deletion
-    let size_before = get_dir_size(&cache_path);
+/// Report what `clean_cache` would remove without touching it
+pub fn plan_clean_cache(path: &str) -> Result<PlannedRemoval, DevJanitorError> {
+    let cache_path = resolve_cache_cleanup_target(path)?;
+    Ok(PlannedRemoval::new(&cache_path, path_size(&cache_path)))
+}
 
-    // Try to remove the directory
-    match fs::remove_dir_all(&cache_path) {
-        Ok(_) => Ok(format!(
-            "Successfully cleaned {} (freed {})",
-            path,
-            format_size(size_before)
-        )),
-        Err(e) => {
-            // Try with more aggressive approach on Windows
-            #[cfg(target_os = "windows")]
-            {
-                // Try to remove readonly attributes first
-                if remove_readonly_and_delete(&cache_path).is_err() {
-                    return Err(format!("Failed to clean {}: {}", path, e));
-                }
-                Ok(format!(
-                    "Successfully cleaned {} (freed {})",
-                    path,
-                    format_size(size_before)
-                ))
-            }
+/// Clean a cache directory by moving it into quarantine (or deleting it, per settings)
+pub fn clean_cache(path: &str) -> Result<OperationResult, DevJanitorError> {
+    let cache_path = resolve_cache_cleanup_target(path)?;
 
-            #[cfg(not(target_os = "windows"))]
-            Err(format!("Failed to clean {}: {}", path, e))
-        }
-    }
-}
+    // Get size before the move so the quarantine entry can report it
+    let size_before = path_size(&cache_path);
 
-#[cfg(target_os = "windows")]
-#[allow(clippy::permissions_set_readonly_false)]
-fn remove_readonly_and_delete(path: &PathBuf) -> std::io::Result<()> {
-    use std::os::windows::fs::MetadataExt;
-
-    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-        let entry_path = entry.path();
-        if let Ok(metadata) = fs::metadata(entry_path) {
-            // Check if file is readonly (FILE_ATTRIBUTE_READONLY = 1)
-            if metadata.file_attributes() & 1 != 0 {
-                let mut perms = metadata.permissions();
-                perms.set_readonly(false);
-                let _ = fs::set_permissions(entry_path, perms);
-            }
-        }
-    }
+    let result = remove_cleanup_target(&cache_path, "cache", size_before);
+    let journaled = journal::record_cleanup("clean_cache", &cache_path, size_before, &result);
+    let entry = result?;
 
-    fs::remove_dir_all(path)
+    Ok(OperationResult::cleaned(
+        path,
+        size_before,
+        entry.is_some(),
+        format!(
+            "Successfully cleaned {} ({}, {})",
+            path,
+            format_size(size_before),
+            removal_description(&entry)
+        ),
+    )
+    .journaled(journaled))
 }
 
 #[cfg(test)]
@@ -489,9 +539,9 @@ mod tests {
 
         let result = clean_cache(cache.to_str().unwrap());
 
-        assert!(result
-            .expect_err("orphan project cache name should not be enough to delete")
-            .contains("not a recognized cache target"));
+        let error = result.expect_err("orphan project cache name should not be enough to delete");
+        assert_eq!(error.code(), "not_a_target");
+        assert!(error.to_string().contains("not a recognized cache target"));
         assert!(cache.exists());
 
         fs::remove_dir_all(root).unwrap();
@@ -511,7 +561,11 @@ mod tests {
 
         let result = clean_cache(cache.to_str().unwrap()).expect("project cache should clean");
 
-        assert!(result.contains("Successfully cleaned"));
+        assert!(result.message.contains("Successfully cleaned"));
+        assert_eq!(result.items_removed, 1);
+        // Quarantined by default, so nothing is freed until the quarantine is purged
+        assert_eq!(result.bytes_freed, 0);
+        assert!(result.bytes_quarantined > 1024 * 1024);
         assert!(!cache.exists());
 
         fs::remove_dir_all(project).unwrap();
@@ -10,6 +10,16 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::disk_usage::{format_size, path_size};
+use crate::error::DevJanitorError;
+use crate::journal;
+use crate::operation::OperationResult;
+use crate::plan::PlannedRemoval;
+use crate::quarantine::{removal_description, remove_cleanup_target};
+use crate::rules;
+use crate::scan_job::ScanReporter;
+use crate::settings::active_settings;
+
 const SKIPPED_SCAN_DIRECTORIES: &[&str] = &[
     "node_modules",
     ".git",
@@ -230,41 +240,18 @@ fn get_chat_history_patterns() -> Vec<ChatHistoryPattern> {
     ]
 }
 
-/// Format bytes to human readable string
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
-}
-
-/// Get directory or file size
-fn get_size(path: &Path) -> u64 {
-    if path.is_file() {
-        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
-    } else if path.is_dir() {
-        WalkDir::new(path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.path().is_file())
-            .map(|e| e.path().metadata().map(|m| m.len()).unwrap_or(0))
-            .sum()
-    } else {
-        0
-    }
+/// Built-in chat history patterns of every tool, which user rules can disable
+pub(crate) fn builtin_chat_history_patterns() -> Vec<&'static str> {
+    get_chat_history_patterns()
+        .into_iter()
+        .flat_map(|group| group.patterns)
+        .collect()
 }
 
-/// Check if a path matches any AI tool chat history pattern
-fn check_chat_history_pattern(path: &Path) -> Option<(&'static str, &'static str, &'static str)> {
+/// Check if a path matches any AI tool chat history pattern, with user rules applied
+fn check_chat_history_pattern(path: &Path) -> Option<(String, String, String)> {
+    let rules = rules::active_rules();
+    let overlay = &rules.chat_history;
     let file_name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
     let path_str = path
         .to_string_lossy()
@@ -273,11 +260,18 @@ fn check_chat_history_pattern(path: &Path) -> Option<(&'static str, &'static str
 
     for pattern_group in get_chat_history_patterns() {
         for pattern in &pattern_group.patterns {
+            if overlay.is_disabled(pattern) {
+                continue;
+            }
             let pattern_lower = pattern.to_ascii_lowercase();
 
             // Check exact file name match
             if !pattern.contains('/') && file_name == pattern_lower {
-                return Some((pattern_group.tool, *pattern, pattern_group.file_type));
+                return Some((
+                    pattern_group.tool.to_string(),
+                    pattern.to_string(),
+                    pattern_group.file_type.to_string(),
+                ));
             }
 
             // Check path-based patterns against both the directory/file itself
@@ -287,12 +281,26 @@ fn check_chat_history_pattern(path: &Path) -> Option<(&'static str, &'static str
                     || path_str.ends_with(&format!("/{pattern_lower}"))
                     || path_str.contains(&format!("/{pattern_lower}/")))
             {
-                return Some((pattern_group.tool, *pattern, pattern_group.file_type));
+                return Some((
+                    pattern_group.tool.to_string(),
+                    pattern.to_string(),
+                    pattern_group.file_type.to_string(),
+                ));
             }
         }
     }
 
-    None
+    overlay
+        .added
+        .iter()
+        .find(|rule| rule.pattern.matches_path(path))
+        .map(|rule| {
+            (
+                rule.tool.clone(),
+                rule.pattern.as_str().to_string(),
+                rule.file_type.clone(),
+            )
+        })
 }
 
 /// Detect if a directory is a development project
@@ -338,18 +346,20 @@ fn is_root_path(path: &Path) -> bool {
     path.parent().is_none()
 }
 
-fn canonicalize_existing_path(path: &Path) -> Result<PathBuf, String> {
-    let metadata = fs::symlink_metadata(path)
-        .map_err(|error| format!("Failed to inspect {}: {}", path.display(), error))?;
+fn canonicalize_existing_path(path: &Path) -> Result<PathBuf, DevJanitorError> {
+    let metadata = fs::symlink_metadata(path).map_err(|error| {
+        DevJanitorError::io(format!("Failed to inspect {}", path.display()), error)
+    })?;
     if metadata.file_type().is_symlink() {
-        return Err(format!(
+        return Err(DevJanitorError::UnsafePath(format!(
             "Refusing to delete symlink path: {}",
             path.display()
-        ));
+        )));
     }
 
-    path.canonicalize()
-        .map_err(|error| format!("Failed to resolve {}: {}", path.display(), error))
+    path.canonicalize().map_err(|error| {
+        DevJanitorError::io(format!("Failed to resolve {}", path.display()), error)
+    })
 }
 
 fn is_root_or_home_path(path: &Path) -> bool {
@@ -363,14 +373,14 @@ fn is_root_or_home_path(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn validate_chat_history_delete_target(path: &Path) -> Result<PathBuf, String> {
+fn validate_chat_history_delete_target(path: &Path) -> Result<PathBuf, DevJanitorError> {
     let canonical = canonicalize_existing_path(path)?;
 
     if is_root_or_home_path(&canonical) {
-        return Err(format!(
+        return Err(DevJanitorError::UnsafePath(format!(
             "Refusing to delete unsafe path: {}",
             canonical.display()
-        ));
+        )));
     }
 
     if check_chat_history_pattern(&canonical).is_some()
@@ -378,29 +388,46 @@ fn validate_chat_history_delete_target(path: &Path) -> Result<PathBuf, String> {
     {
         Ok(canonical)
     } else {
-        Err(format!(
+        Err(DevJanitorError::NotATarget(format!(
             "Path is not a recognized chat history target: {}",
             canonical.display()
-        ))
+        )))
     }
 }
 
 /// Scan a directory for projects with AI chat history
 pub fn scan_chat_history(root_path: &str, max_depth: usize) -> Vec<ProjectChatHistory> {
+    scan_chat_history_with_progress(root_path, max_depth, &ScanReporter::silent())
+}
+
+/// `scan_chat_history`, reporting progress and stopping early when cancelled
+pub fn scan_chat_history_with_progress(
+    root_path: &str,
+    max_depth: usize,
+    progress: &ScanReporter,
+) -> Vec<ProjectChatHistory> {
     let root = PathBuf::from(root_path);
     if !root.exists() || !root.is_dir() {
         return Vec::new();
     }
 
+    let settings = active_settings();
+    let exclusions = settings.exclusions();
+
     // First, find all development projects
     let projects: Vec<PathBuf> = WalkDir::new(&root)
         .max_depth(max_depth)
         .into_iter()
         .filter_entry(|entry| {
-            entry.depth() == 0 || !entry.file_type().is_dir() || !is_skipped_scan_dir(entry.path())
+            !exclusions.is_excluded(entry.path())
+                && (entry.depth() == 0
+                    || !entry.file_type().is_dir()
+                    || !is_skipped_scan_dir(entry.path()))
         })
+        .take_while(|_| !progress.is_cancelled())
         .filter_map(|e| e.ok())
         .filter(|e| e.path().is_dir())
+        .inspect(|e| progress.visit_dir(e.path()))
         .filter(|e| is_dev_project(e.path()))
         .map(|e| e.path().to_path_buf())
         .collect();
@@ -416,6 +443,9 @@ pub fn scan_chat_history(root_path: &str, max_depth: usize) -> Vec<ProjectChatHi
             // nested home-style paths such as .local/share/goose/sessions.
             let mut entries = WalkDir::new(project_path).max_depth(4).into_iter();
             while let Some(entry_result) = entries.next() {
+                if progress.is_cancelled() {
+                    break;
+                }
                 let Ok(entry) = entry_result else {
                     continue;
                 };
@@ -425,10 +455,26 @@ pub fn scan_chat_history(root_path: &str, max_depth: usize) -> Vec<ProjectChatHi
                     entries.skip_current_dir();
                     continue;
                 }
+                if exclusions.is_excluded(path) {
+                    if entry.file_type().is_dir() {
+                        entries.skip_current_dir();
+                    }
+                    continue;
+                }
+                if entry.depth() != 0 && entry.file_type().is_dir() {
+                    progress.visit_dir(path);
+                }
 
                 if let Some((tool, _pattern, file_type)) = check_chat_history_pattern(path) {
-                    let size = get_size(path);
+                    let size = path_size(path);
+                    if size < settings.min_chat_history_size {
+                        if entry.file_type().is_dir() {
+                            entries.skip_current_dir();
+                        }
+                        continue;
+                    }
                     let is_dir = path.is_dir();
+                    progress.found(size);
 
                     ai_tools.insert(tool.to_string(), true);
 
@@ -489,73 +535,51 @@ pub fn scan_chat_history(root_path: &str, max_depth: usize) -> Vec<ProjectChatHi
     sorted_results
 }
 
-/// Delete a chat history file or directory
-pub fn delete_chat_file(path: &str) -> Result<String, String> {
+fn resolve_chat_history_delete_target(path: &str) -> Result<PathBuf, DevJanitorError> {
     let path_buf = PathBuf::from(path);
 
     if !path_buf.exists() {
-        return Err(format!("Path does not exist: {}", path));
+        return Err(DevJanitorError::NotFound(format!(
+            "Path does not exist: {}",
+            path
+        )));
     }
 
-    let path_buf = validate_chat_history_delete_target(&path_buf)?;
-
-    let size = get_size(&path_buf);
-    let size_display = format_size(size);
-
-    let result = if path_buf.is_dir() {
-        fs::remove_dir_all(&path_buf)
-    } else {
-        fs::remove_file(&path_buf)
-    };
-
-    match result {
-        Ok(()) => Ok(format!("Deleted {} ({})", path, size_display)),
-        Err(e) => {
-            // Try with permission fix on Windows
-            #[cfg(target_os = "windows")]
-            {
-                if fix_permissions_and_delete(&path_buf).is_err() {
-                    return Err(format!("Failed to delete {}: {}", path, e));
-                }
-                Ok(format!("Deleted {} ({})", path, size_display))
-            }
-
-            #[cfg(not(target_os = "windows"))]
-            {
-                if chmod_and_delete(&path_buf).is_ok() {
-                    return Ok(format!("Deleted {} ({})", path, size_display));
-                }
-                Err(format!("Failed to delete {}: {}", path, e))
-            }
-        }
-    }
+    validate_chat_history_delete_target(&path_buf)
 }
 
-#[cfg(unix)]
-fn chmod_and_delete(path: &PathBuf) -> std::io::Result<()> {
-    use std::os::unix::fs::PermissionsExt;
+/// Delete a chat history file or directory by moving it into quarantine (or deleting it, per settings)
+pub fn delete_chat_file(path: &str) -> Result<OperationResult, DevJanitorError> {
+    let path_buf = resolve_chat_history_delete_target(path)?;
 
-    if let Ok(metadata) = fs::metadata(path) {
-        let mut perms = metadata.permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(path, perms)?;
-    }
+    let size = path_size(&path_buf);
+    let size_display = format_size(size);
 
-    if path.is_dir() {
-        fs::remove_dir_all(path)
-    } else {
-        fs::remove_file(path)
-    }
+    let result = remove_cleanup_target(&path_buf, "chat_history", size);
+    let journaled = journal::record_cleanup("delete_chat_history", &path_buf, size, &result);
+    let entry = result?;
+
+    Ok(OperationResult::cleaned(
+        path,
+        size,
+        entry.is_some(),
+        format!(
+            "Deleted {} ({}, {})",
+            path,
+            size_display,
+            removal_description(&entry)
+        ),
+    )
+    .journaled(journaled))
 }
 
-/// Delete all chat history for a project
-pub fn delete_project_chat_history(project_path: &str) -> Result<(u32, u32, String), String> {
+fn find_project_chat_history(project_path: &str) -> Result<ProjectChatHistory, DevJanitorError> {
     let canonical = canonicalize_existing_path(Path::new(project_path))?;
     if is_root_or_home_path(&canonical) {
-        return Err(format!(
+        return Err(DevJanitorError::UnsafePath(format!(
             "Refusing to scan unsafe project path: {}",
             canonical.display()
-        ));
+        )));
     }
     let canonical_str = canonical.to_string_lossy().to_string();
 
@@ -566,59 +590,69 @@ pub fn delete_project_chat_history(project_path: &str) -> Result<(u32, u32, Stri
         .find(|p| p.project_path == canonical_str)
         .or_else(|| projects.first());
 
-    let project = match project {
-        Some(p) => p,
-        None => return Err("No chat history found in this project".to_string()),
-    };
+    match project {
+        Some(p) => Ok(p.clone()),
+        None => Err(DevJanitorError::NotFound(
+            "No chat history found in this project".to_string(),
+        )),
+    }
+}
+
+/// A chat file path and either what would be removed or why it is refused
+pub type PlannedChatRemoval = (String, Result<PlannedRemoval, DevJanitorError>);
+
+/// Report what `delete_project_chat_history` would remove, per chat file
+pub fn plan_delete_project_chat_history(
+    project_path: &str,
+) -> Result<Vec<PlannedChatRemoval>, DevJanitorError> {
+    let project = find_project_chat_history(project_path)?;
+
+    Ok(project
+        .chat_files
+        .into_iter()
+        .map(|file| {
+            let removal = resolve_chat_history_delete_target(&file.path)
+                .map(|path| PlannedRemoval::new(&path, path_size(&path)));
+            (file.path, removal)
+        })
+        .collect())
+}
+
+/// Delete all chat history for a project
+///
+/// Files that could not be deleted are listed in the result's warnings.
+pub fn delete_project_chat_history(project_path: &str) -> Result<OperationResult, DevJanitorError> {
+    let project = find_project_chat_history(project_path)?;
 
     let mut success_count = 0u32;
-    let mut fail_count = 0u32;
     let mut total_freed = 0u64;
+    let mut total_quarantined = 0u64;
+    let mut warnings = Vec::new();
 
     for file in &project.chat_files {
         match delete_chat_file(&file.path) {
-            Ok(_) => {
+            Ok(result) => {
                 success_count += 1;
-                total_freed += file.size;
-            }
-            Err(_) => {
-                fail_count += 1;
+                total_freed += result.bytes_freed;
+                total_quarantined += result.bytes_quarantined;
             }
+            Err(error) => warnings.push(error.to_string()),
         }
     }
 
-    Ok((success_count, fail_count, format_size(total_freed)))
-}
-
-/// Fix permissions and delete (Windows only)
-#[cfg(target_os = "windows")]
-#[allow(clippy::permissions_set_readonly_false)]
-fn fix_permissions_and_delete(path: &PathBuf) -> std::io::Result<()> {
-    use std::os::windows::fs::MetadataExt;
-
-    if path.is_dir() {
-        // Recursively fix permissions
-        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-            let p = entry.path();
-            if let Ok(meta) = fs::metadata(p) {
-                let attrs = meta.file_attributes();
-                // Clear Windows FILE_ATTRIBUTE_READONLY before deleting.
-                if attrs & 0x1 != 0 {
-                    let mut perms = meta.permissions();
-                    perms.set_readonly(false);
-                    let _ = fs::set_permissions(p, perms);
-                }
-            }
-        }
-        fs::remove_dir_all(path)
-    } else {
-        if let Ok(meta) = fs::metadata(path) {
-            let mut perms = meta.permissions();
-            perms.set_readonly(false);
-            let _ = fs::set_permissions(path, perms);
-        }
-        fs::remove_file(path)
-    }
+    let message = format!(
+        "Deleted {} chat history items ({} freed, {} quarantined, {} failed)",
+        success_count,
+        format_size(total_freed),
+        format_size(total_quarantined),
+        warnings.len()
+    );
+    Ok(OperationResult {
+        warnings,
+        bytes_quarantined: total_quarantined,
+        bytes_quarantined_display: format_size(total_quarantined),
+        ..OperationResult::removed(project_path, success_count, total_freed, message)
+    })
 }
 
 /// Scan global AI chat history locations (home directory)
@@ -633,11 +667,12 @@ pub fn scan_global_chat_history() -> Vec<ChatHistoryFile> {
 
     let home_path = PathBuf::from(&home);
     let mut global_files: Vec<ChatHistoryFile> = Vec::new();
+    let exclusions = active_settings().exclusions();
 
     for (dir_name, tool) in GLOBAL_CHAT_HISTORY_PATTERNS {
         let dir_path = home_path.join(dir_name);
-        if dir_path.exists() {
-            let size = get_size(&dir_path);
+        if dir_path.exists() && !exclusions.is_excluded(&dir_path) {
+            let size = path_size(&dir_path);
             let id = format!("{:x}", md5::compute(dir_path.to_string_lossy().as_bytes()));
 
             global_files.push(ChatHistoryFile {
@@ -0,0 +1,1006 @@
+//! Headless command-line interface for Dev Janitor
+//! Wraps the core scanners so they can run over SSH and in scripts
+
+mod table;
+
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use crate::ai_cleanup::scan_ai_junk;
+use crate::cache::{scan_package_manager_caches, scan_project_caches};
+use crate::chat_history::scan_chat_history;
+use crate::config::diagnose_environment;
+use crate::detection::{scan_all_tools, ToolInfo, ToolVersion};
+use crate::disk_usage::format_size;
+use crate::journal::{query_journal, JournalQuery};
+use crate::package_manager::scan_all_packages;
+use crate::report::{collect_inventory, render_report, ReportFormat};
+use crate::retention::{preview_retention, run_retention};
+use crate::rules::load_user_rules;
+use crate::runtimes::pins::version_pin_report;
+use crate::runtimes::scan_runtime_versions;
+use crate::security_scan::scan_ai_tool_security;
+use crate::settings::{active_settings, load_settings, scan_each_root};
+use crate::snapshot::{diff_snapshots, list_snapshots, take_snapshot, InventoryDiff};
+use crate::utils::runner::{set_active_runner, RecordingRunner, ReplayRunner, SystemRunner};
+
+use table::Table;
+
+const USAGE: &str = "\
+Usage: dev-janitor <COMMAND> [OPTIONS]
+
+Commands:
+  tools                      Detect installed development tools
+  packages                   List global packages from every package manager
+  caches                     Scan package manager caches
+  project-caches [PATH]      Scan PATH for project build caches
+  runtimes                   List runtime versions kept by nvm, pyenv, rustup and other version managers
+  pins [PATH]                Reconcile versions pinned by projects in PATH with installed runtimes
+  ai-junk [PATH]             Scan PATH for AI assistant junk files
+  chat-history [PATH]        Scan PATH for projects with AI chat history
+  diagnose                   Diagnose PATH and shell configuration
+  security                   Scan AI tools for exposed ports and risky configs
+  journal                    Show recorded cleanups, uninstalls and kills
+  rules                      Validate the user rules file and summarize it
+  retention                  Show what the retention policies would clean
+  report                     Write a full inventory report of this machine
+  snapshot                   Save the current inventory as a timestamped snapshot
+  snapshots                  List saved snapshots, newest first
+  diff <FROM> [TO]           Compare two snapshots (ids or report files); TO defaults to now
+
+Options:
+  --json                     Print machine-readable JSON instead of a table
+  --depth <N>                Maximum directory depth for path scans (default from settings)
+  --since <DATE>             journal: entries at or after DATE (YYYY-MM-DD or RFC 3339)
+  --until <DATE>             journal: entries before DATE
+  --action <NAME>            journal: only this action, e.g. clean_cache
+  --limit <N>                journal: at most N entries, newest first
+  --apply                    retention: clean the selected items and record the run
+  --format <FORMAT>          report: markdown (default), html or json
+  --record <FILE>            Append every external command and its output to FILE (JSON Lines)
+  --replay <FILE>            Answer external commands from a recorded FILE instead of running them
+  -h, --help                 Print this help
+  -V, --version              Print the version
+
+PATH defaults to the configured scan roots, or the current directory if there are none.
+";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Tools,
+    Packages,
+    Caches,
+    ProjectCaches {
+        path: Option<String>,
+        depth: Option<usize>,
+    },
+    Runtimes,
+    Pins {
+        path: Option<String>,
+        depth: Option<usize>,
+    },
+    AiJunk {
+        path: Option<String>,
+        depth: Option<usize>,
+    },
+    ChatHistory {
+        path: Option<String>,
+        depth: Option<usize>,
+    },
+    Diagnose,
+    Security,
+    Journal(JournalQuery),
+    Rules,
+    Retention {
+        apply: bool,
+    },
+    Report(ReportFormat),
+    Snapshot,
+    Snapshots,
+    Diff {
+        from: String,
+        to: Option<String>,
+    },
+    Help,
+    Version,
+}
+
+/// Where external commands go instead of straight to the system
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RunnerChoice {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Invocation {
+    command: Command,
+    json: bool,
+    runner: Option<RunnerChoice>,
+}
+
+/// Parse the arguments (without the program name) and run the selected command
+pub fn run<I>(args: I) -> ExitCode
+where
+    I: IntoIterator<Item = String>,
+{
+    let invocation = match parse_args(args) {
+        Ok(invocation) => invocation,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let recorder = match install_runner(invocation.runner.as_ref()) {
+        Ok(recorder) => recorder,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for warning in startup_warnings(&invocation.command) {
+        eprintln!("warning: {}", warning);
+    }
+    let result = execute(invocation);
+    for error in recorder.iter().flat_map(|recorder| recorder.write_errors()) {
+        eprintln!("warning: {}", error);
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // A closed pipe (e.g. `dev-janitor tools | head`) is not a failure
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Problems with the user's files that make scans fall back to defaults
+fn startup_warnings(command: &Command) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if let Err(error) = load_settings() {
+        warnings.push(format!("using default settings: {}", error));
+    }
+    // `rules` reports a broken rules file as its own error
+    if *command != Command::Rules {
+        if let Err(error) = load_user_rules() {
+            warnings.push(format!("ignoring user rules: {}", error));
+        }
+    }
+    warnings
+}
+
+/// Route external commands through the runner chosen with `--record` or `--replay`
+fn install_runner(choice: Option<&RunnerChoice>) -> io::Result<Option<Arc<RecordingRunner>>> {
+    match choice {
+        None => Ok(None),
+        Some(RunnerChoice::Replay(path)) => {
+            set_active_runner(Arc::new(ReplayRunner::from_file(path)?));
+            Ok(None)
+        }
+        Some(RunnerChoice::Record(path)) => {
+            let recorder = Arc::new(RecordingRunner::to_file(Arc::new(SystemRunner), path)?);
+            set_active_runner(recorder.clone());
+            Ok(Some(recorder))
+        }
+    }
+}
+
+fn parse_args<I>(args: I) -> Result<Invocation, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut json = false;
+    let mut apply = false;
+    let mut depth = None;
+    let mut format = None;
+    let mut runner = None;
+    let mut journal = JournalQuery::default();
+    let mut journal_filtered = false;
+    let mut positionals = Vec::new();
+    let mut help = false;
+    let mut version = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--apply" => apply = true,
+            "-h" | "--help" => help = true,
+            "-V" | "--version" => version = true,
+            "--depth" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--depth requires a value".to_string())?;
+                depth = Some(parse_depth(&value)?);
+            }
+            other if other.starts_with("--depth=") => {
+                depth = Some(parse_depth(&other["--depth=".len()..])?);
+            }
+            "--format" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--format requires a value".to_string())?;
+                format = Some(parse_format(&value)?);
+            }
+            other if other.starts_with("--format=") => {
+                format = Some(parse_format(&other["--format=".len()..])?);
+            }
+            "--record" | "--replay" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{} requires a file", arg))?;
+                if runner.is_some() {
+                    return Err("--record and --replay cannot be combined".to_string());
+                }
+                runner = Some(if arg == "--record" {
+                    RunnerChoice::Record(PathBuf::from(value))
+                } else {
+                    RunnerChoice::Replay(PathBuf::from(value))
+                });
+            }
+            "--since" | "--until" | "--action" | "--limit" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{} requires a value", arg))?;
+                match arg.as_str() {
+                    "--since" => journal.since = Some(value),
+                    "--until" => journal.until = Some(value),
+                    "--action" => journal.action = Some(value),
+                    _ => {
+                        journal.limit = Some(
+                            value
+                                .parse()
+                                .map_err(|_| format!("invalid --limit value: {}", value))?,
+                        )
+                    }
+                }
+                journal_filtered = true;
+            }
+            other if other.starts_with('-') && other.len() > 1 => {
+                return Err(format!("unknown option: {}", other));
+            }
+            _ => positionals.push(arg),
+        }
+    }
+
+    if help {
+        return Ok(Invocation {
+            command: Command::Help,
+            json,
+            runner,
+        });
+    }
+    if version {
+        return Ok(Invocation {
+            command: Command::Version,
+            json,
+            runner,
+        });
+    }
+
+    let mut positionals = positionals.into_iter();
+    let name = positionals
+        .next()
+        .ok_or_else(|| "no command given".to_string())?;
+    let path = positionals.next();
+    let to = if name == "diff" {
+        positionals.next()
+    } else {
+        None
+    };
+    if let Some(extra) = positionals.next() {
+        return Err(format!("unexpected argument: {}", extra));
+    }
+
+    let takes_path = matches!(
+        name.as_str(),
+        "project-caches" | "pins" | "ai-junk" | "chat-history"
+    );
+    if !takes_path {
+        if let Some(path) = path.as_ref().filter(|_| name != "diff") {
+            return Err(format!("{} does not take a path: {}", name, path));
+        }
+        if depth.is_some() {
+            return Err(format!("{} does not accept --depth", name));
+        }
+    }
+
+    if journal_filtered && name != "journal" {
+        return Err(format!("{} does not accept journal filters", name));
+    }
+    if apply && name != "retention" {
+        return Err(format!("{} does not accept --apply", name));
+    }
+    if format.is_some() && name != "report" {
+        return Err(format!("{} does not accept --format", name));
+    }
+
+    let command = match name.as_str() {
+        "tools" => Command::Tools,
+        "packages" => Command::Packages,
+        "caches" => Command::Caches,
+        "project-caches" => Command::ProjectCaches { path, depth },
+        "runtimes" => Command::Runtimes,
+        "pins" => Command::Pins { path, depth },
+        "ai-junk" => Command::AiJunk { path, depth },
+        "chat-history" => Command::ChatHistory { path, depth },
+        "diagnose" => Command::Diagnose,
+        "security" => Command::Security,
+        "journal" => Command::Journal(journal),
+        "rules" => Command::Rules,
+        "retention" => Command::Retention { apply },
+        "report" => Command::Report(match format {
+            Some(format) => format,
+            None if json => ReportFormat::Json,
+            None => ReportFormat::Markdown,
+        }),
+        "snapshot" => Command::Snapshot,
+        "snapshots" => Command::Snapshots,
+        "diff" => Command::Diff {
+            from: path.ok_or_else(|| "diff requires a snapshot to compare from".to_string())?,
+            to,
+        },
+        "help" => Command::Help,
+        other => return Err(format!("unknown command: {}", other)),
+    };
+
+    Ok(Invocation {
+        command,
+        json,
+        runner,
+    })
+}
+
+fn parse_depth(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .map_err(|_| format!("invalid --depth value: {}", value))
+}
+
+fn parse_format(value: &str) -> Result<ReportFormat, String> {
+    ReportFormat::parse(value).map_err(|_| format!("invalid --format value: {}", value))
+}
+
+/// The path to scan, falling back to the current directory when no scan roots are configured
+fn path_or_current_dir(path: Option<String>) -> Option<String> {
+    match path {
+        Some(path) => Some(path),
+        None if active_settings().scan_roots.is_empty() => Some(".".to_string()),
+        None => None,
+    }
+}
+
+fn scan_paths<T>(
+    path: Option<String>,
+    depth: Option<usize>,
+    scan: impl FnMut(&str, usize) -> Vec<T>,
+) -> io::Result<Vec<T>> {
+    let path = path_or_current_dir(path);
+    scan_each_root(path.as_deref(), depth, scan).map_err(io::Error::other)
+}
+
+fn execute(invocation: Invocation) -> io::Result<()> {
+    let json = invocation.json;
+    let mut out = io::stdout().lock();
+
+    match invocation.command {
+        Command::Help => write!(out, "{}", USAGE),
+        Command::Version => writeln!(out, "dev-janitor {}", env!("CARGO_PKG_VERSION")),
+        Command::Tools => {
+            let tools = scan_all_tools();
+            if json {
+                return write_json(&mut out, &tools);
+            }
+            let mut table = Table::new(&[
+                "ID", "NAME", "CATEGORY", "VERSION", "STATUS", "HEALTH", "SOURCE", "PATH",
+            ]);
+            for tool in &tools {
+                let active = tool
+                    .versions
+                    .iter()
+                    .find(|version| version.is_active)
+                    .or_else(|| tool.versions.first());
+                table.row(vec![
+                    tool.id.clone(),
+                    tool.name.clone(),
+                    tool.category.clone(),
+                    active.map(|v| v.version.clone()).unwrap_or_default(),
+                    tool.status.clone(),
+                    tool_health(tool),
+                    active.map(install_source).unwrap_or_default(),
+                    active.map(|v| v.path.clone()).unwrap_or_default(),
+                ]);
+            }
+            table.write(&mut out)?;
+            for tool in &tools {
+                for check in tool.failed_checks() {
+                    writeln!(
+                        out,
+                        "\n{}: {} failed: {}",
+                        tool.id, check.name, check.detail
+                    )?;
+                    if let Some(hint) = &check.hint {
+                        writeln!(out, "  {}", hint)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        Command::Packages => {
+            let packages = scan_all_packages();
+            if json {
+                return write_json(&mut out, &packages);
+            }
+            let mut table = Table::new(&["MANAGER", "NAME", "VERSION", "LATEST", "SCOPE"]);
+            for package in &packages {
+                table.row(vec![
+                    package.manager.clone(),
+                    package.name.clone(),
+                    package.version.clone(),
+                    package.latest.clone().unwrap_or_default(),
+                    package.scope.clone().unwrap_or_default(),
+                ]);
+            }
+            table.write(&mut out)
+        }
+        Command::Caches => {
+            let caches = scan_package_manager_caches();
+            if json {
+                return write_json(&mut out, &caches);
+            }
+            write_cache_table(&mut out, &caches)
+        }
+        Command::ProjectCaches { path, depth } => {
+            let caches = scan_paths(path, depth, scan_project_caches)?;
+            if json {
+                return write_json(&mut out, &caches);
+            }
+            write_cache_table(&mut out, &caches)
+        }
+        Command::Runtimes => {
+            let versions = scan_runtime_versions();
+            if json {
+                return write_json(&mut out, &versions);
+            }
+            let mut table =
+                Table::new(&["MANAGER", "RUNTIME", "VERSION", "SIZE", "DEFAULT", "PATH"]);
+            for version in &versions {
+                table.row(vec![
+                    version.manager.clone(),
+                    version.runtime.clone(),
+                    version.version.clone(),
+                    version.size_display.clone(),
+                    if version.is_default { "yes" } else { "" }.to_string(),
+                    version.path.clone(),
+                ]);
+            }
+            table.write(&mut out)
+        }
+        Command::Pins { path, depth } => {
+            let path = path_or_current_dir(path);
+            let report = version_pin_report(path.as_deref(), depth).map_err(io::Error::other)?;
+            if json {
+                return write_json(&mut out, &report);
+            }
+            let mut table = Table::new(&["RUNTIME", "REQUEST", "INSTALLED", "SUPPORT", "FILE"]);
+            for pin in &report.pins {
+                let missing = report
+                    .missing
+                    .iter()
+                    .any(|missing| missing.file == pin.file && missing.request == pin.request);
+                table.row(vec![
+                    pin.runtime.clone(),
+                    pin.request.clone(),
+                    match &pin.installed {
+                        Some(version) => version.clone(),
+                        None if missing => "missing".to_string(),
+                        None => String::new(),
+                    },
+                    pin.lifecycle
+                        .as_ref()
+                        .map(|lifecycle| lifecycle.status.as_str().to_string())
+                        .unwrap_or_default(),
+                    pin.file.clone(),
+                ]);
+            }
+            table.write(&mut out)?;
+
+            if !report.unused.is_empty() {
+                writeln!(out, "\nInstalled versions no project pins:")?;
+                let mut table = Table::new(&["MANAGER", "RUNTIME", "VERSION", "SIZE", "PATH"]);
+                for version in &report.unused {
+                    table.row(vec![
+                        version.manager.clone(),
+                        version.runtime.clone(),
+                        version.version.clone(),
+                        version.size_display.clone(),
+                        version.path.clone(),
+                    ]);
+                }
+                table.write(&mut out)?;
+            }
+            writeln!(
+                out,
+                "{} pins, {} missing locally, {} past end of life, {} unused versions ({})",
+                report.pins.len(),
+                report.missing.len(),
+                report.end_of_life.len(),
+                report.unused.len(),
+                format_size(report.unused.iter().map(|version| version.size).sum())
+            )
+        }
+        Command::AiJunk { path, depth } => {
+            let files = scan_paths(path, depth, scan_ai_junk)?;
+            if json {
+                return write_json(&mut out, &files);
+            }
+            let mut table = Table::new(&["TYPE", "SIZE", "PATH", "REASON"]);
+            for file in &files {
+                table.row(vec![
+                    file.junk_type.clone(),
+                    file.size_display.clone(),
+                    file.path.clone(),
+                    file.reason.clone(),
+                ]);
+            }
+            table.write(&mut out)
+        }
+        Command::ChatHistory { path, depth } => {
+            let projects = scan_paths(path, depth, scan_chat_history)?;
+            if json {
+                return write_json(&mut out, &projects);
+            }
+            let mut table = Table::new(&["PROJECT", "SIZE", "FILES", "TOOLS", "PATH"]);
+            for project in &projects {
+                let mut tools = project.ai_tools_detected.clone();
+                tools.sort();
+                table.row(vec![
+                    project.name.clone(),
+                    project.total_size_display.clone(),
+                    project.chat_files.len().to_string(),
+                    tools.join(", "),
+                    project.project_path.clone(),
+                ]);
+            }
+            table.write(&mut out)
+        }
+        Command::Diagnose => {
+            let diagnosis = diagnose_environment();
+            if json {
+                return write_json(&mut out, &diagnosis);
+            }
+            let mut table = Table::new(&["SEVERITY", "CATEGORY", "MESSAGE", "SUGGESTION"]);
+            for issue in &diagnosis.issues {
+                table.row(vec![
+                    issue.severity.clone(),
+                    issue.category.clone(),
+                    issue.message.clone(),
+                    issue.suggestion.clone().unwrap_or_default(),
+                ]);
+            }
+            table.write(&mut out)?;
+            for issue in diagnosis
+                .issues
+                .iter()
+                .filter(|issue| !issue.evidence.is_empty())
+            {
+                writeln!(out, "\n{}:", issue.message)?;
+                for evidence in &issue.evidence {
+                    writeln!(out, "  {}", evidence)?;
+                }
+            }
+            for suggestion in &diagnosis.suggestions {
+                writeln!(out, "- {}", suggestion)?;
+            }
+            Ok(())
+        }
+        Command::Security => {
+            let result = scan_ai_tool_security();
+            if json {
+                return write_json(&mut out, &result);
+            }
+            let mut table = Table::new(&["RISK", "TOOL", "ISSUE", "REMEDIATION"]);
+            for finding in &result.findings {
+                table.row(vec![
+                    finding.risk_level.as_str().to_string(),
+                    finding.tool_name.clone(),
+                    finding.issue.clone(),
+                    finding.remediation.clone(),
+                ]);
+            }
+            table.write(&mut out)?;
+            writeln!(
+                out,
+                "{} findings ({} critical, {} high, {} medium, {} low)",
+                result.summary.total_findings,
+                result.summary.critical,
+                result.summary.high,
+                result.summary.medium,
+                result.summary.low
+            )
+        }
+        Command::Journal(query) => {
+            let entries = query_journal(&query).map_err(io::Error::other)?;
+            if json {
+                return write_json(&mut out, &entries);
+            }
+            let mut table = Table::new(&["TIME", "ACTION", "RESULT", "FREED", "TARGET", "DETAIL"]);
+            for entry in &entries {
+                let detail = entry
+                    .error
+                    .clone()
+                    .or_else(|| entry.command.clone())
+                    .unwrap_or_default();
+                table.row(vec![
+                    entry.timestamp.clone(),
+                    entry.action.clone(),
+                    if entry.success { "ok" } else { "failed" }.to_string(),
+                    match (entry.bytes_freed, entry.bytes_quarantined) {
+                        (Some(freed), _) => format_size(freed),
+                        (None, Some(quarantined)) => {
+                            format!("{} (quarantined)", format_size(quarantined))
+                        }
+                        (None, None) => String::new(),
+                    },
+                    entry.target.clone(),
+                    detail,
+                ]);
+            }
+            table.write(&mut out)
+        }
+        Command::Rules => write_rules_summary(&mut out, json),
+        Command::Snapshot => {
+            let snapshot = take_snapshot().map_err(io::Error::other)?;
+            if json {
+                return write_json(&mut out, &snapshot);
+            }
+            writeln!(out, "Saved snapshot {} ({})", snapshot.id, snapshot.path)
+        }
+        Command::Snapshots => {
+            let snapshots = list_snapshots().map_err(io::Error::other)?;
+            if json {
+                return write_json(&mut out, &snapshots);
+            }
+            let mut table = Table::new(&["ID", "GENERATED", "HOST", "TOOLS", "PACKAGES"]);
+            for snapshot in &snapshots {
+                table.row(vec![
+                    snapshot.id.clone(),
+                    snapshot.generated_at.clone(),
+                    snapshot.hostname.clone().unwrap_or_default(),
+                    snapshot.tools.to_string(),
+                    snapshot.packages.to_string(),
+                ]);
+            }
+            table.write(&mut out)
+        }
+        Command::Diff { from, to } => {
+            let diff = diff_snapshots(&from, to.as_deref()).map_err(io::Error::other)?;
+            if json {
+                return write_json(&mut out, &diff);
+            }
+            write_diff(&mut out, &diff)
+        }
+        Command::Report(format) => {
+            let report = render_report(&collect_inventory(), format).map_err(io::Error::other)?;
+            write!(out, "{}", report)
+        }
+        Command::Retention { apply } => {
+            let run = if apply {
+                run_retention("manual").map_err(io::Error::other)?
+            } else {
+                preview_retention()
+            };
+            if json {
+                return write_json(&mut out, &run);
+            }
+            let mut table = Table::new(&["POLICY", "SIZE", "AGE", "RESULT", "TARGET"]);
+            for action in &run.actions {
+                let result = match (action.success, &action.error) {
+                    (None, _) => "would clean".to_string(),
+                    (Some(true), _) => "cleaned".to_string(),
+                    (Some(false), error) => error.clone().unwrap_or_default(),
+                };
+                table.row(vec![
+                    action.policy_id.clone(),
+                    action.size_display.clone(),
+                    action
+                        .age_days
+                        .map(|days| format!("{}d", days))
+                        .unwrap_or_default(),
+                    result,
+                    action.target.clone(),
+                ]);
+            }
+            table.write(&mut out)?;
+            for error in &run.errors {
+                writeln!(out, "error: {}", error)?;
+            }
+            if apply {
+                writeln!(
+                    out,
+                    "Freed {}, quarantined {}",
+                    run.bytes_freed_display, run.bytes_quarantined_display
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write_rules_summary(out: &mut impl Write, json: bool) -> io::Result<()> {
+    let summary = load_user_rules().map_err(io::Error::other)?.summary();
+    if json {
+        return write_json(out, &summary);
+    }
+    match &summary.path {
+        Some(path) => writeln!(
+            out,
+            "{}: {} rules added, {} built-in rules disabled",
+            path, summary.rules_added, summary.builtins_disabled
+        ),
+        None => writeln!(
+            out,
+            "No rules file found; looked for {}",
+            summary.search_paths.join(", ")
+        ),
+    }
+}
+
+fn write_diff(out: &mut impl Write, diff: &InventoryDiff) -> io::Result<()> {
+    if diff.is_empty() {
+        return writeln!(
+            out,
+            "No changes between {} and {}",
+            diff.from_generated_at, diff.to_generated_at
+        );
+    }
+
+    let mut table = Table::new(&["CHANGE", "ITEM", "BEFORE", "AFTER"]);
+    let mut row = |change: &str, item: &str, before: String, after: String| {
+        table.row(vec![change.to_string(), item.to_string(), before, after]);
+    };
+    for tool in &diff.tools_added {
+        row(
+            "tool added",
+            &tool.name,
+            String::new(),
+            tool.versions.join(", "),
+        );
+    }
+    for tool in &diff.tools_removed {
+        row(
+            "tool removed",
+            &tool.name,
+            tool.versions.join(", "),
+            String::new(),
+        );
+    }
+    for change in &diff.tool_version_changes {
+        for version in &change.added {
+            let installed = format!("{} ({})", version.version, version.path);
+            row("version added", &change.name, String::new(), installed);
+        }
+        for version in &change.removed {
+            let installed = format!("{} ({})", version.version, version.path);
+            row("version removed", &change.name, installed, String::new());
+        }
+        if change.active_before != change.active_after {
+            row(
+                "active version",
+                &change.name,
+                change.active_before.clone().unwrap_or_default(),
+                change.active_after.clone().unwrap_or_default(),
+            );
+        }
+    }
+    let packages = [
+        ("package added", &diff.packages_added),
+        ("package removed", &diff.packages_removed),
+        ("upgraded", &diff.packages_upgraded),
+        ("downgraded", &diff.packages_downgraded),
+    ];
+    for (change, packages) in packages {
+        for package in packages {
+            row(
+                change,
+                &format!("{}:{}", package.manager, package.name),
+                package.from.clone().unwrap_or_default(),
+                package.to.clone().unwrap_or_default(),
+            );
+        }
+    }
+    for package in &diff.packages_newly_outdated {
+        row(
+            "now outdated",
+            &format!("{}:{}", package.manager, package.name),
+            package.to.clone().unwrap_or_default(),
+            format!("latest {}", package.latest.clone().unwrap_or_default()),
+        );
+    }
+    for cache in &diff.caches_grown {
+        row(
+            "cache grew",
+            &cache.name,
+            format_size(cache.size_before),
+            format!(
+                "{} (+{})",
+                format_size(cache.size_after),
+                cache.growth_display
+            ),
+        );
+    }
+    for finding in &diff.new_security_findings {
+        let issue = format!("{}: {}", finding.risk_level.as_str(), finding.issue);
+        row("new finding", &finding.tool_name, String::new(), issue);
+    }
+    for finding in &diff.resolved_security_findings {
+        let issue = format!("{}: {}", finding.risk_level.as_str(), finding.issue);
+        row("resolved finding", &finding.tool_name, issue, String::new());
+    }
+    table.write(out)
+}
+
+fn write_cache_table(out: &mut impl Write, caches: &[crate::cache::CacheInfo]) -> io::Result<()> {
+    let mut table = Table::new(&["ID", "NAME", "SIZE", "ON DISK", "PATH"]);
+    for cache in caches {
+        table.row(vec![
+            cache.id.clone(),
+            cache.name.clone(),
+            cache.size_display.clone(),
+            format_size(cache.allocated_size),
+            cache.path.clone(),
+        ]);
+    }
+    table.write(out)
+}
+
+/// `ok`, the number of failed checks, or `-` for tools without checks
+fn tool_health(tool: &ToolInfo) -> String {
+    if tool.health.is_empty() {
+        return "-".to_string();
+    }
+    match tool.failed_checks().count() {
+        0 => "ok".to_string(),
+        failed => format!("{}/{} failed", failed, tool.health.len()),
+    }
+}
+
+/// Install source with the package it belongs to, e.g. `apt (git)`
+fn install_source(version: &ToolVersion) -> String {
+    match &version.source_package {
+        Some(package) => format!("{} ({})", version.source.as_str(), package),
+        None => version.source.as_str().to_string(),
+    }
+}
+
+fn write_json<T: Serialize + ?Sized>(out: &mut impl Write, value: &T) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Invocation, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_path_commands_with_defaults() {
+        let invocation = parse(&["project-caches"]).unwrap();
+        assert_eq!(
+            invocation.command,
+            Command::ProjectCaches {
+                path: None,
+                depth: None
+            }
+        );
+        assert!(!invocation.json);
+    }
+
+    #[test]
+    fn parses_flags_in_any_position() {
+        let invocation = parse(&["--json", "ai-junk", "/srv/code", "--depth=99"]).unwrap();
+        assert!(invocation.json);
+        assert_eq!(
+            invocation.command,
+            Command::AiJunk {
+                path: Some("/srv/code".to_string()),
+                depth: Some(99)
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_usage() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+        assert!(parse(&["tools", "/tmp"]).is_err());
+        assert!(parse(&["caches", "--depth", "3"]).is_err());
+        assert!(parse(&["chat-history", "--depth"]).is_err());
+        assert!(parse(&["chat-history", "--depth", "deep"]).is_err());
+        assert!(parse(&["tools", "--verbose"]).is_err());
+        assert!(parse(&["tools", "--since", "2026-10-13"]).is_err());
+        assert!(parse(&["journal", "--limit", "many"]).is_err());
+        assert!(parse(&["caches", "--apply"]).is_err());
+        assert!(parse(&["tools", "--format", "html"]).is_err());
+        assert!(parse(&["report", "--format", "pdf"]).is_err());
+        assert!(parse(&["tools", "--record"]).is_err());
+        assert!(parse(&["tools", "--record", "a.jsonl", "--replay", "b.jsonl"]).is_err());
+    }
+
+    #[test]
+    fn parses_journal_filters() {
+        let invocation = parse(&["journal", "--since", "2026-10-13", "--limit", "5"]).unwrap();
+        assert_eq!(
+            invocation.command,
+            Command::Journal(JournalQuery {
+                since: Some("2026-10-13".to_string()),
+                limit: Some(5),
+                ..JournalQuery::default()
+            })
+        );
+    }
+
+    #[test]
+    fn report_format_defaults_to_markdown() {
+        assert_eq!(
+            parse(&["report"]).unwrap().command,
+            Command::Report(ReportFormat::Markdown)
+        );
+        assert_eq!(
+            parse(&["report", "--json"]).unwrap().command,
+            Command::Report(ReportFormat::Json)
+        );
+        assert_eq!(
+            parse(&["report", "--format=html"]).unwrap().command,
+            Command::Report(ReportFormat::Html)
+        );
+    }
+
+    #[test]
+    fn parses_snapshot_diffs() {
+        assert_eq!(
+            parse(&["diff", "20261017-090000"]).unwrap().command,
+            Command::Diff {
+                from: "20261017-090000".to_string(),
+                to: None
+            }
+        );
+        assert_eq!(
+            parse(&["diff", "a", "b.json"]).unwrap().command,
+            Command::Diff {
+                from: "a".to_string(),
+                to: Some("b.json".to_string())
+            }
+        );
+        assert!(parse(&["diff"]).is_err());
+        assert!(parse(&["diff", "a", "b", "c"]).is_err());
+        assert!(parse(&["snapshot", "a"]).is_err());
+    }
+
+    #[test]
+    fn parses_runner_files() {
+        let invocation = parse(&["packages", "--replay", "machine.jsonl"]).unwrap();
+        assert_eq!(
+            invocation.runner,
+            Some(RunnerChoice::Replay(PathBuf::from("machine.jsonl")))
+        );
+        assert_eq!(parse(&["packages"]).unwrap().runner, None);
+    }
+
+    #[test]
+    fn help_wins_over_missing_command() {
+        assert_eq!(parse(&["--help"]).unwrap().command, Command::Help);
+        assert_eq!(parse(&["help"]).unwrap().command, Command::Help);
+        assert_eq!(parse(&["-V"]).unwrap().command, Command::Version);
+    }
+}
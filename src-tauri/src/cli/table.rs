@@ -0,0 +1,91 @@
+//! Plain-text table rendering for CLI output
+
+use std::io::{self, Write};
+
+const COLUMN_GAP: &str = "  ";
+
+/// Left-aligned table sized to its widest cell per column
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.rows.is_empty() {
+            return writeln!(out, "No results.");
+        }
+
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (index, cell) in row.iter().enumerate() {
+                if let Some(width) = widths.get_mut(index) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+        }
+
+        write_line(out, &self.headers, &widths)?;
+        for row in &self.rows {
+            write_line(out, row, &widths)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_line(out: &mut impl Write, cells: &[String], widths: &[usize]) -> io::Result<()> {
+    let last = cells.len().saturating_sub(1);
+    let mut line = String::new();
+
+    for (index, cell) in cells.iter().enumerate() {
+        // Single-line cells keep rows aligned even for multi-line messages
+        let cell = cell.replace(['\r', '\n'], " ");
+        line.push_str(&cell);
+        if index != last {
+            let padding = widths[index].saturating_sub(cell.chars().count());
+            line.push_str(&" ".repeat(padding));
+            line.push_str(COLUMN_GAP);
+        }
+    }
+
+    writeln!(out, "{}", line.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_columns_to_widest_cell() {
+        let mut table = Table::new(&["ID", "PATH"]);
+        table.row(vec!["npm".to_string(), "/home/dev/.npm".to_string()]);
+        table.row(vec!["go".to_string(), "/home/dev/go".to_string()]);
+
+        let mut buffer = Vec::new();
+        table.write(&mut buffer).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "ID   PATH\nnpm  /home/dev/.npm\ngo   /home/dev/go\n"
+        );
+    }
+
+    #[test]
+    fn reports_empty_tables() {
+        let table = Table::new(&["ID"]);
+        let mut buffer = Vec::new();
+        table.write(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "No results.\n");
+    }
+}
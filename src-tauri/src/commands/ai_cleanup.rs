@@ -1,29 +1,32 @@
 //! Tauri commands for AI junk cleanup
 
-use super::run_blocking;
+use super::{run_blocking, run_operation};
 use crate::ai_cleanup::{delete_ai_junk, scan_ai_junk, AiJunkFile};
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::plan::{plan_delete_multiple_ai_junk, CleanupPlan};
+use crate::settings::scan_each_root;
 
-/// Scan a directory for AI junk files
+/// Scan a directory, or the configured scan roots, for AI junk files
 #[tauri::command]
 pub async fn scan_ai_junk_cmd(
-    path: String,
-    #[allow(non_snake_case)] maxDepth: usize,
-) -> Result<Vec<AiJunkFile>, String> {
-    let max_depth = maxDepth.min(20);
-    run_blocking(move || scan_ai_junk(&path, max_depth)).await
+    path: Option<String>,
+    #[allow(non_snake_case)] maxDepth: Option<usize>,
+) -> Result<Vec<AiJunkFile>, DevJanitorError> {
+    run_operation(move || scan_each_root(path.as_deref(), maxDepth, scan_ai_junk)).await
 }
 
 /// Delete an AI junk file
 #[tauri::command]
-pub async fn delete_ai_junk_cmd(path: String) -> Result<String, String> {
-    run_blocking(move || delete_ai_junk(&path)).await?
+pub async fn delete_ai_junk_cmd(path: String) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || delete_ai_junk(&path)).await
 }
 
 /// Delete multiple AI junk files
 #[tauri::command]
 pub async fn delete_multiple_ai_junk(
     paths: Vec<String>,
-) -> Result<Vec<Result<String, String>>, String> {
+) -> Result<Vec<Result<OperationResult, DevJanitorError>>, String> {
     run_blocking(move || {
         paths
             .into_iter()
@@ -32,3 +35,9 @@ pub async fn delete_multiple_ai_junk(
     })
     .await
 }
+
+/// Preview what deleting the selected AI junk files would remove
+#[tauri::command]
+pub async fn plan_delete_ai_junk_cmd(paths: Vec<String>) -> Result<CleanupPlan, String> {
+    run_blocking(move || plan_delete_multiple_ai_junk(&paths)).await
+}
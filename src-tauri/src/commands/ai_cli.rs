@@ -1,9 +1,12 @@
 //! Tauri commands for AI CLI tools management
 
-use super::run_blocking;
+use super::{run_blocking, run_operation};
 use crate::ai_cli::{
     get_ai_cli_tools, install_ai_tool, uninstall_ai_tool, update_ai_tool, AiCliTool,
 };
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::plan::{plan_uninstall_ai_tool, CleanupPlan};
 
 /// Get all AI CLI tools with status
 #[tauri::command]
@@ -15,20 +18,30 @@ pub async fn get_ai_cli_tools_cmd() -> Result<Vec<AiCliTool>, String> {
 #[tauri::command]
 pub async fn install_ai_tool_cmd(
     #[allow(non_snake_case)] toolId: String,
-) -> Result<String, String> {
-    run_blocking(move || install_ai_tool(&toolId)).await?
+) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || install_ai_tool(&toolId)).await
 }
 
 /// Update an AI CLI tool
 #[tauri::command]
-pub async fn update_ai_tool_cmd(#[allow(non_snake_case)] toolId: String) -> Result<String, String> {
-    run_blocking(move || update_ai_tool(&toolId)).await?
+pub async fn update_ai_tool_cmd(
+    #[allow(non_snake_case)] toolId: String,
+) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || update_ai_tool(&toolId)).await
 }
 
 /// Uninstall an AI CLI tool
 #[tauri::command]
 pub async fn uninstall_ai_tool_cmd(
     #[allow(non_snake_case)] toolId: String,
-) -> Result<String, String> {
-    run_blocking(move || uninstall_ai_tool(&toolId)).await?
+) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || uninstall_ai_tool(&toolId)).await
+}
+
+/// Preview the commands uninstalling an AI CLI tool would try
+#[tauri::command]
+pub async fn plan_uninstall_ai_tool_cmd(
+    #[allow(non_snake_case)] toolId: String,
+) -> Result<CleanupPlan, DevJanitorError> {
+    run_operation(move || plan_uninstall_ai_tool(&toolId)).await
 }
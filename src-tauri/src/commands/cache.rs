@@ -1,7 +1,12 @@
 //! Tauri commands for cache management
 
-use super::run_blocking;
+use super::{run_blocking, run_operation};
 use crate::cache::{clean_cache, scan_package_manager_caches, scan_project_caches, CacheInfo};
+use crate::disk_usage::{disk_usage_of, format_size};
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::plan::{plan_clean_caches, CleanupPlan};
+use crate::settings::scan_each_root;
 
 /// Scan all package manager caches
 #[tauri::command]
@@ -9,27 +14,26 @@ pub async fn scan_caches() -> Result<Vec<CacheInfo>, String> {
     run_blocking(scan_package_manager_caches).await
 }
 
-/// Scan project caches in a directory
+/// Scan project caches in a directory, or in the configured scan roots
 #[tauri::command]
 pub async fn scan_project_caches_cmd(
-    path: String,
-    #[allow(non_snake_case)] maxDepth: usize,
-) -> Result<Vec<CacheInfo>, String> {
-    let max_depth = maxDepth.min(20);
-    run_blocking(move || scan_project_caches(&path, max_depth)).await
+    path: Option<String>,
+    #[allow(non_snake_case)] maxDepth: Option<usize>,
+) -> Result<Vec<CacheInfo>, DevJanitorError> {
+    run_operation(move || scan_each_root(path.as_deref(), maxDepth, scan_project_caches)).await
 }
 
 /// Clean a specific cache
 #[tauri::command]
-pub async fn clean_cache_cmd(path: String) -> Result<String, String> {
-    run_blocking(move || clean_cache(&path)).await?
+pub async fn clean_cache_cmd(path: String) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || clean_cache(&path)).await
 }
 
 /// Clean multiple caches
 #[tauri::command]
 pub async fn clean_multiple_caches(
     paths: Vec<String>,
-) -> Result<Vec<Result<String, String>>, String> {
+) -> Result<Vec<Result<OperationResult, DevJanitorError>>, String> {
     run_blocking(move || paths.into_iter().map(|path| clean_cache(&path)).collect()).await
 }
 
@@ -37,19 +41,14 @@ pub async fn clean_multiple_caches(
 #[tauri::command]
 pub async fn get_total_cache_size(paths: Vec<String>) -> Result<String, String> {
     run_blocking(move || {
-        let total: u64 = paths
-            .iter()
-            .filter_map(|p| {
-                let path = std::path::PathBuf::from(p);
-                if path.exists() {
-                    Some(crate::cache::get_dir_size(&path))
-                } else {
-                    None
-                }
-            })
-            .sum();
-
-        crate::cache::format_size(total)
+        let paths: Vec<std::path::PathBuf> = paths.iter().map(std::path::PathBuf::from).collect();
+        format_size(disk_usage_of(&paths).apparent)
     })
     .await
 }
+
+/// Preview what cleaning the selected caches would remove
+#[tauri::command]
+pub async fn plan_clean_caches_cmd(paths: Vec<String>) -> Result<CleanupPlan, String> {
+    run_blocking(move || plan_clean_caches(&paths)).await
+}
@@ -4,16 +4,19 @@ use super::super::chat_history::{
     delete_chat_file, delete_project_chat_history, scan_chat_history, scan_global_chat_history,
     ChatHistoryFile, ProjectChatHistory,
 };
-use super::run_blocking;
+use super::{run_blocking, run_operation};
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::plan::{plan_delete_project_chat_history, CleanupPlan};
+use crate::settings::scan_each_root;
 
-/// Scan for projects with AI chat history
+/// Scan a directory, or the configured scan roots, for projects with AI chat history
 #[tauri::command]
 pub async fn scan_chat_history_cmd(
-    path: String,
-    #[allow(non_snake_case)] maxDepth: usize,
-) -> Result<Vec<ProjectChatHistory>, String> {
-    let max_depth = maxDepth.min(20);
-    run_blocking(move || scan_chat_history(&path, max_depth)).await
+    path: Option<String>,
+    #[allow(non_snake_case)] maxDepth: Option<usize>,
+) -> Result<Vec<ProjectChatHistory>, DevJanitorError> {
+    run_operation(move || scan_each_root(path.as_deref(), maxDepth, scan_chat_history)).await
 }
 
 /// Scan global AI chat history locations
@@ -24,16 +27,16 @@ pub async fn scan_global_chat_history_cmd() -> Result<Vec<ChatHistoryFile>, Stri
 
 /// Delete a single chat history file or directory
 #[tauri::command]
-pub async fn delete_chat_file_cmd(path: String) -> Result<String, String> {
-    run_blocking(move || delete_chat_file(&path)).await?
+pub async fn delete_chat_file_cmd(path: String) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || delete_chat_file(&path)).await
 }
 
 /// Delete all chat history for a project
 #[tauri::command]
 pub async fn delete_project_chat_history_cmd(
     project_path: String,
-) -> Result<(u32, u32, String), String> {
-    run_blocking(move || delete_project_chat_history(&project_path)).await?
+) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || delete_project_chat_history(&project_path)).await
 }
 
 /// Delete multiple chat history files
@@ -51,7 +54,7 @@ pub async fn delete_multiple_chat_files(
                 Ok(_) => success_count += 1,
                 Err(e) => {
                     fail_count += 1;
-                    errors.push(e);
+                    errors.push(e.to_string());
                 }
             }
         }
@@ -60,3 +63,11 @@ pub async fn delete_multiple_chat_files(
     })
     .await
 }
+
+/// Preview what deleting a project's chat history would remove
+#[tauri::command]
+pub async fn plan_delete_project_chat_history_cmd(
+    project_path: String,
+) -> Result<CleanupPlan, DevJanitorError> {
+    run_operation(move || plan_delete_project_chat_history(&project_path)).await
+}
@@ -0,0 +1,11 @@
+//! Tauri commands for the audit journal
+
+use super::run_operation;
+use crate::error::DevJanitorError;
+use crate::journal::{query_journal, JournalEntry, JournalQuery};
+
+/// Query recorded actions, newest first
+#[tauri::command]
+pub async fn query_journal_cmd(query: JournalQuery) -> Result<Vec<JournalEntry>, DevJanitorError> {
+    run_operation(move || query_journal(&query)).await
+}
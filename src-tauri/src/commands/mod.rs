@@ -5,11 +5,23 @@ pub mod ai_cli;
 pub mod cache;
 pub mod chat_history;
 pub mod config;
+pub mod journal;
 pub mod packages;
+pub mod plan;
+pub mod quarantine;
+pub mod report;
+pub mod retention;
+pub mod rules;
+pub mod runtimes;
+pub mod scan_job;
 pub mod security;
 pub mod services;
+pub mod settings;
+pub mod snapshot;
 pub mod tools;
 
+use crate::error::DevJanitorError;
+
 /// Run filesystem and subprocess-heavy work away from Tauri's UI thread.
 pub(crate) async fn run_blocking<T, F>(work: F) -> Result<T, String>
 where
@@ -21,12 +33,33 @@ where
         .map_err(|error| format!("Background task failed: {error}"))
 }
 
+/// Like `run_blocking`, for operations that fail with a structured `DevJanitorError`.
+pub(crate) async fn run_operation<T, F>(work: F) -> Result<T, DevJanitorError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, DevJanitorError> + Send + 'static,
+{
+    run_blocking(work)
+        .await
+        .map_err(DevJanitorError::Internal)?
+}
+
 pub use ai_cleanup::*;
 pub use ai_cli::*;
 pub use cache::*;
 pub use chat_history::*;
 pub use config::*;
+pub use journal::*;
 pub use packages::*;
+pub use plan::*;
+pub use quarantine::*;
+pub use report::*;
+pub use retention::*;
+pub use rules::*;
+pub use runtimes::*;
+pub use scan_job::*;
 pub use security::*;
 pub use services::*;
+pub use settings::*;
+pub use snapshot::*;
 pub use tools::*;
@@ -1,8 +1,10 @@
 //! Tauri commands for package management
 
-use super::run_blocking;
-use crate::package_manager::{cargo, composer, conda, npm, pip, pnpm, yarn};
-use crate::package_manager::{scan_all_packages, PackageInfo, PackageManager};
+use super::{run_blocking, run_operation};
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::package_manager::{self, scan_all_packages, ManagerSummary, PackageInfo};
+use crate::plan::{plan_uninstall_package, CleanupPlan};
 
 /// Scan all package managers for installed packages
 #[tauri::command]
@@ -10,140 +12,66 @@ pub async fn scan_packages() -> Result<Vec<PackageInfo>, String> {
     run_blocking(scan_all_packages).await
 }
 
-/// Update a package
+/// Installed package managers and the operations each supports
 #[tauri::command]
-pub async fn update_package(manager: String, name: String) -> Result<String, String> {
-    run_blocking(move || update_package_sync(&manager, &name)).await?
+pub async fn list_package_managers_cmd() -> Result<Vec<ManagerSummary>, String> {
+    run_blocking(package_manager::package_managers).await
 }
 
-fn update_package_sync(manager: &str, name: &str) -> Result<String, String> {
-    match manager {
-        "npm" => {
-            if let Some(m) = npm::NpmManager::new() {
-                m.update_package(name)
-            } else {
-                Err("npm is not available".to_string())
-            }
-        }
-        "pnpm" => {
-            if let Some(m) = pnpm::PnpmManager::new() {
-                m.update_package(name)
-            } else {
-                Err("pnpm is not available".to_string())
-            }
-        }
-        "yarn" => {
-            if let Some(m) = yarn::YarnManager::new() {
-                m.update_package(name)
-            } else {
-                Err("yarn is not available".to_string())
-            }
-        }
-        "pip" => {
-            if let Some(m) = pip::PipManager::new() {
-                m.update_package(name)
-            } else {
-                Err("pip is not available".to_string())
-            }
-        }
-        "cargo" => {
-            if let Some(m) = cargo::CargoManager::new() {
-                m.update_package(name)
-            } else {
-                Err("cargo is not available".to_string())
-            }
-        }
-        "composer" => {
-            if let Some(m) = composer::ComposerManager::new() {
-                m.update_package(name)
-            } else {
-                Err("composer is not available".to_string())
-            }
-        }
-        "conda" => {
-            if let Some(m) = conda::CondaManager::new() {
-                m.update_package(name)
-            } else {
-                Err("conda is not available".to_string())
-            }
-        }
-        "homebrew" => {
-            use crate::package_manager::homebrew;
-            if let Some(m) = homebrew::HomebrewManager::new() {
-                m.update_package(name)
-            } else {
-                Err("homebrew is not available".to_string())
-            }
-        }
-        _ => Err(format!("Unknown package manager: {}", manager)),
-    }
+/// Update a package
+#[tauri::command]
+pub async fn update_package(
+    manager: String,
+    name: String,
+) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || package_manager::update_package(&manager, &name)).await
 }
 
 /// Uninstall a package
 #[tauri::command]
-pub async fn uninstall_package(manager: String, name: String) -> Result<String, String> {
-    run_blocking(move || uninstall_package_sync(&manager, &name)).await?
+pub async fn uninstall_package(
+    manager: String,
+    name: String,
+) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || package_manager::uninstall_package(&manager, &name)).await
+}
+
+/// Install a package
+#[tauri::command]
+pub async fn install_package_cmd(
+    manager: String,
+    name: String,
+) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || package_manager::install_package(&manager, &name)).await
+}
+
+/// Install an exact version of a package
+#[tauri::command]
+pub async fn pin_package_cmd(
+    manager: String,
+    name: String,
+    version: String,
+) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || package_manager::pin_package(&manager, &name, &version)).await
+}
+
+/// Update every package of one manager
+#[tauri::command]
+pub async fn update_all_packages_cmd(manager: String) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || package_manager::update_all_packages(&manager)).await
 }
 
-fn uninstall_package_sync(manager: &str, name: &str) -> Result<String, String> {
-    match manager {
-        "npm" => {
-            if let Some(m) = npm::NpmManager::new() {
-                m.uninstall_package(name)
-            } else {
-                Err("npm is not available".to_string())
-            }
-        }
-        "pnpm" => {
-            if let Some(m) = pnpm::PnpmManager::new() {
-                m.uninstall_package(name)
-            } else {
-                Err("pnpm is not available".to_string())
-            }
-        }
-        "yarn" => {
-            if let Some(m) = yarn::YarnManager::new() {
-                m.uninstall_package(name)
-            } else {
-                Err("yarn is not available".to_string())
-            }
-        }
-        "pip" => {
-            if let Some(m) = pip::PipManager::new() {
-                m.uninstall_package(name)
-            } else {
-                Err("pip is not available".to_string())
-            }
-        }
-        "cargo" => {
-            if let Some(m) = cargo::CargoManager::new() {
-                m.uninstall_package(name)
-            } else {
-                Err("cargo is not available".to_string())
-            }
-        }
-        "composer" => {
-            if let Some(m) = composer::ComposerManager::new() {
-                m.uninstall_package(name)
-            } else {
-                Err("composer is not available".to_string())
-            }
-        }
-        "conda" => {
-            if let Some(m) = conda::CondaManager::new() {
-                m.uninstall_package(name)
-            } else {
-                Err("conda is not available".to_string())
-            }
-        }
-        "homebrew" => {
-            use crate::package_manager::homebrew;
-            if let Some(m) = homebrew::HomebrewManager::new() {
-                m.uninstall_package(name)
-            } else {
-                Err("homebrew is not available".to_string())
-            }
-        }
-        _ => Err(format!("Unknown package manager: {}", manager)),
-    }
+/// Package details as the manager prints them
+#[tauri::command]
+pub async fn package_info_cmd(manager: String, name: String) -> Result<String, DevJanitorError> {
+    run_operation(move || package_manager::package_info(&manager, &name)).await
+}
+
+/// Preview the command `uninstall_package` would run
+#[tauri::command]
+pub async fn plan_uninstall_package_cmd(
+    manager: String,
+    name: String,
+) -> Result<CleanupPlan, DevJanitorError> {
+    run_operation(move || plan_uninstall_package(&manager, &name)).await
 }
@@ -0,0 +1,14 @@
+//! Tauri commands for approving dry-run plans
+
+use super::run_blocking;
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::plan::{execute_plan, CleanupPlan};
+
+/// Execute a reviewed plan, returning one result per step
+#[tauri::command]
+pub async fn execute_cleanup_plan_cmd(
+    plan: CleanupPlan,
+) -> Result<Vec<Result<OperationResult, DevJanitorError>>, String> {
+    run_blocking(move || execute_plan(&plan)).await
+}
@@ -0,0 +1,28 @@
+//! Tauri commands for the cleanup quarantine
+
+use super::{run_blocking, run_operation};
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::quarantine::{
+    list_quarantine, purge_quarantine, restore_quarantined, PurgeSummary, QuarantineEntry,
+};
+
+/// List items moved into quarantine by cleanups
+#[tauri::command]
+pub async fn list_quarantine_cmd() -> Result<Vec<QuarantineEntry>, String> {
+    run_blocking(list_quarantine).await
+}
+
+/// Restore a quarantined item to its original location
+#[tauri::command]
+pub async fn restore_quarantined_cmd(id: String) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || restore_quarantined(&id)).await
+}
+
+/// Permanently delete quarantined items older than the given number of days
+#[tauri::command]
+pub async fn purge_quarantine_cmd(
+    #[allow(non_snake_case)] olderThanDays: u32,
+) -> Result<PurgeSummary, DevJanitorError> {
+    run_operation(move || purge_quarantine(olderThanDays)).await
+}
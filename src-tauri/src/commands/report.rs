@@ -0,0 +1,11 @@
+//! Tauri commands for the machine inventory report
+
+use super::run_operation;
+use crate::error::DevJanitorError;
+use crate::report::generate_report;
+
+/// Collect the full inventory and render it as json, markdown or html
+#[tauri::command]
+pub async fn generate_report_cmd(format: String) -> Result<String, DevJanitorError> {
+    run_operation(move || generate_report(&format)).await
+}
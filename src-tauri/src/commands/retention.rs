@@ -0,0 +1,28 @@
+//! Tauri commands for retention policies
+
+use super::{run_blocking, run_operation};
+use crate::error::DevJanitorError;
+use crate::retention::{list_retention_runs, preview_retention, run_retention, RetentionRun};
+
+/// Event carrying a `DevJanitorError` when a scheduled retention run is skipped or fails
+pub const RETENTION_PROBLEM_EVENT: &str = "retention-problem";
+
+/// Show what the enabled retention policies would clean
+#[tauri::command]
+pub async fn preview_retention_cmd() -> Result<RetentionRun, String> {
+    run_blocking(preview_retention).await
+}
+
+/// Apply the enabled retention policies now
+#[tauri::command]
+pub async fn run_retention_cmd() -> Result<RetentionRun, DevJanitorError> {
+    run_operation(|| run_retention("manual")).await
+}
+
+/// Recorded retention runs, newest first
+#[tauri::command]
+pub async fn list_retention_runs_cmd(
+    limit: Option<usize>,
+) -> Result<Vec<RetentionRun>, DevJanitorError> {
+    run_operation(move || list_retention_runs(limit)).await
+}
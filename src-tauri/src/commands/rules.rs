@@ -0,0 +1,11 @@
+//! Tauri commands for user rule overlays
+
+use super::run_operation;
+use crate::error::DevJanitorError;
+use crate::rules::{reload_rules, RulesSummary};
+
+/// Re-read and validate the user rules file, applying it to later scans
+#[tauri::command]
+pub async fn reload_rules_cmd() -> Result<RulesSummary, DevJanitorError> {
+    run_operation(reload_rules).await
+}
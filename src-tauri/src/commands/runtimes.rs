@@ -0,0 +1,35 @@
+//! Tauri commands for runtime versions installed by version managers
+
+use super::{run_blocking, run_operation};
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::plan::{plan_remove_runtime_versions, CleanupPlan};
+use crate::runtimes::pins::{version_pin_report, PinReport};
+use crate::runtimes::{remove_runtime_version, scan_runtime_versions, RuntimeVersion};
+
+/// List every runtime version kept by nvm, fnm, Volta, mise, asdf, pyenv, SDKMAN and rustup
+#[tauri::command]
+pub async fn scan_runtime_versions_cmd() -> Result<Vec<RuntimeVersion>, String> {
+    run_blocking(scan_runtime_versions).await
+}
+
+/// Remove an installed runtime version
+#[tauri::command]
+pub async fn remove_runtime_version_cmd(path: String) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || remove_runtime_version(&path)).await
+}
+
+/// Preview what removing the selected runtime versions would remove
+#[tauri::command]
+pub async fn plan_remove_runtime_versions_cmd(paths: Vec<String>) -> Result<CleanupPlan, String> {
+    run_blocking(move || plan_remove_runtime_versions(&paths)).await
+}
+
+/// Reconcile versions pinned by projects in a directory (or the scan roots) with installed versions
+#[tauri::command]
+pub async fn scan_version_pins_cmd(
+    path: Option<String>,
+    #[allow(non_snake_case)] maxDepth: Option<usize>,
+) -> Result<PinReport, DevJanitorError> {
+    run_operation(move || version_pin_report(path.as_deref(), maxDepth)).await
+}
@@ -0,0 +1,37 @@
+//! Tauri commands for cancellable scans with progress events
+
+use tauri::{AppHandle, Emitter};
+
+use super::run_operation;
+use crate::error::DevJanitorError;
+use crate::scan_job::{cancel_scan_job, run_scan_job, ScanJobOutcome};
+
+/// Event carrying a `ScanProgress` payload while a scan job runs
+pub const SCAN_PROGRESS_EVENT: &str = "scan-progress";
+
+/// Run a scan as a job, emitting `scan-progress` events until it finishes or is cancelled
+///
+/// `kind` is project_caches, ai_junk, chat_history or tools. The caller picks
+/// `jobId` so it can cancel the job before this command returns. Without a
+/// `path` the configured scan roots are scanned.
+#[tauri::command]
+pub async fn start_scan_job_cmd(
+    app: AppHandle,
+    #[allow(non_snake_case)] jobId: String,
+    kind: String,
+    path: Option<String>,
+    #[allow(non_snake_case)] maxDepth: Option<usize>,
+) -> Result<ScanJobOutcome, DevJanitorError> {
+    run_operation(move || {
+        run_scan_job(&jobId, &kind, path.as_deref(), maxDepth, move |progress| {
+            let _ = app.emit(SCAN_PROGRESS_EVENT, progress);
+        })
+    })
+    .await
+}
+
+/// Cancel a running scan job; returns false if it already finished
+#[tauri::command]
+pub async fn cancel_scan_job_cmd(#[allow(non_snake_case)] jobId: String) -> Result<bool, String> {
+    Ok(cancel_scan_job(&jobId))
+}
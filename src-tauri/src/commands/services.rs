@@ -1,6 +1,8 @@
 //! Tauri commands for service monitoring
 
-use super::run_blocking;
+use super::{run_blocking, run_operation};
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
 use crate::services::{
     get_all_processes, get_common_dev_ports, get_dev_processes, get_ports_in_use, kill_process,
     PortInfo, ProcessInfo,
@@ -20,8 +22,8 @@ pub async fn get_all_processes_cmd() -> Result<Vec<ProcessInfo>, String> {
 
 /// Kill a process by PID
 #[tauri::command]
-pub async fn kill_process_cmd(pid: u32) -> Result<String, String> {
-    run_blocking(move || kill_process(pid)).await?
+pub async fn kill_process_cmd(pid: u32) -> Result<OperationResult, DevJanitorError> {
+    run_operation(move || kill_process(pid)).await
 }
 
 /// Get all ports in use
@@ -0,0 +1,17 @@
+//! Tauri commands for persistent settings
+
+use super::run_operation;
+use crate::error::DevJanitorError;
+use crate::settings::{load_settings, save_settings, Settings};
+
+/// Read the stored settings, or the defaults when none are stored
+#[tauri::command]
+pub async fn get_settings_cmd() -> Result<Settings, DevJanitorError> {
+    run_operation(load_settings).await
+}
+
+/// Validate and store settings; later scans and cleanups use them
+#[tauri::command]
+pub async fn save_settings_cmd(settings: Settings) -> Result<Settings, DevJanitorError> {
+    run_operation(move || save_settings(settings)).await
+}
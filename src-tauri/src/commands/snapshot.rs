@@ -0,0 +1,33 @@
+//! Tauri commands for inventory snapshots
+
+use super::run_operation;
+use crate::error::DevJanitorError;
+use crate::snapshot::{
+    delete_snapshot, diff_snapshots, list_snapshots, take_snapshot, InventoryDiff, SnapshotInfo,
+};
+
+/// Collect the inventory now and store it as a snapshot
+#[tauri::command]
+pub async fn take_snapshot_cmd() -> Result<SnapshotInfo, DevJanitorError> {
+    run_operation(take_snapshot).await
+}
+
+/// Stored snapshots, newest first
+#[tauri::command]
+pub async fn list_snapshots_cmd() -> Result<Vec<SnapshotInfo>, DevJanitorError> {
+    run_operation(list_snapshots).await
+}
+
+#[tauri::command]
+pub async fn delete_snapshot_cmd(id: String) -> Result<(), DevJanitorError> {
+    run_operation(move || delete_snapshot(&id)).await
+}
+
+/// Diff two snapshots; `to` defaults to the current state of the machine
+#[tauri::command]
+pub async fn diff_snapshots_cmd(
+    from: String,
+    to: Option<String>,
+) -> Result<InventoryDiff, DevJanitorError> {
+    run_operation(move || diff_snapshots(&from, to.as_deref())).await
+}
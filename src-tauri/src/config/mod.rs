@@ -1,11 +1,19 @@
 //! Environment configuration diagnostics module for Dev Janitor v2
 //! PATH and Shell configuration analysis
 
+mod toolchain;
+
+pub use toolchain::toolchain_issues;
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::detection::{scan_all_tools, ToolInfo};
+use crate::lifecycle::{Lifecycle, SupportStatus};
+use crate::runtimes::{installed_runtime_versions, RuntimeVersion};
 #[cfg(target_os = "windows")]
 use winreg::{
     enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ},
@@ -49,6 +57,9 @@ pub struct DiagnosisIssue {
     pub category: String,
     pub message: String,
     pub suggestion: Option<String>,
+    /// What was observed, e.g. the paths and versions that disagree
+    #[serde(default)]
+    pub evidence: Vec<String>,
 }
 
 /// Dev-related path patterns
@@ -357,8 +368,25 @@ fn analyze_shell_config(content: &str) -> Vec<String> {
     issues
 }
 
-/// Run full environment diagnosis
+/// Run full environment diagnosis: PATH, shell configs, runtime lifecycles and toolchain mismatches
 pub fn diagnose_environment() -> EnvDiagnosis {
+    let (mut diagnosis, tools, runtimes) = std::thread::scope(|scope| {
+        let tools = scope.spawn(scan_all_tools);
+        let runtimes = scope.spawn(installed_runtime_versions);
+        let diagnosis = diagnose_path_and_shell();
+        (
+            diagnosis,
+            tools.join().unwrap_or_default(),
+            runtimes.join().unwrap_or_default(),
+        )
+    });
+    diagnosis.issues.extend(lifecycle_issues(&tools, &runtimes));
+    diagnosis.issues.extend(toolchain_issues(&tools));
+    diagnosis
+}
+
+/// Diagnose PATH entries and shell configuration files
+pub fn diagnose_path_and_shell() -> EnvDiagnosis {
     let path_entries = analyze_path();
     let shell_configs = get_shell_configs();
 
@@ -374,6 +402,7 @@ pub fn diagnose_environment() -> EnvDiagnosis {
             category: "PATH".to_string(),
             message: format!("{} PATH entries do not exist", non_existent.len()),
             suggestion: Some("Consider removing non-existent paths from PATH".to_string()),
+            evidence: Vec::new(),
         });
     }
 
@@ -388,6 +417,7 @@ pub fn diagnose_environment() -> EnvDiagnosis {
             category: "PATH".to_string(),
             message: format!("{} duplicate PATH entries found", duplicates.len()),
             suggestion: Some("Remove duplicate entries to clean up PATH".to_string()),
+            evidence: Vec::new(),
         });
     }
 
@@ -413,6 +443,7 @@ pub fn diagnose_environment() -> EnvDiagnosis {
             category: "Shell".to_string(),
             message: "No shell configuration files found".to_string(),
             suggestion: None,
+            evidence: Vec::new(),
         });
     }
 
@@ -424,6 +455,7 @@ pub fn diagnose_environment() -> EnvDiagnosis {
                 category: config.name.clone(),
                 message: issue.clone(),
                 suggestion: None,
+                evidence: Vec::new(),
             });
         }
     }
@@ -436,6 +468,87 @@ pub fn diagnose_environment() -> EnvDiagnosis {
     }
 }
 
+/// Warnings for detected tools and installed runtime versions that are past or near end of life
+pub fn lifecycle_issues(tools: &[ToolInfo], runtimes: &[RuntimeVersion]) -> Vec<DiagnosisIssue> {
+    let mut issues = Vec::new();
+
+    for tool in tools {
+        for version in &tool.versions {
+            if let Some(issue) = version.lifecycle.as_ref().and_then(|lifecycle| {
+                lifecycle_issue(&tool.name, &version.version, &version.path, lifecycle, None)
+            }) {
+                issues.push(issue);
+            }
+        }
+    }
+
+    // Versions already reported through the binary found on PATH are not repeated
+    for runtime in runtimes {
+        let on_path = tools.iter().flat_map(|tool| &tool.versions).any(|version| {
+            !version.real_path.is_empty()
+                && Path::new(&version.real_path).starts_with(&runtime.path)
+        });
+        if on_path {
+            continue;
+        }
+        if let Some(issue) = runtime.lifecycle.as_ref().and_then(|lifecycle| {
+            lifecycle_issue(
+                &runtime.runtime,
+                &runtime.version,
+                &runtime.path,
+                lifecycle,
+                Some(&runtime.manager),
+            )
+        }) {
+            issues.push(issue);
+        }
+    }
+
+    issues
+}
+
+fn lifecycle_issue(
+    name: &str,
+    version: &str,
+    path: &str,
+    lifecycle: &Lifecycle,
+    manager: Option<&str>,
+) -> Option<DiagnosisIssue> {
+    let eol = lifecycle.eol.as_deref()?;
+    let (severity, message, suggestion) = match lifecycle.status {
+        SupportStatus::Supported => return None,
+        SupportStatus::NearingEol => (
+            "info",
+            format!(
+                "{} {} reaches end of life on {} ({})",
+                name, version, eol, path
+            ),
+            format!("Plan an upgrade to a newer {} release", name),
+        ),
+        SupportStatus::Eol => (
+            "warning",
+            format!(
+                "{} {} reached end of life on {} ({})",
+                name, version, eol, path
+            ),
+            match manager {
+                Some(manager) => format!(
+                    "Remove it with {} if no project still depends on it",
+                    manager
+                ),
+                None => format!("Upgrade to a supported {} release", name),
+            },
+        ),
+    };
+    Some(DiagnosisIssue {
+        severity: severity.to_string(),
+        category: "Lifecycle".to_string(),
+        message,
+        suggestion: Some(suggestion),
+        evidence: Vec::new(),
+    })
+}
+
 /// Get recommended PATH cleanup
 pub fn get_path_cleanup_suggestions(entries: &[PathEntry]) -> Vec<String> {
     entries
@@ -448,6 +561,8 @@ pub fn get_path_cleanup_suggestions(entries: &[PathEntry]) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::detection::source::InstallSource;
+    use crate::detection::ToolVersion;
     use std::fs;
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -520,4 +635,59 @@ mod tests {
             .iter()
             .any(|issue| issue == "Path does not exist"));
     }
+
+    #[test]
+    fn lifecycle_issues_report_each_end_of_life_install_once() {
+        let eol = || {
+            Some(Lifecycle {
+                status: SupportStatus::Eol,
+                cycle: "16".to_string(),
+                release: "2021-04-20".to_string(),
+                eol: Some("2023-09-11".to_string()),
+            })
+        };
+        let runtime = |version: &str, path: &str| RuntimeVersion {
+            manager: "nvm".to_string(),
+            runtime: "node".to_string(),
+            version: version.to_string(),
+            path: path.to_string(),
+            size: 0,
+            size_display: String::new(),
+            allocated_size: 0,
+            is_default: false,
+            lifecycle: eol(),
+        };
+        let tools = vec![ToolInfo {
+            id: "node".to_string(),
+            name: "Node.js".to_string(),
+            category: "runtime".to_string(),
+            versions: vec![ToolVersion {
+                version: "16.20.2".to_string(),
+                path: "/home/dev/.nvm/versions/node/v16.20.2/bin/node".to_string(),
+                is_active: true,
+                real_path: "/home/dev/.nvm/versions/node/v16.20.2/bin/node".to_string(),
+                shadowed_by: None,
+                lifecycle: eol(),
+                source: InstallSource::Nvm,
+                source_package: Some("v16.20.2".to_string()),
+            }],
+            status: "installed".to_string(),
+            health: Vec::new(),
+        }];
+        let runtimes = vec![
+            runtime("16.20.2", "/home/dev/.nvm/versions/node/v16.20.2"),
+            runtime("16.20", "/home/dev/.nvm/versions/node/v16.20"),
+        ];
+
+        let issues = lifecycle_issues(&tools, &runtimes);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|issue| issue.category == "Lifecycle"));
+        assert!(issues[0]
+            .message
+            .starts_with("Node.js 16.20.2 reached end of life"));
+        assert!(issues[1]
+            .message
+            .contains("/home/dev/.nvm/versions/node/v16.20)"));
+        assert!(issues[1].suggestion.as_deref().unwrap().contains("nvm"));
+    }
 }
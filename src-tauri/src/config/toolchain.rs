@@ -0,0 +1,373 @@
+//! Consistency checks between tools that have to come from the same installation
+//! pip and python, npm and node, cargo and rustc, and `JAVA_HOME` and java
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::DiagnosisIssue;
+use crate::detection::{ToolInfo, ToolVersion};
+use crate::utils::runner::{active_runner, CommandRunner};
+
+#[cfg(target_os = "windows")]
+const PYTHON: &str = "python";
+#[cfg(not(target_os = "windows"))]
+const PYTHON: &str = "python3";
+
+/// Prints the interpreter's `major.minor`, prefix and user site-packages, one per line
+const PYTHON_PROBE: &str = "import site, sys; print('%d.%d' % sys.version_info[:2]); \
+                            print(sys.prefix); print(site.getusersitepackages())";
+
+fn active<'a>(tools: &'a [ToolInfo], id: &str) -> Option<&'a ToolVersion> {
+    tools
+        .iter()
+        .find(|tool| tool.id == id)?
+        .versions
+        .iter()
+        .find(|version| version.is_active)
+}
+
+fn real_path(version: &ToolVersion) -> PathBuf {
+    let path = if version.real_path.is_empty() {
+        &version.path
+    } else {
+        &version.real_path
+    };
+    PathBuf::from(path)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `major.minor` of a version string
+fn release(version: &str) -> String {
+    version.split('.').take(2).collect::<Vec<_>>().join(".")
+}
+
+fn describe(name: &str, version: &ToolVersion) -> String {
+    if version.real_path.is_empty() || version.real_path == version.path {
+        format!("{} {}: {}", name, version.version, version.path)
+    } else {
+        format!(
+            "{} {}: {} -> {}",
+            name, version.version, version.path, version.real_path
+        )
+    }
+}
+
+fn mismatch(message: String, suggestion: String, evidence: Vec<String>) -> DiagnosisIssue {
+    DiagnosisIssue {
+        severity: "warning".to_string(),
+        category: "Toolchain".to_string(),
+        message,
+        suggestion: Some(suggestion),
+        evidence,
+    }
+}
+
+fn stdout_of(runner: &dyn CommandRunner, program: &str, args: &[&str]) -> Option<String> {
+    let output = runner.output(program, args, Duration::from_secs(10)).ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Location and interpreter `major.minor` from `pip 23.2.1 from /usr/lib/python3/dist-packages/pip (python 3.11)`
+fn parse_pip_version(output: &str) -> Option<(&str, &str)> {
+    let (_, rest) = output.split_once(" from ")?;
+    let (location, python) = rest.trim().rsplit_once(" (python ")?;
+    Some((location, python.trim_end_matches(')')))
+}
+
+fn pip_issue(runner: &dyn CommandRunner, tools: &[ToolInfo]) -> Option<DiagnosisIssue> {
+    active(tools, "pip")?;
+    let pip = stdout_of(runner, "pip", &["--version"])?;
+    let (location, pip_python) = parse_pip_version(&pip)?;
+    let probe = stdout_of(runner, PYTHON, &["-c", PYTHON_PROBE])?;
+    let mut lines = probe.lines().map(str::trim);
+    let (python, prefix, user_site) = (lines.next()?, lines.next()?, lines.next()?);
+
+    let location = canonical(Path::new(location));
+    let belongs = pip_python == python
+        && (location.starts_with(canonical(Path::new(prefix)))
+            || location.starts_with(canonical(Path::new(user_site))));
+    if belongs {
+        return None;
+    }
+
+    let message = if pip_python != python {
+        format!(
+            "pip installs packages for Python {}, but {} is Python {}",
+            pip_python, PYTHON, python
+        )
+    } else {
+        format!(
+            "pip belongs to a different Python {} installation than {}",
+            python, PYTHON
+        )
+    };
+    Some(mismatch(
+        message,
+        format!(
+            "Run pip as `{} -m pip` so packages go where {} looks for them",
+            PYTHON, PYTHON
+        ),
+        vec![
+            format!("pip --version: {}", pip),
+            format!("{}: Python {} with prefix {}", PYTHON, python, prefix),
+        ],
+    ))
+}
+
+/// Installation prefix of a node binary: `<prefix>/bin/node`, or the folder of `node.exe`
+fn node_prefix(node: &Path) -> Option<&Path> {
+    let dir = node.parent()?;
+    if dir.file_name().is_some_and(|name| name == "bin") {
+        dir.parent()
+    } else {
+        Some(dir)
+    }
+}
+
+/// Homebrew prefix of a binary inside a keg: `<prefix>/Cellar/<formula>/<version>/...`
+fn homebrew_prefix(path: &Path) -> Option<&Path> {
+    path.ancestors()
+        .find(|dir| dir.file_name().is_some_and(|name| name == "Cellar"))?
+        .parent()
+}
+
+fn npm_issue(tools: &[ToolInfo]) -> Option<DiagnosisIssue> {
+    let (node, npm) = (active(tools, "node")?, active(tools, "npm")?);
+    let node_path = real_path(node);
+    let prefix = node_prefix(&node_path)?;
+    let npm_path = real_path(npm);
+    // The Homebrew node formula installs its npm outside the keg, into the shared prefix
+    let homebrew_npm = homebrew_prefix(&node_path)
+        .is_some_and(|homebrew| npm_path.starts_with(homebrew.join("lib/node_modules/npm")));
+    if npm_path.starts_with(prefix) || homebrew_npm {
+        return None;
+    }
+    Some(mismatch(
+        format!(
+            "npm {} on PATH is not the npm bundled with node {}",
+            npm.version, node.version
+        ),
+        format!(
+            "Put {} first on PATH, or remove the separate npm install",
+            node_path.parent().unwrap_or(prefix).display()
+        ),
+        vec![describe("node", node), describe("npm", npm)],
+    ))
+}
+
+fn cargo_issue(tools: &[ToolInfo]) -> Option<DiagnosisIssue> {
+    let (rustc, cargo) = (active(tools, "rust")?, active(tools, "cargo")?);
+    // cargo is versioned with the toolchain it ships in
+    if rustc.source == cargo.source && release(&rustc.version) == release(&cargo.version) {
+        return None;
+    }
+    Some(mismatch(
+        format!(
+            "cargo {} ({}) and rustc {} ({}) come from different toolchains",
+            cargo.version,
+            cargo.source.as_str(),
+            rustc.version,
+            rustc.source.as_str()
+        ),
+        "Use cargo and rustc from one installation, e.g. both through rustup".to_string(),
+        vec![describe("cargo", cargo), describe("rustc", rustc)],
+    ))
+}
+
+fn java_home_issue(tools: &[ToolInfo], java_home: Option<PathBuf>) -> Option<DiagnosisIssue> {
+    let java_home = java_home.filter(|home| !home.as_os_str().is_empty())?;
+    let java = active(tools, "java")?;
+    let evidence = vec![
+        format!("JAVA_HOME: {}", java_home.display()),
+        describe("java", java),
+    ];
+    if !java_home.is_dir() {
+        return Some(mismatch(
+            format!(
+                "JAVA_HOME points to {}, which does not exist",
+                java_home.display()
+            ),
+            "Point JAVA_HOME at an installed JDK or unset it".to_string(),
+            evidence,
+        ));
+    }
+    if real_path(java).starts_with(canonical(&java_home)) {
+        return None;
+    }
+    Some(mismatch(
+        format!(
+            "JAVA_HOME ({}) is not the Java {} found on PATH",
+            java_home.display(),
+            java.version
+        ),
+        "Point JAVA_HOME at the JDK on PATH, or put $JAVA_HOME/bin first on PATH".to_string(),
+        evidence,
+    ))
+}
+
+/// Tool pairs that come from different installations, with what was observed
+pub fn toolchain_issues(tools: &[ToolInfo]) -> Vec<DiagnosisIssue> {
+    toolchain_issues_with(
+        active_runner().as_ref(),
+        tools,
+        std::env::var_os("JAVA_HOME").map(PathBuf::from),
+    )
+}
+
+fn toolchain_issues_with(
+    runner: &dyn CommandRunner,
+    tools: &[ToolInfo],
+    java_home: Option<PathBuf>,
+) -> Vec<DiagnosisIssue> {
+    [
+        pip_issue(runner, tools),
+        npm_issue(tools),
+        cargo_issue(tools),
+        java_home_issue(tools, java_home),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detection::source::InstallSource;
+    use crate::utils::runner::ReplayRunner;
+
+    fn tool(id: &str, version: &str, path: &str, real_path: &str) -> ToolInfo {
+        tool_from(id, version, path, real_path, InstallSource::Unknown)
+    }
+
+    fn tool_from(
+        id: &str,
+        version: &str,
+        path: &str,
+        real_path: &str,
+        source: InstallSource,
+    ) -> ToolInfo {
+        ToolInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            category: "runtime".to_string(),
+            versions: vec![ToolVersion {
+                version: version.to_string(),
+                path: path.to_string(),
+                is_active: true,
+                real_path: real_path.to_string(),
+                shadowed_by: None,
+                lifecycle: None,
+                source,
+                source_package: None,
+            }],
+            status: "installed".to_string(),
+            health: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn flags_pip_of_another_interpreter() {
+        let tools = [tool("pip", "23.2.1", "/usr/bin/pip", "/usr/bin/pip")];
+        let runner = ReplayRunner::default()
+            .with_output(
+                "pip",
+                &["--version"],
+                "pip 23.2.1 from /usr/lib/python3/dist-packages/pip (python 3.11)\n",
+            )
+            .with_output(
+                PYTHON,
+                &["-c", PYTHON_PROBE],
+                "3.12\n/home/dev/.pyenv/versions/3.12.1\n/home/dev/.local/lib/python3.12/site-packages\n",
+            );
+
+        let issues = toolchain_issues_with(&runner, &tools, None);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("Python 3.11"));
+        assert!(issues[0].evidence[0].contains("/usr/lib/python3/dist-packages/pip"));
+    }
+
+    #[test]
+    fn flags_npm_cargo_and_java_home_mismatches() {
+        let tools = [
+            tool(
+                "node",
+                "20.11.1",
+                "/home/dev/.nvm/versions/node/v20.11.1/bin/node",
+                "/home/dev/.nvm/versions/node/v20.11.1/bin/node",
+            ),
+            tool(
+                "npm",
+                "9.2.0",
+                "/usr/bin/npm",
+                "/usr/share/nodejs/npm/bin/npm-cli.js",
+            ),
+            tool_from(
+                "rust",
+                "1.80.0",
+                "/home/dev/.cargo/bin/rustc",
+                "/home/dev/.cargo/bin/rustc",
+                InstallSource::Rustup,
+            ),
+            tool_from(
+                "cargo",
+                "1.63.0",
+                "/usr/bin/cargo",
+                "/usr/bin/cargo",
+                InstallSource::Apt,
+            ),
+            tool(
+                "java",
+                "17.0.15",
+                "/usr/bin/java",
+                "/usr/lib/jvm/java-17/bin/java",
+            ),
+        ];
+
+        let issues = toolchain_issues_with(
+            &ReplayRunner::default(),
+            &tools,
+            Some(PathBuf::from("/definitely/missing/jdk-21")),
+        );
+        let messages: Vec<&str> = issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(messages.len(), 3, "{messages:?}");
+        assert!(messages[0].starts_with("npm 9.2.0 on PATH is not the npm bundled"));
+        assert!(messages[1].contains("different toolchains"));
+        assert!(messages[2].contains("does not exist"));
+
+        // npm installed with the same Node.js is fine
+        let bundled = [
+            tools[0].clone(),
+            tool(
+                "npm",
+                "10.2.4",
+                "/home/dev/.nvm/versions/node/v20.11.1/bin/npm",
+                "/home/dev/.nvm/versions/node/v20.11.1/lib/node_modules/npm/bin/npm-cli.js",
+            ),
+        ];
+        assert!(toolchain_issues_with(&ReplayRunner::default(), &bundled, None).is_empty());
+
+        let homebrew = [
+            tool(
+                "node",
+                "22.9.0",
+                "/opt/homebrew/bin/node",
+                "/opt/homebrew/Cellar/node/22.9.0_1/bin/node",
+            ),
+            tool(
+                "npm",
+                "10.8.3",
+                "/opt/homebrew/bin/npm",
+                "/opt/homebrew/lib/node_modules/npm/bin/npm-cli.js",
+            ),
+        ];
+        assert!(toolchain_issues_with(&ReplayRunner::default(), &homebrew, None).is_empty());
+    }
+}
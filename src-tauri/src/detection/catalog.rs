@@ -0,0 +1,235 @@
+//! Tool detection catalog
+//! Built-in rules come from `catalog/tools.json`; the `tools` section of the user rules file adds, replaces or disables them
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use super::health::HealthCheck;
+use crate::ai_tools::ai_tools;
+use crate::plan::PlannedCommand;
+use crate::rules::active_rules;
+
+const BUILTIN_CATALOG: &str = include_str!("../../catalog/tools.json");
+
+/// How a tool is detected and removed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ToolRule {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    /// Command names probed in order, e.g. `python`, `python3`, `py`
+    pub commands: Vec<String>,
+    #[serde(default)]
+    pub version_args: Vec<String>,
+    /// First capture group is the version; any dotted number when absent
+    #[serde(default)]
+    pub version_regex: Option<String>,
+    #[serde(default)]
+    pub uninstall: UninstallHint,
+    /// Smoke tests run with the active binary
+    #[serde(default)]
+    pub health: Vec<HealthCheck>,
+}
+
+/// Commands that uninstall a tool, or instructions when it has to be removed by hand
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct UninstallHint {
+    /// Command lines (program then arguments) tried in order until one succeeds
+    pub commands: Vec<Vec<String>>,
+    /// Package removed with `pip uninstall` after `commands`
+    pub pip_package: Option<String>,
+    pub manual: Option<String>,
+    /// Platform-specific replacements for `manual`
+    pub manual_windows: Option<String>,
+    pub manual_macos: Option<String>,
+    pub manual_linux: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogFile {
+    tools: Vec<ToolRule>,
+}
+
+impl UninstallHint {
+    /// Commands to try in order, or the manual instructions for this platform
+    pub fn commands_or_instructions(&self) -> Result<Vec<PlannedCommand>, Option<String>> {
+        let mut commands: Vec<PlannedCommand> = self
+            .commands
+            .iter()
+            .filter_map(|line| {
+                let (program, args) = line.split_first()?;
+                Some(PlannedCommand::owned(program, args.to_vec()))
+            })
+            .collect();
+        if let Some(package) = &self.pip_package {
+            commands.extend(pip_uninstall_commands(package));
+        }
+        if !commands.is_empty() {
+            return Ok(commands);
+        }
+        Err(self.platform_manual().or(self.manual.as_ref()).cloned())
+    }
+
+    #[cfg(target_os = "windows")]
+    fn platform_manual(&self) -> Option<&String> {
+        self.manual_windows.as_ref()
+    }
+
+    #[cfg(target_os = "macos")]
+    fn platform_manual(&self) -> Option<&String> {
+        self.manual_macos.as_ref()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn platform_manual(&self) -> Option<&String> {
+        self.manual_linux.as_ref()
+    }
+}
+
+fn pip_uninstall_commands(package: &str) -> Vec<PlannedCommand> {
+    #[cfg(target_os = "windows")]
+    let interpreters = ["py", "python"];
+    #[cfg(not(target_os = "windows"))]
+    let interpreters = ["python3", "python"];
+
+    let mut commands: Vec<PlannedCommand> = interpreters
+        .iter()
+        .map(|python| PlannedCommand::new(python, &["-m", "pip", "uninstall", "-y", package]))
+        .collect();
+    commands.push(PlannedCommand::new("pip", &["uninstall", "-y", package]));
+    commands
+}
+
+/// Check a rule from the catalog or a rules file
+pub fn validate_tool_rule(rule: &ToolRule) -> Result<(), String> {
+    if rule.id.trim().is_empty() {
+        return Err("id is empty".to_string());
+    }
+    if rule
+        .commands
+        .iter()
+        .all(|command| command.trim().is_empty())
+    {
+        return Err(format!("`{}` has no commands", rule.id));
+    }
+    if rule.uninstall.commands.iter().any(Vec::is_empty) {
+        return Err(format!("`{}` has an empty uninstall command", rule.id));
+    }
+    if rule.health.iter().any(|check| check.args.is_empty()) {
+        return Err(format!(
+            "`{}` has a health check without arguments",
+            rule.id
+        ));
+    }
+    if let Some(pattern) = &rule.version_regex {
+        let regex = Regex::new(pattern)
+            .map_err(|error| format!("invalid version_regex for `{}`: {}", rule.id, error))?;
+        if regex.captures_len() < 2 {
+            return Err(format!(
+                "version_regex for `{}` needs a capture group for the version",
+                rule.id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Rules of the bundled catalog
+fn builtin_rules() -> &'static [ToolRule] {
+    static RULES: OnceLock<Vec<ToolRule>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        serde_json::from_str::<CatalogFile>(BUILTIN_CATALOG)
+            .expect("bundled tool catalog should parse")
+            .tools
+    })
+}
+
+/// Ids of the catalog's tools, which user rules can disable
+pub(crate) fn builtin_tool_ids() -> Vec<&'static str> {
+    builtin_rules()
+        .iter()
+        .map(|rule| rule.id.as_str())
+        .collect()
+}
+
+/// Every rule in effect: the catalog with user additions, replacements and removals, then AI CLIs
+pub fn tool_rules() -> Vec<ToolRule> {
+    let rules = active_rules();
+    let overlay = &rules.tools;
+
+    let mut tool_rules: Vec<ToolRule> = builtin_rules()
+        .iter()
+        .filter(|rule| {
+            !overlay.is_disabled(&rule.id) && !overlay.added.iter().any(|added| added.id == rule.id)
+        })
+        .cloned()
+        .collect();
+    tool_rules.extend(overlay.added.iter().cloned());
+
+    tool_rules.extend(ai_tools().iter().map(|tool| {
+        ToolRule {
+            id: tool.id.to_string(),
+            name: tool.name.to_string(),
+            category: "ai_cli".to_string(),
+            commands: tool
+                .commands
+                .iter()
+                .map(|command| command.to_string())
+                .collect(),
+            version_args: tool
+                .version_args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect(),
+            version_regex: tool.version_regex.map(str::to_string),
+            uninstall: UninstallHint::default(),
+            health: Vec::new(),
+        }
+    }));
+
+    tool_rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detection::health::HealthExpectation;
+
+    #[test]
+    fn bundled_catalog_is_valid() {
+        let rules = builtin_rules();
+        assert!(rules.len() >= 30);
+        for rule in rules {
+            validate_tool_rule(rule).unwrap();
+        }
+
+        let java = rules.iter().find(|rule| rule.id == "java").unwrap();
+        assert_eq!(java.version_args, ["-version"]);
+        let npm = rules.iter().find(|rule| rule.id == "npm").unwrap();
+        assert_eq!(npm.health[0].expect, HealthExpectation::WritableDir);
+        let mut ids: Vec<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), rules.len(), "catalog ids must be unique");
+    }
+
+    #[test]
+    fn rejects_rules_without_a_version_group() {
+        let rule = ToolRule {
+            id: "acme".to_string(),
+            name: "Acme CLI".to_string(),
+            category: "internal".to_string(),
+            commands: vec!["acme".to_string()],
+            version_args: vec!["--version".to_string()],
+            version_regex: Some(r"\d+\.\d+".to_string()),
+            uninstall: UninstallHint::default(),
+            health: Vec::new(),
+        };
+        assert!(validate_tool_rule(&rule)
+            .unwrap_err()
+            .contains("capture group"));
+    }
+}
@@ -0,0 +1,263 @@
+//! Functional health checks of detected tools
+//! A binary that prints its version can still be unusable: a daemon that is not running, a
+//! global prefix only root can write, a Python built without `ssl`. The catalog declares a few
+//! cheap commands per tool that show whether it actually works.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+use super::ToolInfo;
+use crate::utils::runner::CommandRunner;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a check command has to produce to pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthExpectation {
+    /// Exit status 0
+    #[default]
+    Success,
+    /// Exit status 0 and something on stdout
+    Output,
+    /// stdout names an existing directory
+    ExistingDir,
+    /// stdout names a directory the current user can write to
+    WritableDir,
+}
+
+/// A command run with the tool's active binary, e.g. `npm config get prefix`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HealthCheck {
+    /// What the check shows when it passes, e.g. `global prefix is writable`
+    pub name: String,
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub expect: HealthExpectation,
+    /// How to fix the tool when the check fails
+    pub hint: String,
+}
+
+/// Outcome of one health check
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HealthCheckResult {
+    pub name: String,
+    pub passed: bool,
+    /// The checked value on success, what went wrong otherwise
+    pub detail: String,
+    /// Remediation, only for failed checks
+    pub hint: Option<String>,
+}
+
+impl ToolInfo {
+    /// Failed health checks of the active binary
+    pub fn failed_checks(&self) -> impl Iterator<Item = &HealthCheckResult> {
+        self.health.iter().filter(|check| !check.passed)
+    }
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+}
+
+/// Asks the kernel instead of writing a probe file, so ACLs and read-only mounts count and a
+/// check run as root never touches a prefix such as `/usr`
+#[cfg(unix)]
+fn is_writable_dir(dir: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(path) = CString::new(dir.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: `path` is a valid NUL-terminated string that outlives the call
+    unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+}
+
+#[cfg(not(unix))]
+fn is_writable_dir(dir: &Path) -> bool {
+    std::fs::metadata(dir).is_ok_and(|metadata| !metadata.permissions().readonly())
+}
+
+/// Whether a check passed, with the detail to show for it
+fn evaluate(
+    expect: HealthExpectation,
+    success: bool,
+    stdout: &str,
+    stderr: &str,
+) -> (bool, String) {
+    let value = first_line(stdout);
+    if !success {
+        let reason = [first_line(stderr), value]
+            .into_iter()
+            .find(|line| !line.is_empty())
+            .unwrap_or("command failed");
+        return (false, reason.to_string());
+    }
+    match expect {
+        HealthExpectation::Success => (true, value.to_string()),
+        HealthExpectation::Output if value.is_empty() => (false, "no output".to_string()),
+        HealthExpectation::Output => (true, value.to_string()),
+        HealthExpectation::ExistingDir | HealthExpectation::WritableDir if value.is_empty() => {
+            (false, "no directory reported".to_string())
+        }
+        HealthExpectation::ExistingDir | HealthExpectation::WritableDir => {
+            let dir = Path::new(value);
+            if !dir.is_dir() {
+                (false, format!("{} does not exist", value))
+            } else if expect == HealthExpectation::WritableDir && !is_writable_dir(dir) {
+                (false, format!("{} is not writable", value))
+            } else {
+                (true, value.to_string())
+            }
+        }
+    }
+}
+
+fn run_check(runner: &dyn CommandRunner, program: &str, check: &HealthCheck) -> HealthCheckResult {
+    let (passed, detail) = match runner.output_vec(program, &check.args, CHECK_TIMEOUT) {
+        Ok(output) => evaluate(
+            check.expect,
+            output.status.success(),
+            &String::from_utf8_lossy(&output.stdout),
+            &String::from_utf8_lossy(&output.stderr),
+        ),
+        Err(error) => (false, error.to_string()),
+    };
+    HealthCheckResult {
+        name: check.name.clone(),
+        passed,
+        detail,
+        hint: (!passed).then(|| check.hint.clone()),
+    }
+}
+
+/// Run every check against `program`, the tool's active binary
+pub fn check_health(
+    runner: &dyn CommandRunner,
+    program: &str,
+    checks: &[HealthCheck],
+) -> Vec<HealthCheckResult> {
+    checks
+        .iter()
+        .map(|check| run_check(runner, program, check))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::{RecordedCommand, ReplayRunner};
+    use std::fs;
+
+    fn check(name: &str, args: &[&str], expect: HealthExpectation) -> HealthCheck {
+        HealthCheck {
+            name: name.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            expect,
+            hint: format!("fix {}", name),
+        }
+    }
+
+    #[test]
+    fn reports_failures_with_their_hints() {
+        let existing = std::env::temp_dir();
+        let runner = ReplayRunner::new(vec![
+            RecordedCommand {
+                program: "/usr/bin/docker".to_string(),
+                args: vec!["info".to_string()],
+                exit_code: Some(1),
+                stdout: String::new(),
+                stderr: "Cannot connect to the Docker daemon at unix:///var/run/docker.sock\n"
+                    .to_string(),
+                error: None,
+            },
+            RecordedCommand {
+                program: "/usr/bin/docker".to_string(),
+                args: vec!["context".to_string(), "show".to_string()],
+                exit_code: Some(0),
+                stdout: "\n".to_string(),
+                stderr: String::new(),
+                error: None,
+            },
+        ])
+        .with_output(
+            "/usr/bin/docker",
+            &["data-root"],
+            &format!("{}\n", existing.display()),
+        )
+        .with_output(
+            "/usr/bin/docker",
+            &["missing-root"],
+            "/definitely/missing/dir\n",
+        );
+
+        let results = check_health(
+            &runner,
+            "/usr/bin/docker",
+            &[
+                check("daemon is reachable", &["info"], HealthExpectation::Success),
+                check(
+                    "context is set",
+                    &["context", "show"],
+                    HealthExpectation::Output,
+                ),
+                check(
+                    "data root exists",
+                    &["data-root"],
+                    HealthExpectation::ExistingDir,
+                ),
+                check(
+                    "cache exists",
+                    &["missing-root"],
+                    HealthExpectation::ExistingDir,
+                ),
+                check("not recorded", &["version"], HealthExpectation::Success),
+            ],
+        );
+
+        assert!(!results[0].passed);
+        assert!(results[0]
+            .detail
+            .starts_with("Cannot connect to the Docker daemon"));
+        assert_eq!(results[0].hint.as_deref(), Some("fix daemon is reachable"));
+        assert_eq!(results[1].detail, "no output");
+        assert!(results[2].passed);
+        assert_eq!(results[2].hint, None);
+        assert_eq!(results[3].detail, "/definitely/missing/dir does not exist");
+        assert!(!results[4].passed);
+    }
+
+    #[test]
+    fn writable_directory_check_creates_nothing() {
+        let dir = std::env::temp_dir().join(format!("dev-janitor-health-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let value = dir.to_string_lossy().to_string();
+
+        assert_eq!(
+            evaluate(HealthExpectation::WritableDir, true, &value, ""),
+            (true, value.clone())
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        let mut permissions = fs::metadata(&dir).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&dir, permissions.clone()).unwrap();
+        // Root passes access checks regardless of permission bits
+        if fs::File::create(dir.join("probe")).is_err() {
+            assert_eq!(
+                evaluate(HealthExpectation::WritableDir, true, &value, ""),
+                (false, format!("{} is not writable", value))
+            );
+        }
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        fs::set_permissions(&dir, permissions).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
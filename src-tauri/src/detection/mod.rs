@@ -1,14 +1,26 @@
 //! Tool detection engine for Dev Janitor v2
 //! Supports 39+ development tools with multi-version detection
 
+pub mod catalog;
+pub mod health;
+pub mod source;
+pub mod system_package;
+pub mod uninstall;
+
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use crate::ai_tools::ai_tools;
-use crate::utils::command::command_output_with_timeout;
+use crate::lifecycle::{self, Lifecycle};
+use crate::scan_job::ScanReporter;
+use crate::utils::runner::active_runner;
+use catalog::{tool_rules, ToolRule};
+use health::{check_health, HealthCheckResult};
+use source::{attribute_tools, InstallSource};
 
 /// Represents a detected tool version
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +28,21 @@ pub struct ToolVersion {
     pub version: String,
     pub path: String,
     pub is_active: bool,
+    /// `path` with symlinks resolved, i.e. where the tool is really installed
+    #[serde(default)]
+    pub real_path: String,
+    /// Binary that wins the PATH lookup over this one
+    #[serde(default)]
+    pub shadowed_by: Option<String>,
+    /// Support state from the bundled lifecycle dataset, for runtimes it covers
+    #[serde(default)]
+    pub lifecycle: Option<Lifecycle>,
+    /// How the binary was installed
+    #[serde(default)]
+    pub source: InstallSource,
+    /// Package, formula, toolchain or version directory within `source`
+    #[serde(default)]
+    pub source_package: Option<String>,
 }
 
 /// Represents a detected development tool
@@ -25,310 +52,17 @@ pub struct ToolInfo {
     pub name: String,
     pub category: String,
     pub versions: Vec<ToolVersion>,
-    pub status: String, // "installed", "not_in_path", "multiple_versions"
-}
-
-/// Tool detection rule
-#[derive(Debug, Clone)]
-struct ToolRule {
-    id: &'static str,
-    name: &'static str,
-    category: &'static str,
-    commands: &'static [&'static str],
-    version_args: &'static [&'static str],
-    version_regex: Option<&'static str>,
-}
-
-/// Get all tool detection rules
-fn get_tool_rules() -> Vec<ToolRule> {
-    let mut rules = vec![
-        // === Runtimes ===
-        ToolRule {
-            id: "node",
-            name: "Node.js",
-            category: "runtime",
-            commands: &["node"],
-            version_args: &["--version"],
-            version_regex: Some(r"v?(\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "python",
-            name: "Python",
-            category: "runtime",
-            commands: &["python", "python3", "py"],
-            version_args: &["--version"],
-            version_regex: Some(r"Python (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "java",
-            name: "Java",
-            category: "runtime",
-            commands: &["java"],
-            version_args: &["-version"],
-            version_regex: Some(r#"version "(\d+[\.\d+]*)""#),
-        },
-        ToolRule {
-            id: "go",
-            name: "Go",
-            category: "runtime",
-            commands: &["go"],
-            version_args: &["version"],
-            version_regex: Some(r"go(\d+\.\d+\.?\d*)"),
-        },
-        ToolRule {
-            id: "rust",
-            name: "Rust",
-            category: "runtime",
-            commands: &["rustc"],
-            version_args: &["--version"],
-            version_regex: Some(r"rustc (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "ruby",
-            name: "Ruby",
-            category: "runtime",
-            commands: &["ruby"],
-            version_args: &["--version"],
-            version_regex: Some(r"ruby (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "php",
-            name: "PHP",
-            category: "runtime",
-            commands: &["php"],
-            version_args: &["--version"],
-            version_regex: Some(r"PHP (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "dotnet",
-            name: ".NET",
-            category: "runtime",
-            commands: &["dotnet"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+\.?\d*)"),
-        },
-        ToolRule {
-            id: "deno",
-            name: "Deno",
-            category: "runtime",
-            commands: &["deno"],
-            version_args: &["--version"],
-            version_regex: Some(r"deno (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "bun",
-            name: "Bun",
-            category: "runtime",
-            commands: &["bun"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+\.\d+)"),
-        },
-        // === Package Managers ===
-        ToolRule {
-            id: "npm",
-            name: "npm",
-            category: "package_manager",
-            commands: &["npm"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "pnpm",
-            name: "pnpm",
-            category: "package_manager",
-            commands: &["pnpm"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "yarn",
-            name: "Yarn",
-            category: "package_manager",
-            commands: &["yarn"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "pip",
-            name: "pip",
-            category: "package_manager",
-            commands: &["pip", "pip3"],
-            version_args: &["--version"],
-            version_regex: Some(r"pip (\d+\.\d+\.?\d*)"),
-        },
-        ToolRule {
-            id: "cargo",
-            name: "Cargo",
-            category: "package_manager",
-            commands: &["cargo"],
-            version_args: &["--version"],
-            version_regex: Some(r"cargo (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "composer",
-            name: "Composer",
-            category: "package_manager",
-            commands: &["composer"],
-            version_args: &["--version"],
-            version_regex: Some(r"Composer version (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "maven",
-            name: "Maven",
-            category: "package_manager",
-            commands: &["mvn"],
-            version_args: &["--version"],
-            version_regex: Some(r"Apache Maven (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "gradle",
-            name: "Gradle",
-            category: "package_manager",
-            commands: &["gradle"],
-            version_args: &["--version"],
-            version_regex: Some(r"Gradle (\d+\.\d+\.?\d*)"),
-        },
-        ToolRule {
-            id: "uv",
-            name: "uv",
-            category: "package_manager",
-            commands: &["uv"],
-            version_args: &["--version"],
-            version_regex: Some(r"uv (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "pipx",
-            name: "pipx",
-            category: "package_manager",
-            commands: &["pipx"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "poetry",
-            name: "Poetry",
-            category: "package_manager",
-            commands: &["poetry"],
-            version_args: &["--version"],
-            version_regex: Some(r"Poetry \(version (\d+\.\d+\.\d+)\)"),
-        },
-        // === Version Managers ===
-        ToolRule {
-            id: "nvm",
-            name: "nvm",
-            category: "version_manager",
-            commands: &["nvm"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "pyenv",
-            name: "pyenv",
-            category: "version_manager",
-            commands: &["pyenv"],
-            version_args: &["--version"],
-            version_regex: Some(r"pyenv (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "rustup",
-            name: "rustup",
-            category: "version_manager",
-            commands: &["rustup"],
-            version_args: &["--version"],
-            version_regex: Some(r"rustup (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "sdkman",
-            name: "SDKMAN",
-            category: "version_manager",
-            commands: &["sdk"],
-            version_args: &["version"],
-            version_regex: Some(r"(\d+\.\d+\.\d+)"),
-        },
-        // === Build Tools ===
-        ToolRule {
-            id: "cmake",
-            name: "CMake",
-            category: "build_tool",
-            commands: &["cmake"],
-            version_args: &["--version"],
-            version_regex: Some(r"cmake version (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "make",
-            name: "Make",
-            category: "build_tool",
-            commands: &["make"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "ninja",
-            name: "Ninja",
-            category: "build_tool",
-            commands: &["ninja"],
-            version_args: &["--version"],
-            version_regex: Some(r"(\d+\.\d+\.\d+)"),
-        },
-        // === Version Control ===
-        ToolRule {
-            id: "git",
-            name: "Git",
-            category: "version_control",
-            commands: &["git"],
-            version_args: &["--version"],
-            version_regex: Some(r"git version (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "svn",
-            name: "SVN",
-            category: "version_control",
-            commands: &["svn"],
-            version_args: &["--version"],
-            version_regex: Some(r"svn, version (\d+\.\d+\.\d+)"),
-        },
-        // === Containers ===
-        ToolRule {
-            id: "docker",
-            name: "Docker",
-            category: "container",
-            commands: &["docker"],
-            version_args: &["--version"],
-            version_regex: Some(r"Docker version (\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "kubectl",
-            name: "kubectl",
-            category: "container",
-            commands: &["kubectl"],
-            version_args: &["version", "--client", "--output=yaml"],
-            version_regex: Some(r"gitVersion:\s*v(\d+\.\d+\.\d+)"),
-        },
-        ToolRule {
-            id: "podman",
-            name: "Podman",
-            category: "container",
-            commands: &["podman"],
-            version_args: &["--version"],
-            version_regex: Some(r"podman version (\d+\.\d+\.\d+)"),
-        },
-    ];
-
-    rules.extend(ai_tools().iter().map(|tool| ToolRule {
-        id: tool.id,
-        name: tool.name,
-        category: "ai_cli",
-        commands: tool.commands,
-        version_args: tool.version_args,
-        version_regex: tool.version_regex,
-    }));
-
-    rules
+    pub status: String, // "installed", "not_in_path", "multiple_versions", "path_conflict"
+    /// Results of the catalog's health checks against the active binary
+    #[serde(default)]
+    pub health: Vec<HealthCheckResult>,
 }
 
 /// Execute a command and capture output
-fn execute_command(cmd: &str, args: &[&str]) -> Option<(String, String)> {
-    let output = command_output_with_timeout(cmd, args, Duration::from_secs(6)).ok()?;
+fn execute_command(cmd: &str, args: &[String]) -> Option<(String, String)> {
+    let output = active_runner()
+        .output_vec(cmd, args, Duration::from_secs(6))
+        .ok()?;
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -336,9 +70,26 @@ fn execute_command(cmd: &str, args: &[&str]) -> Option<(String, String)> {
     Some((stdout, stderr))
 }
 
-/// Find command path using which
-fn find_command_path(cmd: &str) -> Option<PathBuf> {
-    which::which(cmd).ok()
+/// Every distinct binary named `cmd` along PATH in lookup order, with symlinks resolved
+///
+/// PATH entries that lead to the same file (e.g. `/bin` and `/usr/bin` on merged-usr
+/// systems) count once.
+fn find_command_paths(cmd: &str) -> Vec<(PathBuf, PathBuf)> {
+    find_command_paths_in(cmd, std::env::var_os("PATH"))
+}
+
+fn find_command_paths_in(cmd: &str, path_var: Option<OsString>) -> Vec<(PathBuf, PathBuf)> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let Ok(matches) = which::which_in_all(cmd, path_var, cwd) else {
+        return Vec::new();
+    };
+    let mut seen = HashSet::new();
+    matches
+        .filter_map(|path| {
+            let real_path = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            seen.insert(real_path.clone()).then_some((path, real_path))
+        })
+        .collect()
 }
 
 /// Extract version from output using regex
@@ -357,36 +108,65 @@ fn extract_version(output: &str, pattern: Option<&str>) -> Option<String> {
     }
 }
 
+/// Run a binary with the rule's version arguments and extract the version
+fn probe_version(program: &str, rule: &ToolRule) -> Option<String> {
+    let (stdout, stderr) = execute_command(program, &rule.version_args)?;
+    let output = if stdout.trim().is_empty() {
+        &stderr
+    } else {
+        &stdout
+    };
+    Some(
+        extract_version(output, rule.version_regex.as_deref())
+            .unwrap_or_else(|| "unknown".to_string()),
+    )
+}
+
+fn tool_status(versions: &[ToolVersion]) -> &'static str {
+    if versions.iter().any(|version| version.shadowed_by.is_some()) {
+        "path_conflict"
+    } else if versions.len() > 1 {
+        "multiple_versions"
+    } else {
+        "installed"
+    }
+}
+
 /// Detect a single tool
 fn detect_tool(rule: &ToolRule) -> Option<ToolInfo> {
     let mut versions: Vec<ToolVersion> = Vec::new();
-    let mut found_paths: HashMap<String, bool> = HashMap::new();
-
-    for cmd in rule.commands {
-        // Try to find the command
-        if let Some(path) = find_command_path(cmd) {
-            let path_str = path.to_string_lossy().to_string();
-
-            // Skip if we already found this path
-            if found_paths.contains_key(&path_str) {
+    let mut found_paths: HashSet<PathBuf> = HashSet::new();
+
+    for cmd in &rule.commands {
+        let matches = find_command_paths(cmd);
+        let Some((winner, _)) = matches.first() else {
+            continue;
+        };
+        let winner = winner.to_string_lossy().to_string();
+
+        for (index, (path, real_path)) in matches.iter().enumerate() {
+            // Another command name may already have led to this binary (python -> python3)
+            if !found_paths.insert(real_path.clone()) {
                 continue;
             }
-            found_paths.insert(path_str.clone(), true);
-
-            // Try to get version
-            if let Some((stdout, stderr)) = execute_command(cmd, rule.version_args) {
-                let output = if stdout.trim().is_empty() {
-                    &stderr
-                } else {
-                    &stdout
-                };
-                let version = extract_version(output, rule.version_regex)
-                    .unwrap_or_else(|| "unknown".to_string());
 
+            // The winner runs by name as PATH would pick it; shadowed binaries need their full path
+            let path_str = path.to_string_lossy().to_string();
+            let program = if index == 0 {
+                cmd.as_str()
+            } else {
+                path_str.as_str()
+            };
+            if let Some(version) = probe_version(program, rule) {
                 versions.push(ToolVersion {
+                    lifecycle: lifecycle::assess(&rule.id, &version),
                     version,
+                    is_active: versions.is_empty() && index == 0, // First found is active
+                    real_path: real_path.to_string_lossy().to_string(),
+                    shadowed_by: (index > 0).then(|| winner.clone()),
                     path: path_str,
-                    is_active: versions.is_empty(), // First found is active
+                    source: InstallSource::Unknown,
+                    source_package: None,
                 });
             }
         }
@@ -395,31 +175,24 @@ fn detect_tool(rule: &ToolRule) -> Option<ToolInfo> {
     // Check common installation paths for multiple versions
     #[cfg(target_os = "windows")]
     {
-        let extra_paths = get_windows_extra_paths(rule.id);
+        let extra_paths = get_windows_extra_paths(&rule.id);
         for extra_path in extra_paths {
-            if extra_path.exists()
-                && !found_paths.contains_key(&extra_path.to_string_lossy().to_string())
-            {
-                let cmd = extra_path.join(rule.commands[0]);
-                if cmd.exists() {
-                    if let Some((stdout, stderr)) =
-                        execute_command(&cmd.to_string_lossy(), rule.version_args)
-                    {
-                        let output = if stdout.trim().is_empty() {
-                            &stderr
-                        } else {
-                            &stdout
-                        };
-                        let version = extract_version(output, rule.version_regex)
-                            .unwrap_or_else(|| "unknown".to_string());
-
-                        versions.push(ToolVersion {
-                            version,
-                            path: extra_path.to_string_lossy().to_string(),
-                            is_active: false,
-                        });
-                    }
-                }
+            let cmd = extra_path.join(&rule.commands[0]);
+            let real_path = fs::canonicalize(&cmd).unwrap_or_else(|_| cmd.clone());
+            if !cmd.exists() || !found_paths.insert(real_path.clone()) {
+                continue;
+            }
+            if let Some(version) = probe_version(&cmd.to_string_lossy(), rule) {
+                versions.push(ToolVersion {
+                    lifecycle: lifecycle::assess(&rule.id, &version),
+                    version,
+                    path: extra_path.to_string_lossy().to_string(),
+                    is_active: false,
+                    real_path: real_path.to_string_lossy().to_string(),
+                    shadowed_by: None,
+                    source: InstallSource::Unknown,
+                    source_package: None,
+                });
             }
         }
     }
@@ -428,18 +201,20 @@ fn detect_tool(rule: &ToolRule) -> Option<ToolInfo> {
         return None;
     }
 
-    let status = if versions.len() > 1 {
-        "multiple_versions".to_string()
-    } else {
-        "installed".to_string()
-    };
+    let status = tool_status(&versions).to_string();
+    let health = versions
+        .iter()
+        .find(|version| version.is_active)
+        .map(|active| check_health(active_runner().as_ref(), &active.path, &rule.health))
+        .unwrap_or_default();
 
     Some(ToolInfo {
-        id: rule.id.to_string(),
-        name: rule.name.to_string(),
-        category: rule.category.to_string(),
+        id: rule.id.clone(),
+        name: rule.name.clone(),
+        category: rule.category.clone(),
         versions,
         status,
+        health,
     })
 }
 
@@ -516,10 +291,32 @@ fn get_windows_extra_paths(tool_id: &str) -> Vec<PathBuf> {
 
 /// Scan for all development tools
 pub fn scan_all_tools() -> Vec<ToolInfo> {
-    let rules = get_tool_rules();
+    scan_all_tools_with_progress(&ScanReporter::silent())
+}
+
+/// `scan_all_tools`, reporting each tool probed and skipping the rest once cancelled
+pub fn scan_all_tools_with_progress(progress: &ScanReporter) -> Vec<ToolInfo> {
+    let rules = tool_rules();
 
     // Use parallel scanning for better performance
-    rules.par_iter().filter_map(detect_tool).collect()
+    let mut tools: Vec<ToolInfo> = rules
+        .par_iter()
+        .filter_map(|rule| {
+            if progress.is_cancelled() {
+                return None;
+            }
+            progress.visit(&rule.id);
+            let tool = detect_tool(rule)?;
+            progress.found(0);
+            Some(tool)
+        })
+        .collect();
+
+    // One package database query for all binaries instead of one per tool
+    if !progress.is_cancelled() {
+        attribute_tools(&mut tools);
+    }
+    tools
 }
 
 #[cfg(test)]
@@ -533,6 +330,57 @@ mod tests {
         assert_eq!(version, Some("20.11.0".to_string()));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn finds_every_distinct_binary_on_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("dev-janitor-path-{nanos}"));
+        let (first, alias, second) = (root.join("first"), root.join("alias"), root.join("second"));
+        for dir in [&first, &second] {
+            fs::create_dir_all(dir).unwrap();
+            let binary = dir.join("dj-probe");
+            fs::write(&binary, "#!/bin/sh\necho v1.0.0\n").unwrap();
+            fs::set_permissions(&binary, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        // A PATH entry that links back to the first directory is not another install
+        std::os::unix::fs::symlink(&first, &alias).unwrap();
+
+        let path_var = std::env::join_paths([&first, &alias, &second]).unwrap();
+        let found = find_command_paths_in("dj-probe", Some(path_var));
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, first.join("dj-probe"));
+        assert_eq!(
+            found[1].1,
+            fs::canonicalize(second.join("dj-probe")).unwrap()
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn shadowed_binaries_mark_a_path_conflict() {
+        let version = |path: &str, shadowed_by: Option<&str>| ToolVersion {
+            version: "20.11.1".to_string(),
+            path: path.to_string(),
+            is_active: shadowed_by.is_none(),
+            real_path: path.to_string(),
+            shadowed_by: shadowed_by.map(str::to_string),
+            lifecycle: None,
+            source: InstallSource::Unknown,
+            source_package: None,
+        };
+        let winner = version("/usr/local/bin/node", None);
+        let shadowed = version("/usr/bin/node", Some("/usr/local/bin/node"));
+
+        assert_eq!(tool_status(std::slice::from_ref(&winner)), "installed");
+        assert_eq!(tool_status(&[winner, shadowed]), "path_conflict");
+    }
+
     #[test]
     fn test_scan_tools() {
         let tools = scan_all_tools();
@@ -544,7 +392,7 @@ mod tests {
 
     #[test]
     fn test_ai_cli_command_rules() {
-        let rules = get_tool_rules();
+        let rules = tool_rules();
         let cursor = rules.iter().find(|rule| rule.id == "cursor").unwrap();
         assert_eq!(cursor.commands, ["cursor-agent", "cursor"]);
 
@@ -0,0 +1,864 @@
+//! Install-source attribution for detected binaries
+//! Decides from the binary's location, and the distribution's package database for system
+//! paths, how a tool was installed and therefore how it has to be removed
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use super::system_package::inspect_package;
+use super::ToolInfo;
+use crate::plan::{quote_arg, PlannedCommand};
+use crate::runtimes::ManagerRoots;
+use crate::utils::paths::{self, env_path};
+use crate::utils::runner::{active_runner, CommandRunner};
+
+/// Binaries rustup links into `$CARGO_HOME/bin`
+const RUSTUP_PROXIES: &[&str] = &[
+    "rustup",
+    "rustc",
+    "rustdoc",
+    "cargo",
+    "rustfmt",
+    "cargo-fmt",
+    "cargo-clippy",
+    "clippy-driver",
+    "rust-analyzer",
+    "rust-gdb",
+    "rust-gdbgui",
+    "rust-lldb",
+];
+
+/// npm packages that ship with Node.js itself rather than being installed globally
+const BUNDLED_NODE_PACKAGES: &[&str] = &["npm", "corepack"];
+
+/// How a binary was installed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallSource {
+    /// Debian package (apt/dpkg)
+    Apt,
+    Rpm,
+    Pacman,
+    /// Homebrew or Linuxbrew formula or cask
+    Homebrew,
+    Nvm,
+    Pyenv,
+    Rustup,
+    Snap,
+    /// Below the `node_modules` of `npm prefix -g`
+    NpmGlobal,
+    /// Below `pnpm root -g`
+    PnpmGlobal,
+    /// `yarn global add`
+    YarnGlobal,
+    /// `bun add -g`
+    BunGlobal,
+    Pipx,
+    UvTool,
+    /// Outside any package manager, e.g. an unpacked tarball in `/usr/local` or `/opt`
+    Manual,
+    #[default]
+    Unknown,
+}
+
+impl InstallSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstallSource::Apt => "apt",
+            InstallSource::Rpm => "rpm",
+            InstallSource::Pacman => "pacman",
+            InstallSource::Homebrew => "homebrew",
+            InstallSource::Nvm => "nvm",
+            InstallSource::Pyenv => "pyenv",
+            InstallSource::Rustup => "rustup",
+            InstallSource::Snap => "snap",
+            InstallSource::NpmGlobal => "npm_global",
+            InstallSource::PnpmGlobal => "pnpm_global",
+            InstallSource::YarnGlobal => "yarn_global",
+            InstallSource::BunGlobal => "bun_global",
+            InstallSource::Pipx => "pipx",
+            InstallSource::UvTool => "uv_tool",
+            InstallSource::Manual => "manual",
+            InstallSource::Unknown => "unknown",
+        }
+    }
+}
+
+/// Install source of a binary and the package it belongs to there
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attribution {
+    pub source: InstallSource,
+    /// Package, formula, snap, toolchain or version directory, when known
+    pub package: Option<String>,
+}
+
+impl Attribution {
+    fn new(source: InstallSource, package: Option<String>) -> Self {
+        Self { source, package }
+    }
+
+    /// Removal matching the install source: commands, or instructions when it needs root
+    /// the settings do not grant or a choice the user has to make. `None` when the source
+    /// says nothing about removal.
+    pub fn removal(&self, path: &str) -> Option<Result<Vec<PlannedCommand>, String>> {
+        let package = self.package.as_deref();
+        let removal = match (self.source, package) {
+            (InstallSource::Apt | InstallSource::Rpm | InstallSource::Pacman, Some(package)) => {
+                inspect_package(active_runner().as_ref(), self.source, package)?.removal(path)
+            }
+            (InstallSource::Homebrew, Some(formula)) => {
+                Ok(vec![PlannedCommand::new("brew", &["uninstall", formula])])
+            }
+            (InstallSource::Nvm, Some(version)) => Ok(vec![nvm_uninstall_command(version)]),
+            (InstallSource::Pyenv, Some(version)) => Ok(vec![PlannedCommand::new(
+                "pyenv",
+                &["uninstall", "-f", version],
+            )]),
+            (InstallSource::Pyenv, None) => Err(format!(
+                "{} is a pyenv shim. Remove the version it selects with: pyenv uninstall <version>",
+                path
+            )),
+            (InstallSource::Rustup, Some(toolchain)) => Ok(vec![PlannedCommand::new(
+                "rustup",
+                &["toolchain", "uninstall", toolchain],
+            )]),
+            (InstallSource::Snap, Some(snap)) => {
+                Ok(vec![PlannedCommand::new("snap", &["remove", snap])])
+            }
+            (InstallSource::NpmGlobal, Some(package)) => Ok(vec![PlannedCommand::new(
+                "npm",
+                &["uninstall", "-g", package],
+            )]),
+            (InstallSource::PnpmGlobal, Some(package)) => Ok(vec![PlannedCommand::new(
+                "pnpm",
+                &["remove", "-g", package],
+            )]),
+            (InstallSource::YarnGlobal, Some(package)) => Ok(vec![PlannedCommand::new(
+                "yarn",
+                &["global", "remove", package],
+            )]),
+            (InstallSource::BunGlobal, Some(package)) => {
+                Ok(vec![PlannedCommand::new("bun", &["remove", "-g", package])])
+            }
+            (InstallSource::Pipx, Some(package)) => {
+                Ok(vec![PlannedCommand::new("pipx", &["uninstall", package])])
+            }
+            (InstallSource::UvTool, Some(package)) => Ok(vec![PlannedCommand::new(
+                "uv",
+                &["tool", "uninstall", package],
+            )]),
+            _ => return None,
+        };
+        Some(removal)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn nvm_uninstall_command(version: &str) -> PlannedCommand {
+    PlannedCommand::new("nvm", &["uninstall", version])
+}
+
+/// nvm is a shell function, so its script has to be sourced first
+#[cfg(not(target_os = "windows"))]
+fn nvm_uninstall_command(version: &str) -> PlannedCommand {
+    let nvm_sh = match ManagerRoots::detect().nvm {
+        Some(nvm_dir) => quote_arg(&nvm_dir.join("nvm.sh").to_string_lossy()),
+        None => "\"$HOME/.nvm/nvm.sh\"".to_string(),
+    };
+    PlannedCommand::shell(&format!(
+        ". {} && nvm uninstall {}",
+        nvm_sh,
+        quote_arg(version)
+    ))
+}
+
+/// Install locations of the package managers and tool installers attribution knows
+#[derive(Debug, Clone, Default)]
+struct SourceRoots {
+    managers: ManagerRoots,
+    cargo_home: Option<PathBuf>,
+    /// Global `node_modules` directories of npm and pnpm, as the tools report them
+    npm_global: Vec<PathBuf>,
+    pnpm_global: Vec<PathBuf>,
+    /// Global package directories of Yarn and Bun, which keep their own `node_modules`
+    yarn_global: Vec<PathBuf>,
+    bun_global: Vec<PathBuf>,
+    pipx: Vec<PathBuf>,
+    uv_tools: Vec<PathBuf>,
+    homebrew: Vec<PathBuf>,
+}
+
+/// Path printed by a command such as `pnpm root -g`, resolved like the binaries it is compared to
+fn command_path(runner: &dyn CommandRunner, program: &str, args: &[&str]) -> Option<PathBuf> {
+    let output = runner
+        .output(program, args, Duration::from_secs(10))
+        .ok()
+        .filter(|output| output.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let path = PathBuf::from(stdout.lines().next()?.trim());
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+    Some(fs::canonicalize(&path).unwrap_or(path))
+}
+
+/// Where npm puts global packages below its prefix
+fn npm_global_modules(prefix: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        prefix.join("node_modules")
+    } else {
+        prefix.join("lib/node_modules")
+    }
+}
+
+impl SourceRoots {
+    fn detect(runner: &dyn CommandRunner) -> Self {
+        let home = paths::home_dir();
+        let in_home = |relative: &str| home.as_ref().map(|home| home.join(relative));
+        let data_home = env_path("XDG_DATA_HOME").or_else(|| in_home(".local/share"));
+        let config_home = env_path("XDG_CONFIG_HOME").or_else(|| in_home(".config"));
+
+        let yarn_global = [
+            config_home
+                .as_ref()
+                .map(|config| config.join("yarn/global")),
+            in_home(".config/yarn/global"),
+            env_path("LOCALAPPDATA").map(|local| local.join("Yarn/Data/global")),
+        ];
+        let bun_global = [
+            env_path("BUN_INSTALL").map(|bun| bun.join("install/global")),
+            in_home(".bun/install/global"),
+        ];
+
+        let pipx = [
+            env_path("PIPX_HOME"),
+            in_home(".local/pipx"),
+            data_home.as_ref().map(|data| data.join("pipx")),
+            paths::user_data_base().map(|base| base.join("pipx")),
+        ];
+        let uv_tools = [
+            env_path("UV_TOOL_DIR"),
+            data_home.as_ref().map(|data| data.join("uv/tools")),
+            env_path("APPDATA").map(|appdata| appdata.join("uv/data/tools")),
+        ];
+        let homebrew = [
+            env_path("HOMEBREW_PREFIX"),
+            Some(PathBuf::from("/opt/homebrew")),
+            Some(PathBuf::from("/usr/local")),
+            Some(PathBuf::from("/home/linuxbrew/.linuxbrew")),
+            in_home(".linuxbrew"),
+        ];
+
+        Self {
+            managers: ManagerRoots::detect(),
+            cargo_home: env_path("CARGO_HOME").or_else(|| in_home(".cargo")),
+            npm_global: command_path(runner, "npm", &["prefix", "-g"])
+                .map(|prefix| npm_global_modules(&prefix))
+                .into_iter()
+                .collect(),
+            pnpm_global: command_path(runner, "pnpm", &["root", "-g"])
+                .into_iter()
+                .collect(),
+            yarn_global: yarn_global.into_iter().flatten().collect(),
+            bun_global: bun_global.into_iter().flatten().collect(),
+            pipx: pipx.into_iter().flatten().collect(),
+            uv_tools: uv_tools.into_iter().flatten().collect(),
+            homebrew: homebrew.into_iter().flatten().collect(),
+        }
+    }
+}
+
+/// First path component below `root`, when `path` lies inside it
+fn entry_below(path: &Path, root: &Path) -> Option<String> {
+    match path.strip_prefix(root).ok()?.components().next()? {
+        Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+        _ => None,
+    }
+}
+
+/// Package directory after the first `node_modules` component, keeping npm scopes
+fn node_modules_package(path: &Path) -> Option<String> {
+    let mut components = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .skip_while(|name| name != "node_modules")
+        .skip(1);
+    let name = components.next()?;
+    if name.starts_with('@') {
+        Some(format!("{}/{}", name, components.next()?))
+    } else {
+        Some(name)
+    }
+}
+
+/// Package directory directly below a global `node_modules` directory, keeping npm scopes
+fn global_modules_package(path: &Path, modules: &Path) -> Option<String> {
+    let rest = path.strip_prefix(modules).ok()?;
+    let mut names = rest
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string());
+    let name = names.next()?;
+    if name == ".pnpm" {
+        // pnpm's virtual store: `.pnpm/<name>@<version>/node_modules/<name>`
+        node_modules_package(rest)
+    } else if name.starts_with('@') {
+        Some(format!("{}/{}", name, names.next()?))
+    } else {
+        Some(name)
+    }
+}
+
+/// Paths owned by the distribution's package manager
+fn is_system_path(path: &Path) -> bool {
+    cfg!(target_os = "linux")
+        && !path.starts_with("/usr/local")
+        && ["/usr", "/bin", "/sbin", "/lib"]
+            .iter()
+            .any(|root| path.starts_with(root))
+}
+
+/// Attribution from the location alone; `None` for system paths that need the package database
+fn classify(path: &Path, real_path: &Path, roots: &SourceRoots) -> Option<Attribution> {
+    use InstallSource::*;
+
+    let managers = &roots.managers;
+    // Yarn and Bun globals live in node_modules directories too, but npm cannot remove them
+    let global_package = |globals: &[PathBuf]| {
+        globals
+            .iter()
+            .find(|global| real_path.starts_with(global))
+            .and_then(|global| node_modules_package(real_path.strip_prefix(global).ok()?))
+    };
+    if let Some(package) = global_package(&roots.yarn_global) {
+        return Some(Attribution::new(YarnGlobal, Some(package)));
+    }
+    if let Some(package) = global_package(&roots.bun_global) {
+        return Some(Attribution::new(BunGlobal, Some(package)));
+    }
+    let modules_package = |modules: &[PathBuf]| {
+        modules
+            .iter()
+            .find_map(|modules| global_modules_package(real_path, modules))
+    };
+    if let Some(package) = modules_package(&roots.pnpm_global) {
+        return Some(Attribution::new(PnpmGlobal, Some(package)));
+    }
+    let bundled = |package: &String| BUNDLED_NODE_PACKAGES.contains(&package.as_str());
+    if let Some(package) = modules_package(&roots.npm_global).filter(|package| !bundled(package)) {
+        return Some(Attribution::new(NpmGlobal, Some(package)));
+    }
+    // Project dependencies and globals of other Node.js installs are not npm's to remove
+    if node_modules_package(real_path).is_some_and(|package| !bundled(&package)) {
+        return Some(Attribution::default());
+    }
+    if let Some(nvm) = &managers.nvm {
+        if let Some(version) = entry_below(real_path, &nvm.join("versions/node")) {
+            return Some(Attribution::new(Nvm, Some(version)));
+        }
+    }
+    if let Some(nvm) = &managers.nvm_windows {
+        if let Some(version) = entry_below(real_path, nvm) {
+            return Some(Attribution::new(Nvm, Some(version)));
+        }
+    }
+    if let Some(pyenv) = &managers.pyenv {
+        if let Some(version) = entry_below(real_path, &pyenv.join("versions")) {
+            return Some(Attribution::new(Pyenv, Some(version)));
+        }
+        if path.starts_with(pyenv.join("shims")) {
+            return Some(Attribution::new(Pyenv, None));
+        }
+    }
+    if let Some(rustup) = &managers.rustup {
+        if let Some(toolchain) = entry_below(real_path, &rustup.join("toolchains")) {
+            return Some(Attribution::new(Rustup, Some(toolchain)));
+        }
+    }
+    if let Some(cargo_home) = &roots.cargo_home {
+        let proxy = real_path
+            .file_stem()
+            .is_some_and(|stem| RUSTUP_PROXIES.iter().any(|proxy| stem == *proxy));
+        if proxy && real_path.starts_with(cargo_home.join("bin")) {
+            return Some(Attribution::new(Rustup, None));
+        }
+    }
+    for pipx in &roots.pipx {
+        if let Some(package) = entry_below(real_path, &pipx.join("venvs")) {
+            return Some(Attribution::new(Pipx, Some(package)));
+        }
+    }
+    for tools in &roots.uv_tools {
+        if let Some(package) = entry_below(real_path, tools) {
+            return Some(Attribution::new(UvTool, Some(package)));
+        }
+    }
+    for prefix in &roots.homebrew {
+        for kegs in ["Cellar", "Caskroom"] {
+            if let Some(formula) = entry_below(real_path, &prefix.join(kegs)) {
+                return Some(Attribution::new(Homebrew, Some(formula)));
+            }
+        }
+    }
+    // Snap commands are links to /usr/bin/snap named after the snap (`snap.app` for extra apps)
+    if let Some(name) = entry_below(path, Path::new("/snap/bin")) {
+        let snap = name.split('.').next().unwrap_or(&name).to_string();
+        return Some(Attribution::new(Snap, Some(snap)));
+    }
+    if let Some(snap) = entry_below(real_path, Path::new("/snap")) {
+        return Some(Attribution::new(Snap, Some(snap)));
+    }
+
+    if is_system_path(real_path) || is_system_path(path) {
+        return None;
+    }
+    if ["/usr/local", "/opt"]
+        .iter()
+        .any(|root| real_path.starts_with(root))
+    {
+        return Some(Attribution::new(Manual, None));
+    }
+    Some(Attribution::default())
+}
+
+/// Paths to ask the package database about; merged-usr systems may list `/bin/x` for `/usr/bin/x`
+fn owner_queries(path: &Path, real_path: &Path) -> Vec<PathBuf> {
+    let mut queries = vec![real_path.to_path_buf()];
+    for candidate in [path, real_path] {
+        if let Ok(rest) = candidate.strip_prefix("/usr") {
+            queries.push(Path::new("/").join(rest));
+        }
+        queries.push(candidate.to_path_buf());
+    }
+    let mut seen = std::collections::HashSet::new();
+    queries.retain(|query| seen.insert(query.clone()));
+    queries
+}
+
+/// Owning package of each of `queries`, with the package manager that owns it
+///
+/// Package managers are asked in turn, each about the paths the earlier ones left unowned, as
+/// systems sometimes carry a second package database (dpkg on Fedora, rpm on Debian). `None`
+/// when no package manager could be run.
+fn system_owners(
+    runner: &dyn CommandRunner,
+    queries: &[PathBuf],
+) -> Option<HashMap<PathBuf, (InstallSource, String)>> {
+    if queries.is_empty() {
+        return None;
+    }
+    let mut answered = false;
+    let mut owners = HashMap::new();
+    for source in [
+        InstallSource::Apt,
+        InstallSource::Pacman,
+        InstallSource::Rpm,
+    ] {
+        let pending: Vec<PathBuf> = queries
+            .iter()
+            .filter(|query| !owners.contains_key(*query))
+            .cloned()
+            .collect();
+        if pending.is_empty() {
+            break;
+        }
+        let Some(found) = query_owners(runner, source, &pending) else {
+            continue;
+        };
+        answered = true;
+        owners.extend(
+            found
+                .into_iter()
+                .map(|(path, package)| (path, (source, package))),
+        );
+    }
+    answered.then_some(owners)
+}
+
+/// Owning packages of `queries` in one package database; `None` when its tool cannot be run
+fn query_owners(
+    runner: &dyn CommandRunner,
+    source: InstallSource,
+    queries: &[PathBuf],
+) -> Option<HashMap<PathBuf, String>> {
+    let timeout = Duration::from_secs(20);
+    let with_args = |leading: &[&str]| -> Vec<String> {
+        leading
+            .iter()
+            .map(|arg| arg.to_string())
+            .chain(
+                queries
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string()),
+            )
+            .collect()
+    };
+
+    // Each tool exits non-zero when any path is unowned, but still reports the others
+    let owners = match source {
+        InstallSource::Apt => {
+            let output = runner
+                .output_vec("dpkg", &with_args(&["-S"]), timeout)
+                .ok()?;
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            stdout
+                .lines()
+                .filter(|line| !line.starts_with("diversion "))
+                .filter_map(|line| {
+                    let (packages, path) = line.split_once(": ")?;
+                    let package = packages.split(", ").next()?;
+                    let package = package.split(':').next().unwrap_or(package);
+                    Some((PathBuf::from(path.trim()), package.to_string()))
+                })
+                .collect()
+        }
+        InstallSource::Pacman => {
+            let output = runner
+                .output_vec("pacman", &with_args(&["-Qo"]), timeout)
+                .ok()?;
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            stdout
+                .lines()
+                .filter_map(|line| {
+                    let (path, rest) = line.split_once(" is owned by ")?;
+                    let package = rest.split_whitespace().next()?;
+                    Some((PathBuf::from(path), package.to_string()))
+                })
+                .collect()
+        }
+        InstallSource::Rpm => {
+            let output = runner
+                .output_vec(
+                    "rpm",
+                    &with_args(&["-qf", "--queryformat", "%{NAME}\\n"]),
+                    timeout,
+                )
+                .ok()?;
+            // One line per queried path, in order
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            queries
+                .iter()
+                .zip(stdout.lines())
+                .filter(|(_, line)| !line.contains(' '))
+                .map(|(path, package)| (path.clone(), package.to_string()))
+                .collect()
+        }
+        _ => return None,
+    };
+    Some(owners)
+}
+
+/// Attribution of every (path, real path) pair, with one package database query for all
+fn attribute_all(
+    runner: &dyn CommandRunner,
+    roots: &SourceRoots,
+    binaries: &[(PathBuf, PathBuf)],
+) -> Vec<Attribution> {
+    let classified: Vec<Option<Attribution>> = binaries
+        .iter()
+        .map(|(path, real_path)| {
+            if !real_path.exists() {
+                return Some(Attribution::default());
+            }
+            classify(path, real_path, roots)
+        })
+        .collect();
+
+    let queries: Vec<Vec<PathBuf>> = binaries
+        .iter()
+        .zip(&classified)
+        .map(|((path, real_path), attribution)| match attribution {
+            Some(_) => Vec::new(),
+            None => owner_queries(path, real_path),
+        })
+        .collect();
+    let all_queries: Vec<PathBuf> = queries.iter().flatten().cloned().collect();
+    let owners = system_owners(runner, &all_queries);
+
+    classified
+        .into_iter()
+        .zip(queries)
+        .map(|(attribution, queries)| {
+            attribution.unwrap_or_else(|| match &owners {
+                Some(owners) => queries
+                    .iter()
+                    .find_map(|query| owners.get(query))
+                    .map(|(source, package)| Attribution::new(*source, Some(package.clone())))
+                    // A file no package owns was put there by hand (`make install`)
+                    .unwrap_or_else(|| Attribution::new(InstallSource::Manual, None)),
+                None => Attribution::default(),
+            })
+        })
+        .collect()
+}
+
+/// How the binary at `path` was installed
+pub fn attribute(path: &Path) -> Attribution {
+    let real_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let runner = active_runner();
+    attribute_all(
+        runner.as_ref(),
+        &SourceRoots::detect(runner.as_ref()),
+        &[(path.to_path_buf(), real_path)],
+    )
+    .pop()
+    .unwrap_or_default()
+}
+
+/// Fill in the install source of every detected binary
+pub fn attribute_tools(tools: &mut [ToolInfo]) {
+    let binaries: Vec<(PathBuf, PathBuf)> = tools
+        .iter()
+        .flat_map(|tool| &tool.versions)
+        .map(|version| {
+            let path = PathBuf::from(&version.path);
+            let real_path = if version.real_path.is_empty() {
+                path.clone()
+            } else {
+                PathBuf::from(&version.real_path)
+            };
+            (path, real_path)
+        })
+        .collect();
+    let runner = active_runner();
+    let roots = SourceRoots::detect(runner.as_ref());
+    let attributions = attribute_all(runner.as_ref(), &roots, &binaries);
+
+    let versions = tools.iter_mut().flat_map(|tool| &mut tool.versions);
+    for (version, attribution) in versions.zip(attributions) {
+        version.source = attribution.source;
+        version.source_package = attribution.package;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::ReplayRunner;
+
+    fn roots(home: &Path) -> SourceRoots {
+        SourceRoots {
+            managers: ManagerRoots {
+                nvm: Some(home.join(".nvm")),
+                pyenv: Some(home.join(".pyenv")),
+                rustup: Some(home.join(".rustup")),
+                ..ManagerRoots::default()
+            },
+            cargo_home: Some(home.join(".cargo")),
+            npm_global: vec![PathBuf::from("/usr/local/lib/node_modules")],
+            pnpm_global: vec![home.join(".local/share/pnpm/global/5/node_modules")],
+            yarn_global: vec![home.join(".config/yarn/global")],
+            bun_global: vec![home.join(".bun/install/global")],
+            pipx: vec![home.join(".local/share/pipx")],
+            uv_tools: vec![home.join(".local/share/uv/tools")],
+            homebrew: vec![PathBuf::from("/opt/homebrew")],
+        }
+    }
+
+    fn attribution(source: InstallSource, package: &str) -> Option<Attribution> {
+        Some(Attribution::new(source, Some(package.to_string())))
+    }
+
+    #[test]
+    fn classifies_install_locations() {
+        let home = Path::new("/home/dev");
+        let roots = roots(home);
+        let classify_real =
+            |path: &str, real: &str| classify(Path::new(path), Path::new(real), &roots);
+
+        assert_eq!(
+            classify_real(
+                "/home/dev/.nvm/versions/node/v18.19.0/bin/node",
+                "/home/dev/.nvm/versions/node/v18.19.0/bin/node"
+            ),
+            attribution(InstallSource::Nvm, "v18.19.0")
+        );
+        // npm ships with the Node.js install; yarn was added with `npm install -g`
+        assert_eq!(
+            classify_real(
+                "/home/dev/.nvm/versions/node/v18.19.0/bin/npm",
+                "/home/dev/.nvm/versions/node/v18.19.0/lib/node_modules/npm/bin/npm-cli.js"
+            ),
+            attribution(InstallSource::Nvm, "v18.19.0")
+        );
+        assert_eq!(
+            classify_real(
+                "/usr/local/bin/tsc",
+                "/usr/local/lib/node_modules/@typescript/native/bin/tsc"
+            ),
+            attribution(InstallSource::NpmGlobal, "@typescript/native")
+        );
+        // A project's dependencies are not a global install
+        assert_eq!(
+            classify_real(
+                "/home/dev/app/node_modules/.bin/tsc",
+                "/home/dev/app/node_modules/typescript/bin/tsc"
+            ),
+            Some(Attribution::default())
+        );
+        assert_eq!(
+            classify_real(
+                "/home/dev/.local/share/pnpm/tsc",
+                "/home/dev/.local/share/pnpm/global/5/node_modules/.pnpm/typescript@5.4.5/node_modules/typescript/bin/tsc"
+            ),
+            attribution(InstallSource::PnpmGlobal, "typescript")
+        );
+        assert_eq!(
+            classify_real(
+                "/home/dev/.yarn/bin/serve",
+                "/home/dev/.config/yarn/global/node_modules/serve/build/main.js"
+            ),
+            attribution(InstallSource::YarnGlobal, "serve")
+        );
+        assert_eq!(
+            classify_real(
+                "/home/dev/.bun/bin/biome",
+                "/home/dev/.bun/install/global/node_modules/@biomejs/biome/bin/biome"
+            ),
+            attribution(InstallSource::BunGlobal, "@biomejs/biome")
+        );
+        assert_eq!(
+            classify_real(
+                "/home/dev/.local/bin/poetry",
+                "/home/dev/.local/share/pipx/venvs/poetry/bin/poetry"
+            ),
+            attribution(InstallSource::Pipx, "poetry")
+        );
+        assert_eq!(
+            classify_real(
+                "/opt/homebrew/bin/go",
+                "/opt/homebrew/Cellar/go/1.23.2/libexec/bin/go"
+            ),
+            attribution(InstallSource::Homebrew, "go")
+        );
+        assert_eq!(
+            classify_real("/snap/bin/node", "/usr/bin/snap"),
+            attribution(InstallSource::Snap, "node")
+        );
+        assert_eq!(
+            classify_real("/home/dev/.cargo/bin/cargo", "/home/dev/.cargo/bin/cargo"),
+            Some(Attribution::new(InstallSource::Rustup, None))
+        );
+        assert_eq!(
+            classify_real("/opt/go/bin/go", "/opt/go/bin/go"),
+            Some(Attribution::new(InstallSource::Manual, None))
+        );
+    }
+
+    #[test]
+    fn asks_npm_and_pnpm_for_their_global_directories() {
+        let runner = ReplayRunner::default()
+            .with_output("npm", &["prefix", "-g"], "/nonexistent/npm-prefix\n")
+            .with_output(
+                "pnpm",
+                &["root", "-g"],
+                "/nonexistent/pnpm/global/5/node_modules\n",
+            );
+        let roots = SourceRoots::detect(&runner);
+        assert_eq!(
+            roots.npm_global,
+            vec![npm_global_modules(Path::new("/nonexistent/npm-prefix"))]
+        );
+        assert_eq!(
+            roots.pnpm_global,
+            vec![PathBuf::from("/nonexistent/pnpm/global/5/node_modules")]
+        );
+
+        let roots = SourceRoots::detect(&ReplayRunner::default());
+        assert!(roots.npm_global.is_empty() && roots.pnpm_global.is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn looks_up_system_paths_in_the_package_database() {
+        let runner = ReplayRunner::default().with_output(
+            "dpkg",
+            &[
+                "-S",
+                "/usr/bin/git",
+                "/bin/git",
+                "/usr/bin/cmake",
+                "/bin/cmake",
+            ],
+            "git: /usr/bin/git\n",
+        );
+        let binaries = [
+            (PathBuf::from("/usr/bin/git"), PathBuf::from("/usr/bin/git")),
+            (
+                PathBuf::from("/usr/bin/cmake"),
+                PathBuf::from("/usr/bin/cmake"),
+            ),
+        ];
+        let queries: Vec<PathBuf> = binaries
+            .iter()
+            .flat_map(|(path, real_path)| owner_queries(path, real_path))
+            .collect();
+
+        let owners = system_owners(&runner, &queries).unwrap();
+        assert_eq!(
+            owners.get(Path::new("/usr/bin/git")).unwrap(),
+            &(InstallSource::Apt, "git".to_string())
+        );
+        assert!(!owners.contains_key(Path::new("/usr/bin/cmake")));
+        assert!(system_owners(&ReplayRunner::default(), &queries).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn asks_the_next_package_database_about_unowned_paths() {
+        // dpkg is installed but empty, as on a Fedora host with Debian packaging tools
+        let fixture = include_str!("../../fixtures/commands/package-owners.jsonl");
+        let runner = ReplayRunner::parse(fixture, "package-owners.jsonl").unwrap();
+        let queries = owner_queries(Path::new("/usr/bin/cmake"), Path::new("/usr/bin/cmake"));
+
+        let owners = system_owners(&runner, &queries).unwrap();
+        assert_eq!(
+            owners.get(Path::new("/usr/bin/cmake")).unwrap(),
+            &(InstallSource::Rpm, "cmake".to_string())
+        );
+    }
+
+    #[test]
+    fn removal_follows_the_install_source() {
+        let pipx = Attribution::new(InstallSource::Pipx, Some("poetry".to_string()));
+        let commands = pipx
+            .removal("/home/dev/.local/bin/poetry")
+            .unwrap()
+            .unwrap();
+        assert_eq!(commands[0].command_line, "pipx uninstall poetry");
+
+        let bun = Attribution::new(InstallSource::BunGlobal, Some("@biomejs/biome".to_string()));
+        let commands = bun.removal("/home/dev/.bun/bin/biome").unwrap().unwrap();
+        assert_eq!(commands[0].command_line, "bun remove -g @biomejs/biome");
+
+        let pnpm = Attribution::new(InstallSource::PnpmGlobal, Some("typescript".to_string()));
+        let commands = pnpm
+            .removal("/home/dev/.local/share/pnpm/tsc")
+            .unwrap()
+            .unwrap();
+        assert_eq!(commands[0].command_line, "pnpm remove -g typescript");
+
+        let shim = Attribution::new(InstallSource::Pyenv, None);
+        assert!(shim
+            .removal("/home/dev/.pyenv/shims/python")
+            .unwrap()
+            .unwrap_err()
+            .contains("pyenv uninstall"));
+
+        assert!(Attribution::default().removal("/usr/bin/node").is_none());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn quotes_nvm_versions_in_the_uninstall_script() {
+        let hostile = Attribution::new(InstallSource::Nvm, Some("v20`id`$(id)*".to_string()));
+        let commands = hostile
+            .removal("/home/dev/.nvm/versions/node/v20/bin/node")
+            .unwrap()
+            .unwrap();
+        assert_eq!(commands[0].program, "sh");
+        assert!(commands[0].args[1].ends_with("&& nvm uninstall 'v20`id`$(id)*'"));
+    }
+}
@@ -0,0 +1,302 @@
+//! Distribution packages that own detected binaries
+//! Reports the package version and what depends on it, and builds the removal command. Removing
+//! a package needs root, so it only runs through a privilege helper when settings allow it and
+//! nothing else depends on the package. The removal commands refuse rather than take dependents
+//! along: `dpkg --remove`, `rpm -e` and `pacman -R` all fail on a broken dependency.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+use super::source::{attribute, InstallSource};
+use crate::plan::PlannedCommand;
+use crate::settings::active_settings;
+use crate::utils::runner::{active_runner, CommandRunner};
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Helpers tried in order to run a removal as root: a polkit prompt, then cached sudo credentials
+const PRIVILEGE_HELPERS: &[(&str, &[&str])] = &[("pkexec", &[]), ("sudo", &["-n"])];
+
+/// The distribution package a binary belongs to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SystemPackage {
+    /// apt, rpm or pacman
+    pub manager: InstallSource,
+    pub name: String,
+    pub version: Option<String>,
+    /// Installed packages that depend on this one
+    pub reverse_dependencies: Vec<String>,
+    /// Removal command as root would run it
+    pub removal_command: PlannedCommand,
+}
+
+impl SystemPackage {
+    /// Removal commands wrapped in each privilege helper, when settings allow running them and
+    /// no installed package depends on this one, otherwise instructions naming the exact command
+    pub fn removal(&self, path: &str) -> Result<Vec<PlannedCommand>, String> {
+        self.removal_with(path, active_settings().allow_privileged_uninstall)
+    }
+
+    fn removal_with(
+        &self,
+        path: &str,
+        allow_privileged: bool,
+    ) -> Result<Vec<PlannedCommand>, String> {
+        if allow_privileged && self.reverse_dependencies.is_empty() {
+            return Ok(privileged(&self.removal_command));
+        }
+
+        let mut message = format!(
+            "{} belongs to the {} package {}",
+            path,
+            self.manager.as_str(),
+            self.name
+        );
+        if let Some(version) = &self.version {
+            message.push_str(&format!(" {}", version));
+        }
+        message.push('.');
+        if self.reverse_dependencies.is_empty() {
+            message.push_str(&format!(
+                " Run: sudo {} (or allow privileged uninstalls in settings)",
+                self.removal_command.command_line
+            ));
+        } else {
+            message.push_str(&format!(
+                " Installed packages that depend on it: {}. Remove or keep them first, then run: sudo {}",
+                self.reverse_dependencies.join(", "),
+                self.removal_command.command_line
+            ));
+        }
+        Err(message)
+    }
+}
+
+fn privileged(command: &PlannedCommand) -> Vec<PlannedCommand> {
+    PRIVILEGE_HELPERS
+        .iter()
+        .map(|(helper, flags)| {
+            let args = flags
+                .iter()
+                .map(|flag| flag.to_string())
+                .chain(std::iter::once(command.program.clone()))
+                .chain(command.args.iter().cloned())
+                .collect();
+            PlannedCommand::owned(helper, args)
+        })
+        .collect()
+}
+
+fn stdout_of(runner: &dyn CommandRunner, program: &str, args: &[&str]) -> Option<String> {
+    // rpm and dpkg exit non-zero for "nothing found" while still printing what they know
+    let output = runner.output(program, args, QUERY_TIMEOUT).ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Package names from `apt-cache rdepends --installed` (alternatives are marked with `|`)
+fn parse_apt_rdepends(output: &str, package: &str) -> Vec<String> {
+    let mut names: Vec<String> = output
+        .lines()
+        .skip_while(|line| !line.starts_with("Reverse Depends:"))
+        .skip(1)
+        .map(|line| line.trim().trim_start_matches('|').to_string())
+        .filter(|name| !name.is_empty() && name != package)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// `Version` and `Required By` fields of `pacman -Qi`
+fn parse_pacman_info(output: &str) -> (Option<String>, Vec<String>) {
+    let field = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    };
+    let required_by = field("Required By")
+        .filter(|value| value != "None")
+        .map(|value| value.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default();
+    (field("Version"), required_by)
+}
+
+/// Version, reverse dependencies and removal command of an installed package
+pub fn inspect_package(
+    runner: &dyn CommandRunner,
+    manager: InstallSource,
+    name: &str,
+) -> Option<SystemPackage> {
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let (version, reverse_dependencies, removal_command) = match manager {
+        InstallSource::Apt => (
+            non_empty(stdout_of(
+                runner,
+                "dpkg-query",
+                &["-W", "-f=${Version}", name],
+            )),
+            stdout_of(
+                runner,
+                "apt-cache",
+                &[
+                    "rdepends",
+                    "--installed",
+                    "--no-recommends",
+                    "--no-suggests",
+                    name,
+                ],
+            )
+            .map(|output| parse_apt_rdepends(&output, name))
+            .unwrap_or_default(),
+            PlannedCommand::new("dpkg", &["--remove", name]),
+        ),
+        InstallSource::Rpm => (
+            non_empty(stdout_of(
+                runner,
+                "rpm",
+                &["-q", "--queryformat", "%{VERSION}-%{RELEASE}", name],
+            )),
+            stdout_of(
+                runner,
+                "rpm",
+                &["-q", "--whatrequires", name, "--queryformat", "%{NAME}\\n"],
+            )
+            .map(|output| {
+                // "no package requires <name>" when nothing does
+                output
+                    .lines()
+                    .filter(|line| !line.trim().is_empty() && !line.contains(' '))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+            PlannedCommand::new("rpm", &["-e", name]),
+        ),
+        InstallSource::Pacman => {
+            let (version, required_by) = stdout_of(runner, "pacman", &["-Qi", name])
+                .map(|output| parse_pacman_info(&output))
+                .unwrap_or_default();
+            (
+                version,
+                required_by,
+                PlannedCommand::new("pacman", &["-R", "--noconfirm", name]),
+            )
+        }
+        _ => return None,
+    };
+
+    Some(SystemPackage {
+        manager,
+        name: name.to_string(),
+        version,
+        reverse_dependencies,
+        removal_command,
+    })
+}
+
+/// The distribution package owning the binary at `path`, if any
+pub fn system_package_of(path: &str) -> Option<SystemPackage> {
+    let attribution = attribute(Path::new(path));
+    inspect_package(
+        active_runner().as_ref(),
+        attribution.source,
+        attribution.package.as_deref()?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::ReplayRunner;
+
+    #[test]
+    fn describes_debian_packages_and_their_dependents() {
+        let runner = ReplayRunner::default()
+            .with_output(
+                "dpkg-query",
+                &["-W", "-f=${Version}", "git"],
+                "1:2.39.5-0+deb12u2",
+            )
+            .with_output(
+                "apt-cache",
+                &[
+                    "rdepends",
+                    "--installed",
+                    "--no-recommends",
+                    "--no-suggests",
+                    "git",
+                ],
+                "git\nReverse Depends:\n  git-man\n |git-lfs\n  git-lfs\n",
+            );
+
+        let package = inspect_package(&runner, InstallSource::Apt, "git").unwrap();
+        assert_eq!(package.version.as_deref(), Some("1:2.39.5-0+deb12u2"));
+        assert_eq!(package.reverse_dependencies, ["git-lfs", "git-man"]);
+        assert_eq!(package.removal_command.command_line, "dpkg --remove git");
+
+        let message = package.removal_with("/usr/bin/git", false).unwrap_err();
+        assert!(message.contains("apt package git 1:2.39.5-0+deb12u2"));
+        assert!(message.contains("git-lfs, git-man"));
+        assert!(message.contains("then run: sudo dpkg --remove git"));
+    }
+
+    #[test]
+    fn packages_with_dependents_are_never_removed_as_root() {
+        let runner = ReplayRunner::default()
+            .with_output(
+                "rpm",
+                &["-q", "--queryformat", "%{VERSION}-%{RELEASE}", "git-core"],
+                "2.46.0-1.fc40",
+            )
+            .with_output(
+                "rpm",
+                &[
+                    "-q",
+                    "--whatrequires",
+                    "git-core",
+                    "--queryformat",
+                    "%{NAME}\\n",
+                ],
+                "git\ngit-core-doc\n",
+            );
+
+        let package = inspect_package(&runner, InstallSource::Rpm, "git-core").unwrap();
+        assert_eq!(package.removal_command.command_line, "rpm -e git-core");
+        let message = package.removal_with("/usr/bin/git", true).unwrap_err();
+        assert!(message.contains("git, git-core-doc"));
+
+        let leaf = SystemPackage {
+            reverse_dependencies: Vec::new(),
+            ..package
+        };
+        assert_eq!(
+            leaf.removal_with("/usr/bin/git", true).unwrap()[0].command_line,
+            "pkexec rpm -e git-core"
+        );
+    }
+
+    #[test]
+    fn reads_pacman_package_info() {
+        let info = "Name            : cmake\nVersion         : 3.30.3-1\nRequired By     : extra-cmake-modules  kdevelop\n";
+        let (version, required_by) = parse_pacman_info(info);
+        assert_eq!(version.as_deref(), Some("3.30.3-1"));
+        assert_eq!(required_by, ["extra-cmake-modules", "kdevelop"]);
+        assert_eq!(
+            parse_pacman_info("Required By     : None\n").1,
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn privileged_removal_tries_each_helper() {
+        let commands = privileged(&PlannedCommand::new("dpkg", &["--remove", "git"]));
+        assert_eq!(commands[0].command_line, "pkexec dpkg --remove git");
+        assert_eq!(commands[1].command_line, "sudo -n dpkg --remove git");
+    }
+}
@@ -0,0 +1,87 @@
+//! Uninstall support for detected development tools
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::ai_cli::{plan_uninstall_ai_tool, uninstall_ai_tool};
+use crate::ai_tools::normalize_ai_tool_id;
+use crate::detection::catalog::tool_rules;
+use crate::detection::source::attribute;
+use crate::error::DevJanitorError;
+use crate::operation::{run_first_success, OperationResult};
+use crate::plan::PlannedCommand;
+
+const UNINSTALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Commands `uninstall_tool` would try in order, or manual instructions
+pub fn plan_uninstall_tool(
+    tool_id: &str,
+    path: &str,
+) -> Result<Vec<PlannedCommand>, DevJanitorError> {
+    if normalize_ai_tool_id(tool_id).is_some() {
+        // AI CLI tools - defer to dedicated module (handles latest install methods)
+        return plan_uninstall_ai_tool(tool_id);
+    }
+    // Package managers and version managers know their own installs; the catalog is the fallback
+    if let Some(removal) = attribute(Path::new(path)).removal(path) {
+        return removal.map_err(DevJanitorError::ManualActionRequired);
+    }
+    manual_uninstall_commands(tool_id, path).map_err(DevJanitorError::ManualActionRequired)
+}
+
+/// Uninstall commands from the tool's catalog entry, or its manual instructions
+fn manual_uninstall_commands(tool_id: &str, path: &str) -> Result<Vec<PlannedCommand>, String> {
+    let rule = tool_rules().into_iter().find(|rule| rule.id == tool_id);
+    rule.map_or(Err(None), |rule| rule.uninstall.commands_or_instructions())
+        .map_err(|instructions| {
+            instructions.unwrap_or_else(|| {
+                format!(
+                    "Uninstall method for {} is not configured. Path: {}",
+                    tool_id, path
+                )
+            })
+        })
+}
+
+/// Uninstall a tool, trying each planned command until one succeeds
+pub fn uninstall_tool(tool_id: &str, path: &str) -> Result<OperationResult, DevJanitorError> {
+    // AI CLI tools run through their own module (longer timeout, native installers)
+    if normalize_ai_tool_id(tool_id).is_some() {
+        return uninstall_ai_tool(tool_id);
+    }
+
+    let commands = plan_uninstall_tool(tool_id, path)?;
+    run_first_success(
+        "uninstall_tool",
+        tool_id,
+        None,
+        &commands,
+        UNINSTALL_TIMEOUT,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::{with_thread_runner, ReplayRunner};
+    use std::sync::Arc;
+
+    #[test]
+    fn plans_fallbacks_in_execution_order() {
+        // No package database answers, so nothing on the host is queried
+        with_thread_runner(Arc::new(ReplayRunner::default()), || {
+            let commands = plan_uninstall_tool("poetry", "/usr/local/bin/poetry").unwrap();
+            assert_eq!(commands[0].command_line, "pipx uninstall poetry");
+            assert_eq!(
+                commands.last().unwrap().command_line,
+                "pip uninstall -y poetry"
+            );
+            assert_eq!(
+                plan_uninstall_tool("pip", "/usr/bin/pip")
+                    .unwrap_err()
+                    .code(),
+                "manual_action_required"
+            );
+        });
+    }
+}
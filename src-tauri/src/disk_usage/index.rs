@@ -0,0 +1,460 @@
+//! Persistent size index for repeated scans
+//! Each directory keeps one aggregate of the files directly inside it. When its device, inode
+//! and mtime are unchanged the aggregate is reused without reading the directory or stat'ing its
+//! files; only its subdirectories are stat'ed to check their own keys. A file rewritten in place
+//! does not change its directory's mtime, so such growth is picked up when the record expires.
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{DiskUsage, SeenInodes};
+use crate::error::DevJanitorError;
+use crate::utils::paths;
+
+/// One index file per sized tree, so a scan rewrites only the trees that changed
+const INDEX_DIR_NAME: &str = "size-index";
+const INDEX_VERSION: u32 = 4;
+
+/// Records older than this are rebuilt, so files rewritten in place are picked up eventually
+const MAX_RECORD_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A directory modified this close to being indexed may change again within the same mtime tick
+const RACY_WINDOW_NANOS: i128 = 2_000_000_000;
+
+type TreeRecords = HashMap<String, DirRecord>;
+
+/// Index files loaded so far, by tree root, shared by every scan in the process
+static SIZE_INDEX: Mutex<Option<HashMap<PathBuf, Arc<TreeRecords>>>> = Mutex::new(None);
+
+/// What one directory held directly when it was last read
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct DirRecord {
+    dev: u64,
+    ino: u64,
+    /// Modification time in nanoseconds since the epoch
+    mtime: i128,
+    /// When the record was taken, in nanoseconds since the epoch
+    indexed_at: i128,
+    /// Regular files with a single link directly inside
+    files: DiskUsage,
+    /// Files with more than one link, deduplicated against the rest of the scan on every use
+    #[serde(default)]
+    linked: Vec<LinkedFile>,
+    /// Names of the directories directly inside
+    #[serde(default)]
+    subdirs: Vec<String>,
+}
+
+/// Device, inode, apparent and allocated size of a file with more than one link
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+struct LinkedFile(u64, u64, u64, u64);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    dirs: TreeRecords,
+}
+
+/// One scan's view of the size index; call `save` when the scan is done
+#[derive(Default)]
+pub struct SizeIndex {
+    /// Trees that had to be read again in this scan, with every record under their root
+    updates: Mutex<Vec<(PathBuf, TreeRecords)>>,
+}
+
+/// State of sizing one tree
+struct TreeWalk<'a> {
+    previous: &'a TreeRecords,
+    seen: &'a SeenInodes,
+    records: Mutex<TreeRecords>,
+    /// Whether any directory had to be read again
+    changed: AtomicBool,
+    now: i128,
+}
+
+impl SizeIndex {
+    pub fn open() -> Self {
+        Self::default()
+    }
+
+    /// Size a file or directory tree, reusing unchanged directories from earlier scans
+    pub fn disk_usage(&self, path: &Path) -> DiskUsage {
+        let seen = SeenInodes::default();
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(_) => return DiskUsage::default(),
+        };
+        if !metadata.is_dir() {
+            return if metadata.is_file() {
+                super::file_usage(&metadata, &seen)
+            } else {
+                DiskUsage::default()
+            };
+        }
+
+        let previous = cached_tree(path);
+        let walk = TreeWalk {
+            previous: &previous,
+            seen: &seen,
+            records: Mutex::new(HashMap::new()),
+            changed: AtomicBool::new(false),
+            now: now_nanos(),
+        };
+        let usage = walk.walk(path, &metadata);
+        if walk.changed.into_inner() {
+            lock(&self.updates).push((path.to_path_buf(), into_inner(walk.records)));
+        }
+        usage
+    }
+
+    /// Write out the trees that changed in this scan
+    pub fn save(self) -> Result<(), DevJanitorError> {
+        let updates = into_inner(self.updates);
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        // Directories under a root that were not visited again no longer exist, so each
+        // tree's records replace its file wholesale
+        for (root, dirs) in updates {
+            let file = IndexFile {
+                version: INDEX_VERSION,
+                dirs,
+            };
+            write_tree(&root, &file)?;
+            lock(&SIZE_INDEX)
+                .get_or_insert_with(HashMap::new)
+                .insert(root, Arc::new(file.dirs));
+        }
+        prune_expired_trees();
+        Ok(())
+    }
+}
+
+impl TreeWalk<'_> {
+    fn walk(&self, dir: &Path, metadata: &Metadata) -> DiskUsage {
+        let key = dir.to_string_lossy().to_string();
+        let (dev, ino) = identity(metadata);
+        let mtime = mtime_nanos(metadata);
+
+        let record = match self.previous.get(&key) {
+            Some(record) if record.is_valid_for(dev, ino, mtime, self.now) => record.clone(),
+            _ => match read_dir_record(dir, dev, ino, mtime, self.now) {
+                Some(record) => {
+                    self.changed.store(true, Ordering::Relaxed);
+                    record
+                }
+                None => return DiskUsage::default(),
+            },
+        };
+
+        let usage = record.usage(self.seen);
+        let subdirs: Vec<PathBuf> = record.subdirs.iter().map(|name| dir.join(name)).collect();
+        lock(&self.records).insert(key, record);
+
+        usage
+            + subdirs
+                .par_iter()
+                .filter_map(|subdir| {
+                    let metadata = fs::symlink_metadata(subdir).ok()?;
+                    metadata.is_dir().then(|| self.walk(subdir, &metadata))
+                })
+                .reduce(DiskUsage::default, std::ops::Add::add)
+    }
+}
+
+impl DirRecord {
+    fn is_valid_for(&self, dev: u64, ino: u64, mtime: i128, now: i128) -> bool {
+        self.dev == dev
+            && self.ino == ino
+            && self.mtime == mtime
+            && self.indexed_at - self.mtime >= RACY_WINDOW_NANOS
+            && now - self.indexed_at < MAX_RECORD_AGE.as_nanos() as i128
+    }
+
+    /// Size of the files directly inside, counting each linked file once per scan
+    fn usage(&self, seen: &SeenInodes) -> DiskUsage {
+        let mut usage = self.files;
+        let mut seen = lock(seen);
+        for &LinkedFile(dev, ino, apparent, allocated) in &self.linked {
+            if seen.insert((dev, ino)) {
+                usage += DiskUsage {
+                    apparent,
+                    allocated,
+                    files: 1,
+                    hardlinks: 0,
+                };
+            } else {
+                usage.hardlinks += 1;
+            }
+        }
+        usage
+    }
+}
+
+fn read_dir_record(dir: &Path, dev: u64, ino: u64, mtime: i128, now: i128) -> Option<DirRecord> {
+    let entries = fs::read_dir(dir).ok()?;
+    let mut record = DirRecord {
+        dev,
+        ino,
+        mtime,
+        indexed_at: now,
+        files: DiskUsage::default(),
+        linked: Vec::new(),
+        subdirs: Vec::new(),
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            record
+                .subdirs
+                .push(entry.file_name().to_string_lossy().to_string());
+        } else if file_type.is_file() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let size = super::file_size(&metadata);
+            match linked_identity(&metadata) {
+                Some((dev, ino)) => {
+                    record
+                        .linked
+                        .push(LinkedFile(dev, ino, size.apparent, size.allocated));
+                }
+                None => record.files += size,
+            }
+        }
+    }
+    Some(record)
+}
+
+#[cfg(unix)]
+fn identity(metadata: &Metadata) -> (u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.dev(), metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn identity(_metadata: &Metadata) -> (u64, u64) {
+    (0, 0)
+}
+
+/// Device and inode of a file with more than one link
+#[cfg(unix)]
+fn linked_identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.nlink() > 1).then(|| identity(metadata))
+}
+
+#[cfg(not(unix))]
+fn linked_identity(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+fn mtime_nanos(metadata: &Metadata) -> i128 {
+    metadata.modified().map(system_time_nanos).unwrap_or(0)
+}
+
+fn now_nanos() -> i128 {
+    system_time_nanos(SystemTime::now())
+}
+
+fn system_time_nanos(time: SystemTime) -> i128 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_nanos() as i128,
+        Err(before) => -(before.duration().as_nanos() as i128),
+    }
+}
+
+fn index_dir() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(INDEX_DIR_NAME))
+}
+
+fn tree_path(root: &Path) -> Option<PathBuf> {
+    let name = format!("{:x}.json", md5::compute(root.to_string_lossy().as_bytes()));
+    index_dir().map(|dir| dir.join(name))
+}
+
+/// Records of the tree at `root`, loaded once per process
+fn cached_tree(root: &Path) -> Arc<TreeRecords> {
+    let mut cached = lock(&SIZE_INDEX);
+    let trees = cached.get_or_insert_with(HashMap::new);
+    Arc::clone(
+        trees
+            .entry(root.to_path_buf())
+            .or_insert_with(|| Arc::new(read_tree(root))),
+    )
+}
+
+/// The stored records of a tree; a missing, damaged or outdated file starts a new one
+fn read_tree(root: &Path) -> TreeRecords {
+    tree_path(root)
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|contents| serde_json::from_slice::<IndexFile>(&contents).ok())
+        .filter(|file| file.version == INDEX_VERSION)
+        .map(|file| file.dirs)
+        .unwrap_or_default()
+}
+
+fn write_tree(root: &Path, file: &IndexFile) -> Result<(), DevJanitorError> {
+    let path = tree_path(root).ok_or_else(|| {
+        DevJanitorError::NotFound("Could not determine the data directory".to_string())
+    })?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| {
+            DevJanitorError::io(format!("Failed to create {}", parent.display()), error)
+        })?;
+    }
+
+    let contents =
+        serde_json::to_vec(file).map_err(|error| DevJanitorError::Internal(error.to_string()))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, contents)
+        .and_then(|_| fs::rename(&temp_path, &path))
+        .map_err(|error| {
+            let _ = fs::remove_file(&temp_path);
+            DevJanitorError::io(format!("Failed to write {}", path.display()), error)
+        })
+}
+
+/// Delete index files not rewritten within a record's lifetime; none of their records is valid
+fn prune_expired_trees() {
+    let Some(entries) = index_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > MAX_RECORD_AGE);
+        if expired {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn into_inner<T>(mutex: Mutex<T>) -> T {
+    mutex
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dev-janitor-size-index-{name}-{}", now_nanos()));
+        fs::create_dir_all(dir.join("pkg/lib")).unwrap();
+        fs::write(dir.join("pkg/index.js"), vec![0u8; 1000]).unwrap();
+        fs::write(dir.join("pkg/lib/util.js"), vec![0u8; 4000]).unwrap();
+        dir
+    }
+
+    /// Pretend every record was taken long enough after its directory changed
+    fn settle(dir: &Path) {
+        let mut cached = lock(&SIZE_INDEX);
+        let trees = cached.get_or_insert_with(HashMap::new);
+        let mut dirs = trees
+            .get(dir)
+            .map(|tree| (**tree).clone())
+            .unwrap_or_default();
+        for record in dirs.values_mut() {
+            record.indexed_at = record.mtime + RACY_WINDOW_NANOS;
+        }
+        trees.insert(dir.to_path_buf(), Arc::new(dirs));
+    }
+
+    #[test]
+    fn reuses_unchanged_directories() {
+        let dir = temp_tree("reuse");
+        let index = SizeIndex::open();
+        assert_eq!(index.disk_usage(&dir).apparent, 5000);
+        index.save().unwrap();
+        settle(&dir);
+
+        // Adding a file deep in the tree changes its directory, which is read again
+        fs::write(dir.join("pkg/lib/extra.js"), vec![0u8; 500]).unwrap();
+        let index = SizeIndex::open();
+        assert_eq!(index.disk_usage(&dir).apparent, 5500);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn does_not_stat_files_of_unchanged_directories() {
+        let dir = temp_tree("no-stat");
+        let index = SizeIndex::open();
+        assert_eq!(index.disk_usage(&dir).apparent, 5000);
+        index.save().unwrap();
+        settle(&dir);
+
+        // Growing files in place leaves every directory untouched; had the second scan
+        // stat'ed them, it would see the new sizes
+        fs::write(dir.join("pkg/index.js"), vec![0u8; 2000]).unwrap();
+        fs::write(dir.join("pkg/lib/util.js"), vec![0u8; 6000]).unwrap();
+        let index = SizeIndex::open();
+        assert_eq!(index.disk_usage(&dir).apparent, 5000);
+        assert!(lock(&index.updates).is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn forgets_removed_directories() {
+        let dir = temp_tree("removed");
+        let index = SizeIndex::open();
+        index.disk_usage(&dir);
+        index.save().unwrap();
+        settle(&dir);
+
+        fs::remove_dir_all(dir.join("pkg/lib")).unwrap();
+        let index = SizeIndex::open();
+        assert_eq!(index.disk_usage(&dir).apparent, 1000);
+        index.save().unwrap();
+
+        let stored = read_tree(&dir);
+        assert!(stored.contains_key(&dir.join("pkg").to_string_lossy().to_string()));
+        assert!(!stored.contains_key(&dir.join("pkg/lib").to_string_lossy().to_string()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_hardlinks_counted_once_on_reuse() {
+        let dir = temp_tree("links");
+        fs::hard_link(dir.join("pkg/lib/util.js"), dir.join("pkg/util-link.js")).unwrap();
+        let index = SizeIndex::open();
+        let first = index.disk_usage(&dir);
+        index.save().unwrap();
+        settle(&dir);
+
+        let second = SizeIndex::open().disk_usage(&dir);
+
+        assert_eq!(first, second);
+        assert_eq!(second.apparent, 5000);
+        assert_eq!(second.hardlinks, 1);
+
+        // `pkg` is reused while `pkg/lib` is read again; its link is still counted once
+        fs::write(dir.join("pkg/lib/extra.js"), vec![0u8; 500]).unwrap();
+        let third = SizeIndex::open().disk_usage(&dir);
+        assert_eq!(third.apparent, 5500);
+        assert_eq!(third.hardlinks, 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+//! Shared disk usage engine
+//! Sizes directory trees in parallel and counts each hardlinked file once
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, Metadata};
+use std::ops::{Add, AddAssign};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+mod index;
+
+pub use index::SizeIndex;
+
+/// Size of a file or directory tree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskUsage {
+    /// Sum of file lengths
+    pub apparent: u64,
+    /// Space the files occupy on disk: allocated blocks on Unix, the file length elsewhere
+    pub allocated: u64,
+    pub files: u64,
+    /// Extra links to files that were already counted
+    pub hardlinks: u64,
+}
+
+impl Add for DiskUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            apparent: self.apparent + other.apparent,
+            allocated: self.allocated + other.allocated,
+            files: self.files + other.files,
+            hardlinks: self.hardlinks + other.hardlinks,
+        }
+    }
+}
+
+impl AddAssign for DiskUsage {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// Inodes with more than one link that were counted already, as (device, inode)
+type SeenInodes = Mutex<HashSet<(u64, u64)>>;
+
+/// Format bytes to human readable string
+pub fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.2} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.2} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.2} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Size a file or directory tree; symlinks inside the tree are not followed
+pub fn disk_usage(path: &Path) -> DiskUsage {
+    disk_usage_of(&[path.to_path_buf()])
+}
+
+/// Size several trees at once, counting files linked from more than one of them once
+pub fn disk_usage_of(paths: &[PathBuf]) -> DiskUsage {
+    let seen = SeenInodes::default();
+    paths
+        .par_iter()
+        .map(|path| match fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => walk_dir(path, &seen),
+            Ok(metadata) if metadata.is_file() => file_usage(&metadata, &seen),
+            _ => DiskUsage::default(),
+        })
+        .reduce(DiskUsage::default, Add::add)
+}
+
+/// Apparent size of a file or directory tree, hardlinks counted once
+pub fn path_size(path: &Path) -> u64 {
+    disk_usage(path).apparent
+}
+
+fn walk_dir(dir: &Path, seen: &SeenInodes) -> DiskUsage {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return DiskUsage::default();
+    };
+
+    let mut usage = DiskUsage::default();
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            subdirs.push(entry.path());
+        } else if file_type.is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                usage += file_usage(&metadata, seen);
+            }
+        }
+    }
+
+    usage
+        + subdirs
+            .par_iter()
+            .map(|subdir| walk_dir(subdir, seen))
+            .reduce(DiskUsage::default, Add::add)
+}
+
+#[cfg(unix)]
+fn file_usage(metadata: &Metadata, seen: &SeenInodes) -> DiskUsage {
+    use std::os::unix::fs::MetadataExt;
+
+    if metadata.nlink() > 1 {
+        let first_link = seen
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert((metadata.dev(), metadata.ino()));
+        if !first_link {
+            return DiskUsage {
+                hardlinks: 1,
+                ..DiskUsage::default()
+            };
+        }
+    }
+
+    file_size(metadata)
+}
+
+#[cfg(not(unix))]
+fn file_usage(metadata: &Metadata, _seen: &SeenInodes) -> DiskUsage {
+    file_size(metadata)
+}
+
+/// Size of one file, regardless of other links to it
+#[cfg(unix)]
+fn file_size(metadata: &Metadata) -> DiskUsage {
+    use std::os::unix::fs::MetadataExt;
+
+    DiskUsage {
+        apparent: metadata.len(),
+        // st_blocks is always in 512-byte units
+        allocated: metadata.blocks() * 512,
+        files: 1,
+        hardlinks: 0,
+    }
+}
+
+#[cfg(not(unix))]
+fn file_size(metadata: &Metadata) -> DiskUsage {
+    DiskUsage {
+        apparent: metadata.len(),
+        allocated: metadata.len(),
+        files: 1,
+        hardlinks: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("dev-janitor-du-{name}-{nanos}"));
+        fs::create_dir_all(dir.join("a/b/c")).unwrap();
+        fs::write(dir.join("top.bin"), vec![0u8; 1000]).unwrap();
+        fs::write(dir.join("a/one.bin"), vec![0u8; 3000]).unwrap();
+        fs::write(dir.join("a/b/c/deep.bin"), vec![0u8; 5000]).unwrap();
+        dir
+    }
+
+    #[test]
+    fn sums_nested_files() {
+        let dir = temp_tree("sum");
+
+        let usage = disk_usage(&dir);
+
+        assert_eq!(usage.apparent, 9000);
+        assert_eq!(usage.files, 3);
+        assert_eq!(disk_usage(&dir.join("a/one.bin")).apparent, 3000);
+        assert_eq!(disk_usage(&dir.join("missing")), DiskUsage::default());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn counts_hardlinked_files_once() {
+        let dir = temp_tree("links");
+        fs::create_dir_all(dir.join("store")).unwrap();
+        fs::write(dir.join("store/pkg.tgz"), vec![1u8; 8192]).unwrap();
+        fs::hard_link(dir.join("store/pkg.tgz"), dir.join("a/pkg.tgz")).unwrap();
+        fs::hard_link(dir.join("store/pkg.tgz"), dir.join("a/b/pkg.tgz")).unwrap();
+        std::os::unix::fs::symlink(dir.join("store"), dir.join("a/store-link")).unwrap();
+
+        let usage = disk_usage(&dir);
+
+        assert_eq!(usage.apparent, 9000 + 8192);
+        assert_eq!(usage.files, 4);
+        assert_eq!(usage.hardlinks, 2);
+        assert!(usage.allocated >= 8192);
+
+        let across = disk_usage_of(&[dir.join("store"), dir.join("a")]);
+        assert_eq!(across.apparent, 3000 + 5000 + 8192);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.50 KB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.00 GB");
+    }
+}
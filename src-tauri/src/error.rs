@@ -1,11 +1,18 @@
 //! Error handling for Dev Janitor
 
+use serde::ser::SerializeStruct;
+use std::fmt::Display;
+use std::io;
 use thiserror::Error;
 
+/// Structured error returned by cleanup, uninstall and other mutating operations
+///
+/// Each variant maps to a stable `code()` so frontends and scripts can react to
+/// a case without parsing the English message.
 #[derive(Error, Debug)]
 pub enum DevJanitorError {
     #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(#[from] io::Error),
 
     #[error("Command execution failed: {0}")]
     CommandFailed(String),
@@ -18,6 +25,91 @@ pub enum DevJanitorError {
 
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
+
+    /// Root, home, symlink or otherwise dangerous path
+    #[error("{0}")]
+    UnsafePath(String),
+
+    /// The path exists but is not something this cleanup is allowed to remove
+    #[error("{0}")]
+    NotATarget(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    AlreadyExists(String),
+
+    #[error("{0}")]
+    Timeout(String),
+
+    /// The action has to be done by hand (e.g. system packages, native installers)
+    #[error("{0}")]
+    ManualActionRequired(String),
+
+    #[error("{0}")]
+    InvalidInput(String),
+
+    /// Quarantine would have to copy the target across filesystems; delete mode still works
+    #[error("{0}")]
+    QuarantineUnavailable(String),
+
+    /// The operation ran but did not succeed, for reasons not covered above
+    #[error("{0}")]
+    Failed(String),
+
+    /// A background worker crashed or was cancelled
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl DevJanitorError {
+    /// Stable machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            DevJanitorError::Io(error) => match error.kind() {
+                io::ErrorKind::PermissionDenied => "permission_denied",
+                io::ErrorKind::NotFound => "not_found",
+                io::ErrorKind::AlreadyExists => "already_exists",
+                io::ErrorKind::TimedOut => "timeout",
+                _ => "io",
+            },
+            DevJanitorError::CommandFailed(_) => "command_failed",
+            DevJanitorError::ToolNotFound(_) => "tool_missing",
+            DevJanitorError::ParseError(_) => "parse_error",
+            DevJanitorError::PermissionDenied(_) => "permission_denied",
+            DevJanitorError::UnsafePath(_) => "unsafe_path",
+            DevJanitorError::NotATarget(_) => "not_a_target",
+            DevJanitorError::NotFound(_) => "not_found",
+            DevJanitorError::AlreadyExists(_) => "already_exists",
+            DevJanitorError::Timeout(_) => "timeout",
+            DevJanitorError::ManualActionRequired(_) => "manual_action_required",
+            DevJanitorError::InvalidInput(_) => "invalid_input",
+            DevJanitorError::QuarantineUnavailable(_) => "quarantine_unavailable",
+            DevJanitorError::Failed(_) => "failed",
+            DevJanitorError::Internal(_) => "internal",
+        }
+    }
+
+    /// Wrap a filesystem error with what was being done, keeping its kind
+    pub fn io(context: impl Display, error: io::Error) -> Self {
+        DevJanitorError::Io(io::Error::new(
+            error.kind(),
+            format!("{}: {}", context, error),
+        ))
+    }
+
+    /// Map a failure to spawn or wait for `program`
+    pub fn spawn(program: &str, error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => DevJanitorError::ToolNotFound(program.to_string()),
+            io::ErrorKind::TimedOut => DevJanitorError::Timeout(error.to_string()),
+            io::ErrorKind::PermissionDenied => {
+                DevJanitorError::PermissionDenied(format!("{}: {}", program, error))
+            }
+            _ => DevJanitorError::CommandFailed(format!("{}: {}", program, error)),
+        }
+    }
 }
 
 impl serde::Serialize for DevJanitorError {
@@ -25,6 +117,40 @@ impl serde::Serialize for DevJanitorError {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let mut state = serializer.serialize_struct("DevJanitorError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_and_message() {
+        let error = DevJanitorError::UnsafePath("Refusing to clean unsafe path: /".to_string());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "unsafe_path",
+                "message": "Refusing to clean unsafe path: /"
+            })
+        );
+    }
+
+    #[test]
+    fn io_errors_keep_their_kind() {
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+        let error = DevJanitorError::io("Failed to quarantine /tmp/x", denied);
+        assert_eq!(error.code(), "permission_denied");
+        assert!(error.to_string().contains("Failed to quarantine /tmp/x"));
+
+        let missing = io::Error::new(io::ErrorKind::NotFound, "missing");
+        assert_eq!(
+            DevJanitorError::spawn("pnpm", missing).code(),
+            "tool_missing"
+        );
     }
 }
@@ -0,0 +1,281 @@
+//! Append-only audit journal of mutating actions
+//! One JSON object per line in `journal.jsonl` under the data directory
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, SecondsFormat, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::DevJanitorError;
+use crate::utils::paths;
+
+const JOURNAL_FILE_NAME: &str = "journal.jsonl";
+
+/// Serializes appends so concurrent workers never interleave lines
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+/// One recorded action
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// RFC 3339 local time the action finished
+    pub timestamp: String,
+    /// e.g. "clean_cache", "delete_ai_junk", "delete_chat_history", "update_package",
+    /// "uninstall_package", "install_ai_tool", "uninstall_tool", "kill_process"
+    pub action: String,
+    /// Path, package name, tool id or process the action applied to
+    pub target: String,
+    pub manager: Option<String>,
+    pub bytes_freed: Option<u64>,
+    /// Bytes moved into quarantine rather than freed
+    #[serde(default)]
+    pub bytes_quarantined: Option<u64>,
+    pub command: Option<String>,
+    /// Exit code of `command`, when the process ran to completion
+    pub exit_status: Option<i32>,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Stable `DevJanitorError` code of a failed action
+    #[serde(default)]
+    pub error_code: Option<String>,
+}
+
+/// Filters for `query_journal`; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalQuery {
+    /// Inclusive lower bound, RFC 3339 or `YYYY-MM-DD` (local midnight)
+    pub since: Option<String>,
+    /// Exclusive upper bound, same formats as `since`
+    pub until: Option<String>,
+    pub action: Option<String>,
+    /// Case-insensitive substring of the target
+    pub target: Option<String>,
+    /// Only successful (true) or failed (false) actions
+    pub success: Option<bool>,
+    /// Newest entries are returned first, at most this many
+    pub limit: Option<usize>,
+}
+
+impl JournalEntry {
+    /// A successful entry stamped with the current time
+    pub fn new(action: &str, target: &str) -> Self {
+        Self {
+            timestamp: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            action: action.to_string(),
+            target: target.to_string(),
+            manager: None,
+            bytes_freed: None,
+            bytes_quarantined: None,
+            command: None,
+            exit_status: None,
+            success: true,
+            error: None,
+            error_code: None,
+        }
+    }
+
+    /// Entry whose success and error mirror an operation's result
+    pub fn from_result<T>(action: &str, target: &str, result: &Result<T, DevJanitorError>) -> Self {
+        let mut entry = Self::new(action, target);
+        if let Err(error) = result {
+            entry.success = false;
+            entry.error = Some(error.to_string());
+            entry.error_code = Some(error.code().to_string());
+        }
+        entry
+    }
+}
+
+/// Record a removal that freed `size` bytes when it succeeded
+pub fn record_removal<T>(
+    action: &str,
+    path: &Path,
+    size: u64,
+    result: &Result<T, DevJanitorError>,
+) -> Result<(), DevJanitorError> {
+    let entry = JournalEntry::from_result(action, &path.to_string_lossy(), result);
+    record(&JournalEntry {
+        bytes_freed: entry.success.then_some(size),
+        ..entry
+    })
+}
+
+/// Record a cleanup of `size` bytes that either deleted its target (`Ok(None)`) or
+/// moved it into quarantine (`Ok(Some(_))`)
+pub fn record_cleanup<T>(
+    action: &str,
+    path: &Path,
+    size: u64,
+    result: &Result<Option<T>, DevJanitorError>,
+) -> Result<(), DevJanitorError> {
+    let entry = JournalEntry::from_result(action, &path.to_string_lossy(), result);
+    let quarantined = matches!(result, Ok(Some(_)));
+    record(&JournalEntry {
+        bytes_freed: (entry.success && !quarantined).then_some(size),
+        bytes_quarantined: quarantined.then_some(size),
+        ..entry
+    })
+}
+
+fn journal_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(JOURNAL_FILE_NAME))
+}
+
+/// Append an entry to the journal
+///
+/// Journaling never fails the action being journaled; callers keep an error
+/// from here as a warning on the action's result.
+pub fn record(entry: &JournalEntry) -> Result<(), DevJanitorError> {
+    append(entry).map_err(|error| {
+        DevJanitorError::Failed(format!("Failed to write audit journal: {}", error))
+    })
+}
+
+fn append(entry: &JournalEntry) -> Result<(), String> {
+    let path =
+        journal_path().ok_or_else(|| "Could not determine the data directory".to_string())?;
+    let mut line = serde_json::to_string(entry).map_err(|error| error.to_string())?;
+    line.push('\n');
+
+    let _guard = JOURNAL_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+/// Read journal entries matching `query`, newest first
+pub fn query_journal(query: &JournalQuery) -> Result<Vec<JournalEntry>, DevJanitorError> {
+    let since = query.since.as_deref().map(parse_bound).transpose()?;
+    let until = query.until.as_deref().map(parse_bound).transpose()?;
+    let target = query.target.as_ref().map(|target| target.to_lowercase());
+
+    let path = journal_path().ok_or_else(|| {
+        DevJanitorError::NotFound("Could not determine the data directory".to_string())
+    })?;
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(DevJanitorError::io(
+                format!("Failed to read {}", path.display()),
+                error,
+            ))
+        }
+    };
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|error| {
+            DevJanitorError::io(format!("Failed to read {}", path.display()), error)
+        })?;
+        // A torn final line from a crash should not hide the rest of the history
+        let Ok(entry) = serde_json::from_str::<JournalEntry>(&line) else {
+            continue;
+        };
+        let Ok(timestamp) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+            continue;
+        };
+
+        let matches = since.is_none_or(|since| timestamp >= since)
+            && until.is_none_or(|until| timestamp < until)
+            && query
+                .action
+                .as_ref()
+                .is_none_or(|action| &entry.action == action)
+            && target
+                .as_ref()
+                .is_none_or(|target| entry.target.to_lowercase().contains(target))
+            && query.success.is_none_or(|success| entry.success == success);
+        if matches {
+            entries.push(entry);
+        }
+    }
+
+    entries.reverse();
+    if let Some(limit) = query.limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
+
+fn parse_bound(value: &str) -> Result<DateTime<FixedOffset>, DevJanitorError> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp);
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+        .map(|timestamp| timestamp.fixed_offset())
+        .ok_or_else(|| {
+            DevJanitorError::InvalidInput(format!(
+                "Invalid date: {} (expected YYYY-MM-DD or an RFC 3339 timestamp)",
+                value
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_and_filters_entries() {
+        let target = format!("/tmp/dev-janitor-journal-{}", std::process::id());
+        let mut failed = JournalEntry::from_result::<()>(
+            "uninstall_package",
+            &target,
+            &Err(DevJanitorError::CommandFailed(
+                "Failed to uninstall".to_string(),
+            )),
+        );
+        failed.manager = Some("npm".to_string());
+        record(&JournalEntry {
+            bytes_freed: Some(42),
+            ..JournalEntry::new("clean_cache", &target)
+        })
+        .unwrap();
+        record(&failed).unwrap();
+
+        let query = JournalQuery {
+            target: Some(target.to_uppercase()),
+            ..JournalQuery::default()
+        };
+        let entries = query_journal(&query).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], failed);
+        assert_eq!(entries[1].bytes_freed, Some(42));
+
+        let cleaned = query_journal(&JournalQuery {
+            action: Some("clean_cache".to_string()),
+            success: Some(true),
+            ..query.clone()
+        })
+        .unwrap();
+        assert_eq!(cleaned.len(), 1);
+
+        let future = query_journal(&JournalQuery {
+            since: Some("2999-01-01".to_string()),
+            ..query
+        })
+        .unwrap();
+        assert!(future.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_bounds() {
+        assert!(parse_bound("last tuesday").is_err());
+        assert!(parse_bound("2026-10-13").is_ok());
+        assert!(parse_bound("2026-10-13T09:30:00+02:00").is_ok());
+    }
+}
@@ -8,28 +8,54 @@ mod ai_cli;
 mod ai_tools;
 mod cache;
 mod chat_history;
+pub mod cli;
 #[cfg(feature = "desktop")]
 mod commands;
 mod config;
 mod detection;
+mod disk_usage;
 mod error;
+mod journal;
+mod lifecycle;
+mod operation;
 mod package_manager;
+mod plan;
+mod quarantine;
+mod report;
+mod retention;
+mod rules;
+mod runtimes;
+mod scan_job;
 mod security_scan;
 mod services;
+mod settings;
+mod snapshot;
 mod utils;
 
 #[cfg(feature = "desktop")]
 use commands::{
-    analyze_path_cmd, clean_cache_cmd, clean_multiple_caches, delete_ai_junk_cmd,
-    delete_chat_file_cmd, delete_multiple_ai_junk, delete_multiple_chat_files,
-    delete_project_chat_history_cmd, diagnose_env_cmd, get_ai_cli_tools_cmd, get_all_processes_cmd,
+    analyze_path_cmd, cancel_scan_job_cmd, clean_cache_cmd, clean_multiple_caches,
+    delete_ai_junk_cmd, delete_chat_file_cmd, delete_multiple_ai_junk, delete_multiple_chat_files,
+    delete_project_chat_history_cmd, delete_snapshot_cmd, diagnose_env_cmd, diff_snapshots_cmd,
+    execute_cleanup_plan_cmd, generate_report_cmd, get_ai_cli_tools_cmd, get_all_processes_cmd,
     get_common_dev_ports_cmd, get_dev_processes_cmd, get_path_suggestions_cmd, get_ports_cmd,
-    get_security_tools_cmd, get_shell_configs_cmd, get_tool_info, get_total_cache_size,
-    install_ai_tool_cmd, kill_process_cmd, scan_ai_junk_cmd, scan_caches, scan_chat_history_cmd,
-    scan_global_chat_history_cmd, scan_packages, scan_project_caches_cmd, scan_security_cmd,
-    scan_tool_security_cmd, scan_tools, uninstall_ai_tool_cmd, uninstall_package, uninstall_tool,
-    update_ai_tool_cmd, update_package,
+    get_security_tools_cmd, get_settings_cmd, get_shell_configs_cmd, get_tool_info,
+    get_total_cache_size, inspect_system_package_cmd, install_ai_tool_cmd, install_package_cmd,
+    kill_process_cmd, list_package_managers_cmd, list_quarantine_cmd, list_retention_runs_cmd,
+    list_snapshots_cmd, package_info_cmd, pin_package_cmd, plan_clean_caches_cmd,
+    plan_delete_ai_junk_cmd, plan_delete_project_chat_history_cmd,
+    plan_remove_runtime_versions_cmd, plan_uninstall_ai_tool_cmd, plan_uninstall_package_cmd,
+    plan_uninstall_tool_cmd, preview_retention_cmd, purge_quarantine_cmd, query_journal_cmd,
+    reload_rules_cmd, remove_runtime_version_cmd, restore_quarantined_cmd, run_retention_cmd,
+    save_settings_cmd, scan_ai_junk_cmd, scan_caches, scan_chat_history_cmd,
+    scan_global_chat_history_cmd, scan_packages, scan_project_caches_cmd,
+    scan_runtime_versions_cmd, scan_security_cmd, scan_tool_security_cmd, scan_tools,
+    scan_version_pins_cmd, start_scan_job_cmd, take_snapshot_cmd, uninstall_ai_tool_cmd,
+    uninstall_package, uninstall_tool, update_ai_tool_cmd, update_all_packages_cmd, update_package,
+    RETENTION_PROBLEM_EVENT,
 };
+#[cfg(feature = "desktop")]
+use tauri::Emitter;
 
 #[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -39,6 +65,10 @@ pub fn run() {
             #[cfg(desktop)]
             app.handle()
                 .plugin(tauri_plugin_updater::Builder::new().build())?;
+            let handle = app.handle().clone();
+            retention::spawn_scheduler(move |error| {
+                let _ = handle.emit(RETENTION_PROBLEM_EVENT, error);
+            });
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
@@ -48,26 +78,62 @@ pub fn run() {
             scan_tools,
             get_tool_info,
             uninstall_tool,
+            plan_uninstall_tool_cmd,
+            inspect_system_package_cmd,
             // Package commands
             scan_packages,
+            list_package_managers_cmd,
             update_package,
             uninstall_package,
+            install_package_cmd,
+            pin_package_cmd,
+            update_all_packages_cmd,
+            package_info_cmd,
+            plan_uninstall_package_cmd,
             // Cache commands
             scan_caches,
             scan_project_caches_cmd,
             clean_cache_cmd,
             clean_multiple_caches,
             get_total_cache_size,
+            plan_clean_caches_cmd,
+            // Runtime version commands
+            scan_runtime_versions_cmd,
+            remove_runtime_version_cmd,
+            plan_remove_runtime_versions_cmd,
+            scan_version_pins_cmd,
             // AI Cleanup commands
             scan_ai_junk_cmd,
             delete_ai_junk_cmd,
             delete_multiple_ai_junk,
+            plan_delete_ai_junk_cmd,
             // Chat History commands
             scan_chat_history_cmd,
             scan_global_chat_history_cmd,
             delete_chat_file_cmd,
             delete_project_chat_history_cmd,
             delete_multiple_chat_files,
+            plan_delete_project_chat_history_cmd,
+            // Scan job commands
+            start_scan_job_cmd,
+            cancel_scan_job_cmd,
+            // Quarantine commands
+            list_quarantine_cmd,
+            restore_quarantined_cmd,
+            purge_quarantine_cmd,
+            // Dry-run plan commands
+            execute_cleanup_plan_cmd,
+            // Audit journal commands
+            query_journal_cmd,
+            // Retention policy commands
+            preview_retention_cmd,
+            run_retention_cmd,
+            list_retention_runs_cmd,
+            // User rule commands
+            reload_rules_cmd,
+            // Settings commands
+            get_settings_cmd,
+            save_settings_cmd,
             // Service monitoring commands
             get_dev_processes_cmd,
             get_all_processes_cmd,
@@ -84,6 +150,14 @@ pub fn run() {
             install_ai_tool_cmd,
             update_ai_tool_cmd,
             uninstall_ai_tool_cmd,
+            plan_uninstall_ai_tool_cmd,
+            // Inventory report commands
+            generate_report_cmd,
+            // Inventory snapshot commands
+            take_snapshot_cmd,
+            list_snapshots_cmd,
+            delete_snapshot_cmd,
+            diff_snapshots_cmd,
             // Security scan commands
             scan_security_cmd,
             scan_tool_security_cmd,
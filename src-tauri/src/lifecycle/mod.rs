@@ -0,0 +1,170 @@
+//! Release and end-of-life dates of language runtimes
+//! Versions are checked against the bundled `catalog/lifecycle.json`, without network access
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::utils::version::matches_version_prefix;
+
+const LIFECYCLE_DATASET: &str = include_str!("../../catalog/lifecycle.json");
+
+/// Support state of a runtime version on a given day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportStatus {
+    Supported,
+    /// End of life within the dataset's warning window (180 days)
+    NearingEol,
+    Eol,
+}
+
+impl SupportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SupportStatus::Supported => "supported",
+            SupportStatus::NearingEol => "nearing_eol",
+            SupportStatus::Eol => "eol",
+        }
+    }
+}
+
+/// Where a version stands in its runtime's release lifecycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lifecycle {
+    pub status: SupportStatus,
+    /// Release line the version belongs to, e.g. `20` for Node.js or `3.12` for Python
+    pub cycle: String,
+    /// First release of the cycle (YYYY-MM-DD)
+    pub release: String,
+    /// Last day of support (YYYY-MM-DD); absent while no date is announced
+    pub eol: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Dataset {
+    nearing_eol_days: i64,
+    runtimes: HashMap<String, Vec<Cycle>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cycle {
+    cycle: String,
+    /// YYYY-MM-DD
+    release: String,
+    eol: Option<String>,
+}
+
+impl Cycle {
+    fn eol_date(&self) -> Option<NaiveDate> {
+        self.eol.as_deref()?.parse().ok()
+    }
+}
+
+fn dataset() -> &'static Dataset {
+    static DATASET: OnceLock<Dataset> = OnceLock::new();
+    DATASET.get_or_init(|| {
+        serde_json::from_str(LIFECYCLE_DATASET).expect("bundled lifecycle dataset should parse")
+    })
+}
+
+/// Common name of a tool or runtime (asdf and mise plugins use `nodejs` and `golang`)
+pub fn canonical_runtime(runtime: &str) -> &str {
+    match runtime {
+        "nodejs" => "node",
+        "golang" => "go",
+        other => other,
+    }
+}
+
+/// Lifecycle of `version` of `runtime` today, when the dataset covers it
+pub fn assess(runtime: &str, version: &str) -> Option<Lifecycle> {
+    assess_on(runtime, version, Local::now().date_naive())
+}
+
+fn assess_on(runtime: &str, version: &str, today: NaiveDate) -> Option<Lifecycle> {
+    let data = dataset();
+    let runtime = canonical_runtime(runtime);
+    // Java 8 and older report themselves as 1.8.0
+    let version = match runtime {
+        "java" => version.strip_prefix("1.").unwrap_or(version),
+        _ => version,
+    };
+
+    let cycle = data
+        .runtimes
+        .get(runtime)?
+        .iter()
+        .filter(|cycle| matches_version_prefix(version, &cycle.cycle))
+        .max_by_key(|cycle| cycle.cycle.len())?;
+
+    let status = match cycle.eol_date() {
+        Some(eol) if today >= eol => SupportStatus::Eol,
+        Some(eol) if (eol - today).num_days() <= data.nearing_eol_days => SupportStatus::NearingEol,
+        _ => SupportStatus::Supported,
+    };
+    Some(Lifecycle {
+        status,
+        cycle: cycle.cycle.clone(),
+        release: cycle.release.clone(),
+        eol: cycle.eol.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn classifies_versions_by_their_cycle() {
+        let today = day("2026-10-18");
+
+        let node = assess_on("node", "v16.20.2", today).unwrap();
+        assert_eq!(node.status, SupportStatus::Eol);
+        assert_eq!(node.cycle, "16");
+        assert_eq!(node.eol.as_deref(), Some("2023-09-11"));
+
+        assert_eq!(
+            assess_on("python", "3.10.13", today).unwrap().status,
+            SupportStatus::NearingEol
+        );
+        assert_eq!(
+            assess_on("python", "3.12.1", today).unwrap().status,
+            SupportStatus::Supported
+        );
+        assert_eq!(assess_on("python", "3.1.5", today), None);
+    }
+
+    #[test]
+    fn understands_runtime_specific_version_strings() {
+        let today = day("2026-10-18");
+
+        assert_eq!(assess_on("java", "1.8.0", today).unwrap().cycle, "8");
+        assert_eq!(assess_on("java", "21.0.2-tem", today).unwrap().cycle, "21");
+        assert_eq!(assess_on("dotnet", "3.1.426", today).unwrap().cycle, "3.1");
+        assert_eq!(assess_on("nodejs", "18.19.0", today).unwrap().cycle, "18");
+        assert_eq!(
+            assess_on("deno", "2.1.4", today).unwrap().status,
+            SupportStatus::Supported
+        );
+        assert_eq!(assess_on("rust", "1.80.0", today), None);
+    }
+
+    #[test]
+    fn bundled_dataset_is_consistent() {
+        for (runtime, cycles) in &dataset().runtimes {
+            for cycle in cycles {
+                let release = day(&cycle.release);
+                assert_eq!(cycle.eol.is_some(), cycle.eol_date().is_some());
+                if let Some(eol) = cycle.eol_date() {
+                    assert!(eol > release, "{} {}", runtime, cycle.cycle);
+                }
+            }
+        }
+    }
+}
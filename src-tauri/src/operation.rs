@@ -0,0 +1,215 @@
+//! Typed results of mutating operations
+//! Shared by cleanups, uninstalls and process management
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::disk_usage::format_size;
+use crate::error::DevJanitorError;
+use crate::journal::{self, JournalEntry};
+use crate::plan::PlannedCommand;
+use crate::utils::runner::active_runner;
+
+/// What an operation did
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OperationResult {
+    /// Path, package, tool or process the operation acted on
+    pub target: String,
+    pub bytes_freed: u64,
+    pub bytes_freed_display: String,
+    /// Bytes moved into quarantine; they are only freed when the quarantine is purged
+    #[serde(default)]
+    pub bytes_quarantined: u64,
+    #[serde(default)]
+    pub bytes_quarantined_display: String,
+    pub items_removed: u32,
+    /// Command line that ran, for package and tool operations
+    pub command: Option<String>,
+    /// Combined stdout and stderr of that command
+    pub output: Option<String>,
+    /// Problems that did not fail the operation as a whole
+    pub warnings: Vec<String>,
+    /// Human-readable summary
+    pub message: String,
+}
+
+impl OperationResult {
+    pub fn new(target: &str, message: String) -> Self {
+        Self {
+            target: target.to_string(),
+            bytes_freed: 0,
+            bytes_freed_display: format_size(0),
+            bytes_quarantined: 0,
+            bytes_quarantined_display: format_size(0),
+            items_removed: 0,
+            command: None,
+            output: None,
+            warnings: Vec::new(),
+            message,
+        }
+    }
+
+    /// Result of removing `items` entries totalling `bytes`
+    pub fn removed(target: &str, items: u32, bytes: u64, message: String) -> Self {
+        Self {
+            bytes_freed: bytes,
+            bytes_freed_display: format_size(bytes),
+            items_removed: items,
+            ..Self::new(target, message)
+        }
+    }
+
+    /// Keep a journal write failure as a warning; the operation itself succeeded
+    pub fn journaled(mut self, journaled: Result<(), DevJanitorError>) -> Self {
+        if let Err(error) = journaled {
+            self.warnings.push(error.to_string());
+        }
+        self
+    }
+
+    /// Result of moving `items` entries totalling `bytes` into quarantine
+    pub fn quarantined(target: &str, items: u32, bytes: u64, message: String) -> Self {
+        Self {
+            bytes_quarantined: bytes,
+            bytes_quarantined_display: format_size(bytes),
+            items_removed: items,
+            ..Self::new(target, message)
+        }
+    }
+
+    /// Result of a cleanup of `bytes`, freed or quarantined depending on what happened
+    pub fn cleaned(target: &str, bytes: u64, quarantined: bool, message: String) -> Self {
+        if quarantined {
+            Self::quarantined(target, 1, bytes, message)
+        } else {
+            Self::removed(target, 1, bytes, message)
+        }
+    }
+}
+
+/// Run a command to completion through the active runner, returning its combined output
+/// and exit code
+pub fn run_command(
+    command: &PlannedCommand,
+    timeout: Duration,
+) -> (Result<String, DevJanitorError>, Option<i32>) {
+    let output = match active_runner().output_vec(&command.program, &command.args, timeout) {
+        Ok(output) => output,
+        Err(error) => return (Err(DevJanitorError::spawn(&command.program, error)), None),
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let combined = format!("{}{}", stdout, stderr).trim().to_string();
+    let exit_status = output.status.code();
+
+    if output.status.success() {
+        return (Ok(combined), exit_status);
+    }
+
+    let detail = if combined.is_empty() {
+        format!("{} exited with {}", command.command_line, output.status)
+    } else {
+        combined
+    };
+    let lowered = detail.to_lowercase();
+    // Package managers report missing privileges in their output, not their exit code
+    let error = if lowered.contains("permission denied")
+        || lowered.contains("eacces")
+        || lowered.contains("access is denied")
+    {
+        DevJanitorError::PermissionDenied(detail)
+    } else {
+        DevJanitorError::CommandFailed(detail)
+    };
+    (Err(error), exit_status)
+}
+
+/// Run commands in order until one succeeds, journaling every attempt
+pub fn run_first_success(
+    action: &str,
+    target: &str,
+    manager: Option<&str>,
+    commands: &[PlannedCommand],
+    timeout: Duration,
+) -> Result<OperationResult, DevJanitorError> {
+    let mut last_error = None;
+    let mut warnings = Vec::new();
+
+    for command in commands {
+        let (result, exit_status) = run_command(command, timeout);
+        let journaled = journal::record(&JournalEntry {
+            manager: manager.map(str::to_string),
+            command: Some(command.command_line.clone()),
+            exit_status,
+            ..JournalEntry::from_result(action, target, &result)
+        });
+        if let Err(error) = journaled {
+            warnings.push(error.to_string());
+        }
+
+        match result {
+            Ok(output) => {
+                return Ok(OperationResult {
+                    command: Some(command.command_line.clone()),
+                    output: Some(output),
+                    warnings,
+                    ..OperationResult::new(
+                        target,
+                        format!("Successfully executed: {}", command.command_line),
+                    )
+                })
+            }
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        DevJanitorError::InvalidInput("No command attempts were provided".to_string())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_and_reports_output() {
+        let commands = [
+            PlannedCommand::new("dev-janitor-missing-program", &["--version"]),
+            PlannedCommand::shell("echo removed"),
+        ];
+        let result = run_first_success(
+            "uninstall_tool",
+            "dev-janitor-test-tool",
+            None,
+            &commands,
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(result.output.as_deref(), Some("removed"));
+        assert_eq!(result.command.as_deref(), Some("sh -c 'echo removed'"));
+    }
+
+    #[test]
+    fn classifies_command_failures() {
+        let (missing, _) = run_command(
+            &PlannedCommand::new("dev-janitor-missing-program", &[]),
+            Duration::from_secs(5),
+        );
+        assert_eq!(missing.unwrap_err().code(), "tool_missing");
+
+        let (denied, status) = run_command(
+            &PlannedCommand::shell("echo 'npm ERR! code EACCES' >&2; exit 243"),
+            Duration::from_secs(5),
+        );
+        assert_eq!(denied.unwrap_err().code(), "permission_denied");
+        assert_eq!(status, Some(243));
+
+        let (slow, _) = run_command(
+            &PlannedCommand::shell("sleep 2"),
+            Duration::from_millis(100),
+        );
+        assert_eq!(slow.unwrap_err().code(), "timeout");
+    }
+}
@@ -0,0 +1,141 @@
+//! Bun global package support
+
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
+
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct BunManager {
+    version: String,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl BunManager {
+    pub fn new() -> Option<Self> {
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        let output = run_bun_command(runner.as_ref(), &["--version"])?;
+        let version = output.trim().to_string();
+        Some(Self { version, runner })
+    }
+}
+
+impl PackageManager for BunManager {
+    fn name(&self) -> &str {
+        "bun"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn get_version(&self) -> Option<String> {
+        Some(self.version.clone())
+    }
+
+    fn list_packages(&self) -> Vec<PackageInfo> {
+        let output = match run_bun_command(self.runner.as_ref(), &["pm", "ls", "-g"]) {
+            Some(o) => o,
+            None => return Vec::new(),
+        };
+
+        parse_bun_global_list(&output)
+            .into_iter()
+            .map(|(name, version)| PackageInfo {
+                name,
+                version,
+                latest: None,
+                manager: "bun".to_string(),
+                is_outdated: false,
+                description: None,
+                scope: None,
+            })
+            .collect()
+    }
+
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("bun", &[], &["add", "-g", &format!("{name}@latest")])
+    }
+
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("bun", &[], &["remove", "-g", name]))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: false,
+            batch_update: false,
+        }
+    }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("bun", &[], &["add", "-g", name]))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "bun",
+            &[],
+            &["add", "-g", &format!("{name}@{version}")],
+        ))
+    }
+}
+
+/// Name and version of each entry in `bun pm ls -g`:
+///
+/// ```text
+/// /home/dev/.bun/install/global node_modules (2)
+/// ├── @biomejs/biome@1.8.3
+/// └── typescript@5.4.5
+/// ```
+fn parse_bun_global_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (_, spec) = line.split_once("── ")?;
+            // Scoped packages start with `@`, so the version follows the last one
+            let (name, version) = spec.trim().rsplit_once('@')?;
+            (!name.is_empty()).then(|| (name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+fn run_bun_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner.output("bun", args, Duration::from_secs(30)).ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_global_package_tree() {
+        let output = "/home/dev/.bun/install/global node_modules (3)\n\
+├── @biomejs/biome@1.8.3\n\
+├── cowsay@1.6.0\n\
+└── typescript@5.4.5\n";
+
+        let packages = parse_bun_global_list(output);
+        assert_eq!(
+            packages,
+            [
+                ("@biomejs/biome".to_string(), "1.8.3".to_string()),
+                ("cowsay".to_string(), "1.6.0".to_string()),
+                ("typescript".to_string(), "5.4.5".to_string()),
+            ]
+        );
+    }
+}
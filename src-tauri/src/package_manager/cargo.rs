@@ -1,25 +1,32 @@
 //! Cargo package manager support
 
-use super::{PackageInfo, PackageManager};
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
 use regex::Regex;
 
-use crate::utils::command::command_output_with_timeout;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub struct CargoManager {
     version: String,
+    runner: Arc<dyn CommandRunner>,
 }
 
 impl CargoManager {
     pub fn new() -> Option<Self> {
-        let output = run_cargo_command(&["--version"])?;
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        let output = run_cargo_command(runner.as_ref(), &["--version"])?;
         // Extract version from "cargo X.Y.Z (hash date)"
         let version = output
             .split_whitespace()
             .nth(1)
             .unwrap_or("unknown")
             .to_string();
-        Some(Self { version })
+        Some(Self { version, runner })
     }
 }
 
@@ -40,7 +47,7 @@ impl PackageManager for CargoManager {
         let mut packages = Vec::new();
 
         // Get installed packages via cargo install --list
-        let output = match run_cargo_command(&["install", "--list"]) {
+        let output = match run_cargo_command(self.runner.as_ref(), &["install", "--list"]) {
             Some(o) => o,
             None => return packages,
         };
@@ -64,6 +71,7 @@ impl PackageManager for CargoManager {
                         manager: "cargo".to_string(),
                         is_outdated: false,
                         description: None,
+                        scope: None,
                     });
                 }
             }
@@ -72,23 +80,43 @@ impl PackageManager for CargoManager {
         packages
     }
 
-    fn update_package(&self, name: &str) -> Result<String, String> {
-        match run_cargo_command(&["install", name, "--force"]) {
-            Some(output) => Ok(format!("Updated {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to update {}", name)),
-        }
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("cargo", &[], &["install", name, "--force"])
     }
 
-    fn uninstall_package(&self, name: &str) -> Result<String, String> {
-        match run_cargo_command(&["uninstall", name]) {
-            Some(output) => Ok(format!("Uninstalled {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to uninstall {}", name)),
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("cargo", &[], &["uninstall", name]))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: false,
         }
     }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("cargo", &[], &["install", name]))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "cargo",
+            &[],
+            &["install", &format!("{name}@{version}"), "--force"],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("cargo", &[], &["info", name]))
+    }
 }
 
-fn run_cargo_command(args: &[&str]) -> Option<String> {
-    let output = command_output_with_timeout("cargo", args, Duration::from_secs(30)).ok()?;
+fn run_cargo_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner.output("cargo", args, Duration::from_secs(30)).ok()?;
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
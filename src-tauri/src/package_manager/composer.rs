@@ -1,13 +1,16 @@
 //! Composer (PHP) package manager support
 
-use super::{PackageInfo, PackageManager};
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
 use serde::Deserialize;
 
-use crate::utils::command::command_output_with_timeout;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub struct ComposerManager {
     version: String,
+    runner: Arc<dyn CommandRunner>,
 }
 
 #[derive(Deserialize)]
@@ -19,14 +22,18 @@ struct ComposerPackage {
 
 impl ComposerManager {
     pub fn new() -> Option<Self> {
-        let output = run_composer_command(&["--version"])?;
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        let output = run_composer_command(runner.as_ref(), &["--version"])?;
         // Extract version from "Composer version X.Y.Z ..."
         let version = output
             .split_whitespace()
             .nth(2)
             .unwrap_or("unknown")
             .to_string();
-        Some(Self { version })
+        Some(Self { version, runner })
     }
 }
 
@@ -47,7 +54,10 @@ impl PackageManager for ComposerManager {
         let mut packages = Vec::new();
 
         // Get global packages
-        let output = match run_composer_command(&["global", "show", "--format=json"]) {
+        let output = match run_composer_command(
+            self.runner.as_ref(),
+            &["global", "show", "--format=json"],
+        ) {
             Some(o) => o,
             None => return packages,
         };
@@ -71,6 +81,7 @@ impl PackageManager for ComposerManager {
                     manager: "composer".to_string(),
                     is_outdated: false,
                     description: pkg.description,
+                    scope: None,
                 });
             }
         }
@@ -78,23 +89,57 @@ impl PackageManager for ComposerManager {
         packages
     }
 
-    fn update_package(&self, name: &str) -> Result<String, String> {
-        match run_composer_command(&["global", "update", name]) {
-            Some(output) => Ok(format!("Updated {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to update {}", name)),
-        }
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("composer", &[], &["global", "update", name])
     }
 
-    fn uninstall_package(&self, name: &str) -> Result<String, String> {
-        match run_composer_command(&["global", "remove", name]) {
-            Some(output) => Ok(format!("Uninstalled {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to uninstall {}", name)),
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "composer",
+            &[],
+            &["global", "remove", name],
+        ))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: true,
         }
     }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "composer",
+            &[],
+            &["global", "require", name],
+        ))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "composer",
+            &[],
+            &["global", "require", &format!("{name}:{version}")],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("composer", &[], &["global", "show", name]))
+    }
+
+    fn update_all_command(&self) -> Option<PlannedCommand> {
+        Some(planned_command("composer", &[], &["global", "update"]))
+    }
 }
 
-fn run_composer_command(args: &[&str]) -> Option<String> {
-    let output = command_output_with_timeout("composer", args, Duration::from_secs(30)).ok()?;
+fn run_composer_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner
+        .output("composer", args, Duration::from_secs(30))
+        .ok()?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).to_string())
@@ -1,13 +1,16 @@
 //! Conda package manager support
 
-use super::{PackageInfo, PackageManager};
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
 use serde::Deserialize;
 
-use crate::utils::command::command_output_with_timeout;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub struct CondaManager {
     version: String,
+    runner: Arc<dyn CommandRunner>,
 }
 
 #[derive(Deserialize)]
@@ -19,14 +22,18 @@ struct CondaPackage {
 
 impl CondaManager {
     pub fn new() -> Option<Self> {
-        let output = run_conda_command(&["--version"])?;
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        let output = run_conda_command(runner.as_ref(), &["--version"])?;
         // Extract version from "conda X.Y.Z"
         let version = output
             .split_whitespace()
             .nth(1)
             .unwrap_or("unknown")
             .to_string();
-        Some(Self { version })
+        Some(Self { version, runner })
     }
 }
 
@@ -47,7 +54,7 @@ impl PackageManager for CondaManager {
         let mut packages = Vec::new();
 
         // Get packages in base environment
-        let output = match run_conda_command(&["list", "--json"]) {
+        let output = match run_conda_command(self.runner.as_ref(), &["list", "--json"]) {
             Some(o) => o,
             None => return packages,
         };
@@ -70,29 +77,54 @@ impl PackageManager for CondaManager {
                 manager: "conda".to_string(),
                 is_outdated: false,
                 description: pkg.channel,
+                scope: None,
             });
         }
 
         packages
     }
 
-    fn update_package(&self, name: &str) -> Result<String, String> {
-        match run_conda_command(&["update", "-y", name]) {
-            Some(output) => Ok(format!("Updated {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to update {}", name)),
-        }
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("conda", &[], &["update", "-y", name])
+    }
+
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("conda", &[], &["remove", "-y", name]))
     }
 
-    fn uninstall_package(&self, name: &str) -> Result<String, String> {
-        match run_conda_command(&["remove", "-y", name]) {
-            Some(output) => Ok(format!("Uninstalled {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to uninstall {}", name)),
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: true,
         }
     }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("conda", &[], &["install", "-y", name]))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "conda",
+            &[],
+            &["install", "-y", &format!("{name}={version}")],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("conda", &[], &["search", "--info", name]))
+    }
+
+    fn update_all_command(&self) -> Option<PlannedCommand> {
+        Some(planned_command("conda", &[], &["update", "--all", "-y"]))
+    }
 }
 
-fn run_conda_command(args: &[&str]) -> Option<String> {
-    let output = command_output_with_timeout("conda", args, Duration::from_secs(30)).ok()?;
+fn run_conda_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner.output("conda", args, Duration::from_secs(30)).ok()?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).to_string())
@@ -100,3 +132,24 @@ fn run_conda_command(args: &[&str]) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::ReplayRunner;
+
+    #[test]
+    fn lists_packages_from_recorded_output() {
+        let fixture = include_str!("../../fixtures/commands/conda.jsonl");
+        let runner = ReplayRunner::parse(fixture, "conda.jsonl").unwrap();
+        let manager = CondaManager::with_runner(Arc::new(runner)).unwrap();
+
+        let packages = manager.list_packages();
+
+        assert_eq!(manager.get_version().as_deref(), Some("24.9.2"));
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["numpy", "requests"]);
+        assert_eq!(packages[0].version, "1.26.4");
+        assert_eq!(packages[0].description.as_deref(), Some("conda-forge"));
+    }
+}
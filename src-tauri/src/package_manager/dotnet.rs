@@ -0,0 +1,152 @@
+//! .NET global tools support
+
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
+
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct DotnetToolManager {
+    version: String,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl DotnetToolManager {
+    pub fn new() -> Option<Self> {
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        // Fails without an SDK, which global tools need anyway
+        let output = run_dotnet_command(runner.as_ref(), &["--version"])?;
+        let version = output.trim().to_string();
+        Some(Self { version, runner })
+    }
+}
+
+impl PackageManager for DotnetToolManager {
+    fn name(&self) -> &str {
+        "dotnet"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn get_version(&self) -> Option<String> {
+        Some(self.version.clone())
+    }
+
+    fn list_packages(&self) -> Vec<PackageInfo> {
+        let output = match run_dotnet_command(self.runner.as_ref(), &["tool", "list", "-g"]) {
+            Some(o) => o,
+            None => return Vec::new(),
+        };
+
+        parse_tool_list(&output)
+            .into_iter()
+            .map(|(name, version)| PackageInfo {
+                name,
+                version,
+                latest: None,
+                manager: "dotnet".to_string(),
+                is_outdated: false,
+                description: None,
+                scope: None,
+            })
+            .collect()
+    }
+
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("dotnet", &[], &["tool", "update", "-g", name])
+    }
+
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "dotnet",
+            &[],
+            &["tool", "uninstall", "-g", name],
+        ))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: false,
+            batch_update: false,
+        }
+    }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "dotnet",
+            &[],
+            &["tool", "install", "-g", name],
+        ))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        // `tool update` also installs tools that are missing
+        Some(planned_command(
+            "dotnet",
+            &[],
+            &["tool", "update", "-g", name, "--version", version],
+        ))
+    }
+}
+
+/// Package id and version from `dotnet tool list -g`:
+///
+/// ```text
+/// Package Id      Version      Commands
+/// -------------------------------------------
+/// dotnet-ef       8.0.1        dotnet-ef
+/// ```
+fn parse_tool_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .skip_while(|line| !line.starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            Some((columns.next()?.to_string(), columns.next()?.to_string()))
+        })
+        .collect()
+}
+
+fn run_dotnet_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner
+        .output("dotnet", args, Duration::from_secs(30))
+        .ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_global_tool_list() {
+        let output = "Package Id                 Version      Commands\n\
+---------------------------------------------------------\n\
+dotnet-ef                  8.0.1        dotnet-ef\n\
+powershell                 7.4.5        pwsh\n";
+
+        assert_eq!(
+            parse_tool_list(output),
+            [
+                ("dotnet-ef".to_string(), "8.0.1".to_string()),
+                ("powershell".to_string(), "7.4.5".to_string()),
+            ]
+        );
+        assert!(parse_tool_list("Package Id      Version      Commands\n---\n").is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! RubyGems package manager support
+
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
+
+use crate::plan::PlannedCommand;
+use crate::utils::paths::home_dir;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct GemManager {
+    version: String,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl GemManager {
+    pub fn new() -> Option<Self> {
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        let output = run_gem_command(runner.as_ref(), &["--version"])?;
+        let version = output.trim().to_string();
+        Some(Self { version, runner })
+    }
+}
+
+impl PackageManager for GemManager {
+    fn name(&self) -> &str {
+        "gem"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn get_version(&self) -> Option<String> {
+        Some(self.version.clone())
+    }
+
+    fn list_packages(&self) -> Vec<PackageInfo> {
+        let output = match run_gem_command(self.runner.as_ref(), &["list", "--local", "--details"])
+        {
+            Some(o) => o,
+            None => return Vec::new(),
+        };
+        let home = home_dir();
+
+        parse_gem_details(&output)
+            .into_iter()
+            .map(|gem| {
+                // `gem install --user-install` and rbenv/rvm keep gems in the home directory
+                let is_user = match (&gem.installed_at, &home) {
+                    (Some(dir), Some(home)) => Path::new(dir).starts_with(home),
+                    _ => false,
+                };
+                PackageInfo {
+                    name: gem.name,
+                    version: gem.version,
+                    latest: None,
+                    manager: "gem".to_string(),
+                    is_outdated: false,
+                    description: gem.summary,
+                    scope: Some(if is_user { "user" } else { "system" }.to_string()),
+                }
+            })
+            .collect()
+    }
+
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("gem", &[], &["update", name])
+    }
+
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "gem",
+            &[],
+            &["uninstall", "--all", "--executables", name],
+        ))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: true,
+        }
+    }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("gem", &[], &["install", name]))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "gem",
+            &[],
+            &["install", name, "--version", version],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("gem", &[], &["info", name]))
+    }
+
+    fn update_all_command(&self) -> Option<PlannedCommand> {
+        Some(planned_command("gem", &[], &["update"]))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ParsedGem {
+    name: String,
+    /// Newest installed version
+    version: String,
+    /// Gem directory of the newest version
+    installed_at: Option<String>,
+    summary: Option<String>,
+}
+
+/// Parse `gem list --local --details`:
+///
+/// ```text
+/// rake (13.1.0, 13.0.6)
+///     Author: Hiroshi SHIBATA, Eric Hodel, Jim Weirich
+///     Installed at (13.1.0): /home/dev/.local/share/gem/ruby/3.2.0
+///                  (13.0.6): /usr/lib/ruby/gems/3.2.0
+///
+///     Rake is a Make-like program implemented in Ruby
+/// ```
+fn parse_gem_details(output: &str) -> Vec<ParsedGem> {
+    let mut gems: Vec<ParsedGem> = Vec::new();
+    let mut after_blank = false;
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            after_blank = true;
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            after_blank = false;
+            let Some((name, versions)) = line.trim_end().split_once(" (") else {
+                continue;
+            };
+            let version = versions
+                .trim_end_matches(')')
+                .split(", ")
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("default: ");
+            gems.push(ParsedGem {
+                name: name.to_string(),
+                version: version.to_string(),
+                installed_at: None,
+                summary: None,
+            });
+            continue;
+        }
+
+        let Some(gem) = gems.last_mut() else {
+            continue;
+        };
+        let line = line.trim();
+        if after_blank {
+            gem.summary.get_or_insert_with(|| line.to_string());
+        } else if let Some(location) = line.strip_prefix("Installed at") {
+            if gem.installed_at.is_none() {
+                let dir = location.split_once(": ").map_or(location, |(_, dir)| dir);
+                gem.installed_at = Some(dir.trim().to_string());
+            }
+        }
+    }
+
+    gems
+}
+
+fn run_gem_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner.output("gem", args, Duration::from_secs(30)).ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gem_details_with_install_locations() {
+        let output = "\n*** LOCAL GEMS ***\n\n\
+bundler (default: 2.4.19)\n    Authors: André Arko, Samuel Giddins\n    Homepage: https://bundler.io\n    License: MIT\n    Installed at (default): /usr/lib/ruby/gems/3.2.0\n\n    The best way to manage your application's dependencies\n\n\
+rake (13.1.0, 13.0.6)\n    Author: Hiroshi SHIBATA, Eric Hodel, Jim Weirich\n    License: MIT\n    Installed at (13.1.0): /home/dev/.local/share/gem/ruby/3.2.0\n                 (13.0.6): /usr/lib/ruby/gems/3.2.0\n\n    Rake is a Make-like program implemented in Ruby\n\n\
+rubocop (1.66.1)\n    Author: Bozhidar Batsov\n    Installed at: /var/lib/gems/3.2.0\n\n    Automatic Ruby code style checking tool.\n";
+
+        let gems = parse_gem_details(output);
+        assert_eq!(gems.len(), 3);
+        assert_eq!(gems[0].version, "2.4.19");
+        assert_eq!(
+            gems[0].installed_at.as_deref(),
+            Some("/usr/lib/ruby/gems/3.2.0")
+        );
+        assert_eq!(
+            gems[1],
+            ParsedGem {
+                name: "rake".to_string(),
+                version: "13.1.0".to_string(),
+                installed_at: Some("/home/dev/.local/share/gem/ruby/3.2.0".to_string()),
+                summary: Some("Rake is a Make-like program implemented in Ruby".to_string()),
+            }
+        );
+        assert_eq!(gems[2].installed_at.as_deref(), Some("/var/lib/gems/3.2.0"));
+    }
+}
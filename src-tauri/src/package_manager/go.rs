@@ -0,0 +1,349 @@
+//! Binaries installed with `go install`
+//! Go has no package list; the module path and version come from the build info embedded in
+//! each binary in `GOBIN` (or `GOPATH/bin`)
+
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
+
+use crate::error::DevJanitorError;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct GoManager {
+    version: String,
+    bin_dir: PathBuf,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl GoManager {
+    pub fn new() -> Option<Self> {
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        // "go version go1.22.0 linux/amd64"
+        let output = run_go_command(runner.as_ref(), &["version"])?;
+        let version = output
+            .split_whitespace()
+            .nth(2)
+            .map(|version| version.trim_start_matches("go"))
+            .unwrap_or("unknown")
+            .to_string();
+
+        let env = run_go_command(runner.as_ref(), &["env", "GOBIN", "GOPATH"])?;
+        let mut lines = env.lines().map(str::trim);
+        let (gobin, gopath) = (lines.next().unwrap_or_default(), lines.next()?);
+        let bin_dir = if gobin.is_empty() {
+            std::env::split_paths(gopath).next()?.join("bin")
+        } else {
+            PathBuf::from(gobin)
+        };
+
+        Some(Self {
+            version,
+            bin_dir,
+            runner,
+        })
+    }
+
+    fn binaries(&self) -> Vec<GoBinary> {
+        let dir = self.bin_dir.to_string_lossy();
+        run_go_command(self.runner.as_ref(), &["version", "-m", &dir])
+            .map(|output| parse_build_info(&output))
+            .unwrap_or_default()
+    }
+
+    /// The installed binary built from `package`, as `go version -m` reports it
+    fn binary_path(&self, package: &str) -> Option<PathBuf> {
+        self.binaries()
+            .into_iter()
+            .find(|binary| binary.package == package)
+            .map(|binary| PathBuf::from(binary.path))
+    }
+}
+
+impl PackageManager for GoManager {
+    fn name(&self) -> &str {
+        "go"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn get_version(&self) -> Option<String> {
+        Some(self.version.clone())
+    }
+
+    fn list_packages(&self) -> Vec<PackageInfo> {
+        self.binaries()
+            .into_iter()
+            .map(|binary| PackageInfo {
+                name: binary.package,
+                version: binary.version,
+                latest: None,
+                manager: "go".to_string(),
+                is_outdated: false,
+                description: None,
+                scope: None,
+            })
+            .collect()
+    }
+
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("go", &[], &["install", &format!("{name}@latest")])
+    }
+
+    /// Go has no uninstall; the binary is removed as the installed file
+    fn uninstall_command(&self, _name: &str) -> Option<PlannedCommand> {
+        None
+    }
+
+    fn installed_file(&self, name: &str) -> Result<PathBuf, DevJanitorError> {
+        self.binary_path(name).ok_or_else(|| {
+            DevJanitorError::NotATarget(format!(
+                "No binary in {} was built from {}",
+                self.bin_dir.display(),
+                name
+            ))
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: false,
+        }
+    }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        let spec = if name.contains('@') {
+            name.to_string()
+        } else {
+            format!("{name}@latest")
+        };
+        Some(planned_command("go", &[], &["install", &spec]))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        let version = if version.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("v{version}")
+        } else {
+            version.to_string()
+        };
+        Some(planned_command(
+            "go",
+            &[],
+            &["install", &format!("{name}@{version}")],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        // Reading build info is harmless, so a binary not listed still gets a best guess
+        let path = self
+            .binary_path(name)
+            .unwrap_or_else(|| self.bin_dir.join(binary_name(name)));
+        Some(planned_command(
+            "go",
+            &[],
+            &["version", "-m", &path.to_string_lossy()],
+        ))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct GoBinary {
+    path: String,
+    /// Import path of the main package, what `go install` takes
+    package: String,
+    /// Module version without the `v` prefix, or `(devel)` for local builds
+    version: String,
+}
+
+/// Parse `go version -m <dir>`:
+///
+/// ```text
+/// /home/dev/go/bin/gopls: go1.22.0
+///         path    golang.org/x/tools/gopls
+///         mod     golang.org/x/tools/gopls        v0.15.1 h1:...
+/// ```
+fn parse_build_info(output: &str) -> Vec<GoBinary> {
+    let mut binaries: Vec<GoBinary> = Vec::new();
+
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some((path, _)) = line.rsplit_once(": ") {
+                binaries.push(GoBinary {
+                    path: path.to_string(),
+                    package: String::new(),
+                    version: String::new(),
+                });
+            }
+            continue;
+        }
+
+        let Some(binary) = binaries.last_mut() else {
+            continue;
+        };
+        let fields: Vec<&str> = line
+            .split('\t')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .collect();
+        match fields.as_slice() {
+            ["path", package, ..] => binary.package = package.to_string(),
+            ["mod", _, version, ..] => {
+                binary.version = version.trim_start_matches('v').to_string();
+            }
+            _ => {}
+        }
+    }
+
+    binaries.retain(|binary| !binary.package.is_empty());
+    binaries
+}
+
+/// File name `go install` gives a package's binary; a `/vN` major version suffix is skipped
+fn binary_name(package: &str) -> String {
+    let mut segments = package.rsplit('/');
+    let last = segments.next().unwrap_or(package);
+    let is_major_suffix =
+        last.len() > 1 && last.starts_with('v') && last[1..].chars().all(|c| c.is_ascii_digit());
+    let name = match segments.next() {
+        Some(parent) if is_major_suffix => parent,
+        _ => last,
+    };
+    format!("{}{}", name, std::env::consts::EXE_SUFFIX)
+}
+
+fn run_go_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner.output("go", args, Duration::from_secs(30)).ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::{with_thread_runner, ReplayRunner};
+
+    #[test]
+    fn lists_binaries_from_embedded_build_info() {
+        let build_info = "/home/dev/go/bin/gopls: go1.22.0\n\
+\tpath\tgolang.org/x/tools/gopls\n\
+\tmod\tgolang.org/x/tools/gopls\tv0.15.1\th1:abc=\n\
+\tdep\tgolang.org/x/mod\tv0.15.0\th1:def=\n\
+\tbuild\t-compiler=gc\n\
+/home/dev/go/bin/golangci-lint: go1.22.1\n\
+\tpath\tgithub.com/golangci/golangci-lint/cmd/golangci-lint\n\
+\tmod\tgithub.com/golangci/golangci-lint\tv1.57.2\th1:ghi=\n";
+        let runner = ReplayRunner::default()
+            .with_output("go", &["version"], "go version go1.22.1 linux/amd64\n")
+            .with_output("go", &["env", "GOBIN", "GOPATH"], "\n/home/dev/go\n")
+            .with_output("go", &["version", "-m", "/home/dev/go/bin"], build_info);
+        let manager = GoManager::with_runner(Arc::new(runner)).unwrap();
+
+        assert_eq!(manager.get_version().as_deref(), Some("1.22.1"));
+        let packages = manager.list_packages();
+        let found: Vec<(&str, &str)> = packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.version.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("golang.org/x/tools/gopls", "0.15.1"),
+                (
+                    "github.com/golangci/golangci-lint/cmd/golangci-lint",
+                    "1.57.2"
+                ),
+            ]
+        );
+        assert_eq!(
+            manager
+                .update_command("golang.org/x/tools/gopls")
+                .command_line,
+            "go install golang.org/x/tools/gopls@latest"
+        );
+        assert!(manager
+            .uninstall_command("golang.org/x/tools/gopls")
+            .is_none());
+        assert_eq!(
+            manager
+                .installed_file("github.com/golangci/golangci-lint/cmd/golangci-lint")
+                .unwrap(),
+            PathBuf::from("/home/dev/go/bin/golangci-lint")
+        );
+        // Never a guessed path: only binaries that carry the package's build info
+        let error = manager
+            .installed_file("github.com/go-task/task/v3/cmd/task")
+            .unwrap_err();
+        assert_eq!(error.code(), "not_a_target");
+    }
+
+    #[test]
+    fn uninstall_removes_only_reported_binaries() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let bin_dir = std::env::temp_dir().join(format!("dev-janitor-go-bin-{nanos}"));
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        let gopls = bin_dir.join("gopls");
+        std::fs::write(&gopls, vec![0u8; 1024]).unwrap();
+        std::fs::write(bin_dir.join("task"), "unrelated").unwrap();
+
+        let dir = bin_dir.to_string_lossy().to_string();
+        let build_info = format!(
+            "{}: go1.22.0\n\tpath\tgolang.org/x/tools/gopls\n",
+            gopls.display()
+        );
+        let runner = ReplayRunner::default()
+            .with_output("go", &["version"], "go version go1.22.0 linux/amd64\n")
+            .with_output(
+                "go",
+                &["env", "GOBIN", "GOPATH"],
+                &format!("{dir}\n/unused\n"),
+            )
+            .with_output("go", &["version", "-m", &dir], &build_info);
+        let runner: Arc<dyn CommandRunner> = Arc::new(runner);
+
+        let missing = with_thread_runner(Arc::clone(&runner), || {
+            crate::package_manager::uninstall_package("go", "github.com/go-task/task/v3/cmd/task")
+        });
+        assert_eq!(missing.unwrap_err().code(), "not_a_target");
+        assert!(bin_dir.join("task").exists());
+
+        let result = with_thread_runner(runner, || {
+            crate::package_manager::uninstall_package("go", "golang.org/x/tools/gopls")
+        })
+        .unwrap();
+        assert_eq!(result.items_removed, 1);
+        assert!(result.command.is_none());
+        assert!(!gopls.exists());
+        std::fs::remove_dir_all(bin_dir).unwrap();
+    }
+
+    #[test]
+    fn binary_names_skip_major_version_suffixes() {
+        let exe = std::env::consts::EXE_SUFFIX;
+        assert_eq!(
+            binary_name("golang.org/x/tools/gopls"),
+            format!("gopls{exe}")
+        );
+        assert_eq!(
+            binary_name("github.com/go-task/task/v3/cmd/task"),
+            format!("task{exe}")
+        );
+        assert_eq!(binary_name("mvdan.cc/gofumpt/v2"), format!("gofumpt{exe}"));
+    }
+}
@@ -1,24 +1,31 @@
 //! Homebrew package manager support (macOS and Linux)
 
-use super::{PackageInfo, PackageManager};
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
 
-use crate::utils::command::command_output_with_timeout;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub struct HomebrewManager {
     version: String,
+    runner: Arc<dyn CommandRunner>,
 }
 
 impl HomebrewManager {
     pub fn new() -> Option<Self> {
-        let output = run_brew_command(&["--version"])?;
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        let output = run_brew_command(runner.as_ref(), &["--version"])?;
         let version = output
             .lines()
             .next()
             .and_then(|line| line.split_whitespace().nth(1))
             .unwrap_or("unknown")
             .to_string();
-        Some(Self { version })
+        Some(Self { version, runner })
     }
 }
 
@@ -38,12 +45,14 @@ impl PackageManager for HomebrewManager {
     fn list_packages(&self) -> Vec<PackageInfo> {
         let mut packages = Vec::new();
 
-        let output = match run_brew_command(&["list", "--formula", "--versions"]) {
-            Some(o) => o,
-            None => return packages,
-        };
+        let output =
+            match run_brew_command(self.runner.as_ref(), &["list", "--formula", "--versions"]) {
+                Some(o) => o,
+                None => return packages,
+            };
 
-        let outdated_output = run_brew_command(&["outdated", "--formula"]).unwrap_or_default();
+        let outdated_output =
+            run_brew_command(self.runner.as_ref(), &["outdated", "--formula"]).unwrap_or_default();
         let outdated_names: std::collections::HashSet<String> = outdated_output
             .lines()
             .map(|l| l.split_whitespace().next().unwrap_or("").to_string())
@@ -63,6 +72,7 @@ impl PackageManager for HomebrewManager {
                     manager: "homebrew".to_string(),
                     is_outdated,
                     description: None,
+                    scope: None,
                 });
             }
         }
@@ -70,23 +80,39 @@ impl PackageManager for HomebrewManager {
         packages
     }
 
-    fn update_package(&self, name: &str) -> Result<String, String> {
-        match run_brew_command(&["upgrade", name]) {
-            Some(output) => Ok(format!("Updated {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to update {}", name)),
-        }
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("brew", &[], &["upgrade", name])
+    }
+
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("brew", &[], &["uninstall", name]))
     }
 
-    fn uninstall_package(&self, name: &str) -> Result<String, String> {
-        match run_brew_command(&["uninstall", name]) {
-            Some(output) => Ok(format!("Uninstalled {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to uninstall {}", name)),
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: true,
+            install: true,
+            pin: false,
+            info: true,
+            batch_update: true,
         }
     }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("brew", &[], &["install", name]))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("brew", &[], &["info", name]))
+    }
+
+    fn update_all_command(&self) -> Option<PlannedCommand> {
+        Some(planned_command("brew", &[], &["upgrade"]))
+    }
 }
 
-fn run_brew_command(args: &[&str]) -> Option<String> {
-    let output = command_output_with_timeout("brew", args, Duration::from_secs(30)).ok()?;
+fn run_brew_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner.output("brew", args, Duration::from_secs(30)).ok()?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).to_string())
@@ -94,3 +120,33 @@ fn run_brew_command(args: &[&str]) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::ReplayRunner;
+
+    #[test]
+    fn marks_outdated_formulae_from_recorded_output() {
+        let fixture = include_str!("../../fixtures/commands/brew.jsonl");
+        let runner = ReplayRunner::parse(fixture, "brew.jsonl").unwrap();
+        let manager = HomebrewManager::with_runner(Arc::new(runner)).unwrap();
+
+        let packages = manager.list_packages();
+
+        assert_eq!(manager.get_version().as_deref(), Some("4.4.2"));
+        let outdated: Vec<&str> = packages
+            .iter()
+            .filter(|p| p.is_outdated)
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(outdated, ["node", "openssl@3"]);
+        assert_eq!(packages.len(), 4);
+        assert_eq!(packages[1].version, "22.9.0");
+    }
+
+    #[test]
+    fn missing_brew_is_not_available() {
+        assert!(HomebrewManager::with_runner(Arc::new(ReplayRunner::default())).is_none());
+    }
+}
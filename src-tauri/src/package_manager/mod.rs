@@ -1,9 +1,14 @@
 //! Package manager module for Dev Janitor v2
-//! Supports npm, pip, Cargo, Composer, Homebrew, Conda, etc.
+//! Supports npm, pnpm, Yarn, Bun, pip, Cargo, Composer, Homebrew, Conda, RubyGems, .NET tools and
+//! binaries installed with `go install`
 
+pub mod bun;
 pub mod cargo;
 pub mod composer;
 pub mod conda;
+pub mod dotnet;
+pub mod gem;
+pub mod go;
 pub mod homebrew;
 pub mod npm;
 pub mod pip;
@@ -12,6 +17,24 @@ pub mod yarn;
 
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::disk_usage::{format_size, path_size};
+use crate::error::DevJanitorError;
+use crate::journal::{self, JournalEntry};
+use crate::operation::{run_first_success, OperationResult};
+use crate::plan::PlannedCommand;
+use crate::quarantine::{removal_description, remove_cleanup_target};
+use crate::utils::runner::{active_runner, CommandRunner};
+
+/// Timeout for package uninstall and info commands
+const PACKAGE_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for commands that download and possibly build packages: install, pin, update and
+/// update all (`cargo install` compiles from source)
+const PACKAGE_INSTALL_TIMEOUT: Duration = Duration::from_secs(600);
 
 /// Represents a global package from any package manager
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,10 +45,34 @@ pub struct PackageInfo {
     pub manager: String,
     pub is_outdated: bool,
     pub description: Option<String>,
+    /// `user` or `system`, for managers that install to both
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+/// Operations a package manager supports besides listing, updating and uninstalling packages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// `list_packages` fills in `latest` and `is_outdated`
+    pub outdated_check: bool,
+    pub install: bool,
+    /// Install an exact version of a package
+    pub pin: bool,
+    pub info: bool,
+    /// Update every package with one command
+    pub batch_update: bool,
+}
+
+/// An available package manager and what it can do
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagerSummary {
+    pub name: String,
+    pub version: Option<String>,
+    pub capabilities: Capabilities,
 }
 
 /// Common trait for all package managers
-pub trait PackageManager {
+pub trait PackageManager: Send + Sync {
     /// Get the name of this package manager
     fn name(&self) -> &str;
 
@@ -38,70 +85,408 @@ pub trait PackageManager {
     /// List all global packages
     fn list_packages(&self) -> Vec<PackageInfo>;
 
-    /// Update a package to the latest version
-    fn update_package(&self, name: &str) -> Result<String, String>;
+    /// Command that updates a package to the latest version
+    fn update_command(&self, name: &str) -> PlannedCommand;
+
+    /// Command that uninstalls a package; `None` for managers without one, whose packages
+    /// are removed as their `installed_file` instead
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand>;
+
+    /// The file that makes up an installed package, for managers without an uninstall command
+    fn installed_file(&self, _name: &str) -> Result<PathBuf, DevJanitorError> {
+        Err(DevJanitorError::InvalidInput(format!(
+            "{} cannot uninstall packages",
+            self.name()
+        )))
+    }
+
+    /// Optional operations this manager supports; each one comes with its command below
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    /// Command that installs a package globally
+    fn install_command(&self, _name: &str) -> Option<PlannedCommand> {
+        None
+    }
+
+    /// Command that installs exactly `version` of a package
+    fn pin_command(&self, _name: &str, _version: &str) -> Option<PlannedCommand> {
+        None
+    }
+
+    /// Command that prints the details of a package
+    fn info_command(&self, _name: &str) -> Option<PlannedCommand> {
+        None
+    }
+
+    /// Command that updates every global package
+    fn update_all_command(&self) -> Option<PlannedCommand> {
+        None
+    }
+}
+
+fn planned_command(program: &str, prefix_args: &[String], args: &[&str]) -> PlannedCommand {
+    let mut full_args = prefix_args.to_vec();
+    full_args.extend(args.iter().map(|arg| arg.to_string()));
+    PlannedCommand::owned(program, full_args)
+}
+
+/// Probes for one manager, returning it when it is installed
+type ManagerFactory = fn(Arc<dyn CommandRunner>) -> Option<Box<dyn PackageManager>>;
+
+fn boxed<M: PackageManager + 'static>(found: Option<M>) -> Option<Box<dyn PackageManager>> {
+    found.map(|manager| Box::new(manager) as Box<dyn PackageManager>)
+}
+
+/// Every supported package manager by name; a new manager plugs in with one entry here
+const MANAGERS: &[(&str, ManagerFactory)] = &[
+    ("npm", |runner| boxed(npm::NpmManager::with_runner(runner))),
+    ("pnpm", |runner| {
+        boxed(pnpm::PnpmManager::with_runner(runner))
+    }),
+    ("yarn", |runner| {
+        boxed(yarn::YarnManager::with_runner(runner))
+    }),
+    ("pip", |runner| boxed(pip::PipManager::with_runner(runner))),
+    ("cargo", |runner| {
+        boxed(cargo::CargoManager::with_runner(runner))
+    }),
+    ("composer", |runner| {
+        boxed(composer::ComposerManager::with_runner(runner))
+    }),
+    ("homebrew", |runner| {
+        boxed(homebrew::HomebrewManager::with_runner(runner))
+    }),
+    ("conda", |runner| {
+        boxed(conda::CondaManager::with_runner(runner))
+    }),
+    ("bun", |runner| boxed(bun::BunManager::with_runner(runner))),
+    ("gem", |runner| boxed(gem::GemManager::with_runner(runner))),
+    ("go", |runner| boxed(go::GoManager::with_runner(runner))),
+    ("dotnet", |runner| {
+        boxed(dotnet::DotnetToolManager::with_runner(runner))
+    }),
+];
+
+/// The package managers installed on this system
+pub struct ManagerRegistry {
+    managers: Vec<Box<dyn PackageManager>>,
+}
+
+impl ManagerRegistry {
+    /// Probe every supported manager in parallel
+    pub fn detect() -> Self {
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        let managers = MANAGERS
+            .par_iter()
+            .filter_map(|(_, create)| create(Arc::clone(&runner)))
+            .collect();
+        Self { managers }
+    }
+
+    pub fn managers(&self) -> &[Box<dyn PackageManager>] {
+        &self.managers
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn PackageManager> {
+        self.managers
+            .iter()
+            .find(|manager| manager.name() == name)
+            .map(|manager| manager.as_ref())
+    }
+
+    /// Global packages of every manager, sorted by manager and name
+    pub fn list_packages(&self) -> Vec<PackageInfo> {
+        let mut all_packages: Vec<PackageInfo> = self
+            .managers
+            .par_iter()
+            .flat_map(|manager| manager.list_packages())
+            .collect();
+
+        all_packages.sort_by(|left, right| {
+            left.manager
+                .cmp(&right.manager)
+                .then_with(|| left.name.cmp(&right.name))
+        });
+
+        all_packages
+    }
 
-    /// Uninstall a package
-    fn uninstall_package(&self, name: &str) -> Result<String, String>;
+    pub fn summaries(&self) -> Vec<ManagerSummary> {
+        self.managers
+            .iter()
+            .map(|manager| ManagerSummary {
+                name: manager.name().to_string(),
+                version: manager.get_version(),
+                capabilities: manager.capabilities(),
+            })
+            .collect()
+    }
 }
 
-type PackageScanFn = fn() -> Vec<PackageInfo>;
+/// Look up an available package manager by name
+pub fn get_manager(manager: &str) -> Result<Box<dyn PackageManager>, DevJanitorError> {
+    let (_, create) = MANAGERS
+        .iter()
+        .find(|(name, _)| *name == manager)
+        .ok_or_else(|| {
+            DevJanitorError::InvalidInput(format!("Unknown package manager: {}", manager))
+        })?;
 
-fn scan_npm_packages() -> Vec<PackageInfo> {
-    npm::NpmManager::new().map_or_else(Vec::new, |manager| manager.list_packages())
+    create(active_runner())
+        .ok_or_else(|| DevJanitorError::ToolNotFound(format!("{} is not available", manager)))
 }
 
-fn scan_pnpm_packages() -> Vec<PackageInfo> {
-    pnpm::PnpmManager::new().map_or_else(Vec::new, |manager| manager.list_packages())
+fn unsupported(manager: &dyn PackageManager, operation: &str) -> DevJanitorError {
+    DevJanitorError::InvalidInput(format!("{} cannot {}", manager.name(), operation))
 }
 
-fn scan_yarn_packages() -> Vec<PackageInfo> {
-    yarn::YarnManager::new().map_or_else(Vec::new, |manager| manager.list_packages())
+/// Installed package managers with their versions and capabilities
+pub fn package_managers() -> Vec<ManagerSummary> {
+    ManagerRegistry::detect().summaries()
 }
 
-fn scan_pip_packages() -> Vec<PackageInfo> {
-    pip::PipManager::new().map_or_else(Vec::new, |manager| manager.list_packages())
+/// Update a package through the named manager and journal the outcome
+pub fn update_package(manager: &str, name: &str) -> Result<OperationResult, DevJanitorError> {
+    run_package_action(
+        "update_package",
+        manager,
+        name,
+        PACKAGE_INSTALL_TIMEOUT,
+        |m| Ok(m.update_command(name)),
+    )
 }
 
-fn scan_cargo_packages() -> Vec<PackageInfo> {
-    cargo::CargoManager::new().map_or_else(Vec::new, |manager| manager.list_packages())
+/// Uninstall a package through the named manager and journal the outcome
+///
+/// Packages of managers without an uninstall command have their file moved into quarantine
+/// (or deleted, per settings) like any cleanup target.
+pub fn uninstall_package(manager: &str, name: &str) -> Result<OperationResult, DevJanitorError> {
+    let action = "uninstall_package";
+    let found =
+        get_manager(manager).map_err(|error| journal_failure(action, manager, name, error))?;
+    if let Some(command) = found.uninstall_command(name) {
+        return run_first_success(
+            action,
+            name,
+            Some(manager),
+            &[command],
+            PACKAGE_COMMAND_TIMEOUT,
+        );
+    }
+
+    let path = found
+        .installed_file(name)
+        .map_err(|error| journal_failure(action, manager, name, error))?;
+    let size = path_size(&path);
+    let result = remove_cleanup_target(&path, "package", size);
+    let journaled = journal::record_cleanup(action, &path, size, &result);
+    let entry = result?;
+
+    Ok(OperationResult::cleaned(
+        name,
+        size,
+        entry.is_some(),
+        format!(
+            "Removed {} ({}, {})",
+            path.display(),
+            format_size(size),
+            removal_description(&entry)
+        ),
+    )
+    .journaled(journaled))
+}
+
+/// Install a package through the named manager and journal the outcome
+pub fn install_package(manager: &str, name: &str) -> Result<OperationResult, DevJanitorError> {
+    run_package_action(
+        "install_package",
+        manager,
+        name,
+        PACKAGE_INSTALL_TIMEOUT,
+        |m| {
+            m.install_command(name)
+                .ok_or_else(|| unsupported(m, "install packages"))
+        },
+    )
 }
 
-fn scan_composer_packages() -> Vec<PackageInfo> {
-    composer::ComposerManager::new().map_or_else(Vec::new, |manager| manager.list_packages())
+/// Install exactly `version` of a package and journal the outcome
+pub fn pin_package(
+    manager: &str,
+    name: &str,
+    version: &str,
+) -> Result<OperationResult, DevJanitorError> {
+    run_package_action("pin_package", manager, name, PACKAGE_INSTALL_TIMEOUT, |m| {
+        m.pin_command(name, version)
+            .ok_or_else(|| unsupported(m, "install a specific version"))
+    })
 }
 
-fn scan_homebrew_packages() -> Vec<PackageInfo> {
-    homebrew::HomebrewManager::new().map_or_else(Vec::new, |manager| manager.list_packages())
+/// Update every global package of the named manager and journal the outcome
+pub fn update_all_packages(manager: &str) -> Result<OperationResult, DevJanitorError> {
+    run_package_action(
+        "update_all_packages",
+        manager,
+        "all packages",
+        PACKAGE_INSTALL_TIMEOUT,
+        |m| {
+            m.update_all_command()
+                .ok_or_else(|| unsupported(m, "update all packages at once"))
+        },
+    )
 }
 
-fn scan_conda_packages() -> Vec<PackageInfo> {
-    conda::CondaManager::new().map_or_else(Vec::new, |manager| manager.list_packages())
+/// What the named manager reports about a package
+pub fn package_info(manager: &str, name: &str) -> Result<String, DevJanitorError> {
+    let found = get_manager(manager)?;
+    let command = found
+        .info_command(name)
+        .ok_or_else(|| unsupported(found.as_ref(), "show package details"))?;
+    let output = active_runner()
+        .output_vec(&command.program, &command.args, PACKAGE_COMMAND_TIMEOUT)
+        .map_err(|error| DevJanitorError::spawn(&command.program, error))?;
+    if !output.status.success() {
+        return Err(DevJanitorError::CommandFailed(format!(
+            "{}: {}",
+            command.command_line,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn run_package_action(
+    action: &str,
+    manager: &str,
+    name: &str,
+    timeout: Duration,
+    command: impl FnOnce(&dyn PackageManager) -> Result<PlannedCommand, DevJanitorError>,
+) -> Result<OperationResult, DevJanitorError> {
+    let command = get_manager(manager)
+        .and_then(|found| command(found.as_ref()))
+        .map_err(|error| journal_failure(action, manager, name, error))?;
+
+    run_first_success(action, name, Some(manager), &[command], timeout)
+}
+
+/// Journal an operation that failed before any command ran
+fn journal_failure(
+    action: &str,
+    manager: &str,
+    name: &str,
+    error: DevJanitorError,
+) -> DevJanitorError {
+    let mut entry = JournalEntry::new(action, name);
+    entry.manager = Some(manager.to_string());
+    entry.success = false;
+    entry.error = Some(error.to_string());
+    entry.error_code = Some(error.code().to_string());
+    // The operation's own error is what the caller reports
+    let _ = journal::record(&entry);
+    error
 }
 
 /// Scan all available package managers and list their packages
 pub fn scan_all_packages() -> Vec<PackageInfo> {
-    let package_scanners: [PackageScanFn; 8] = [
-        scan_npm_packages,
-        scan_pnpm_packages,
-        scan_yarn_packages,
-        scan_pip_packages,
-        scan_cargo_packages,
-        scan_composer_packages,
-        scan_homebrew_packages,
-        scan_conda_packages,
-    ];
-
-    let mut all_packages: Vec<PackageInfo> = package_scanners
-        .par_iter()
-        .flat_map(|scan| scan())
-        .collect();
-
-    all_packages.sort_by(|left, right| {
-        left.manager
-            .cmp(&right.manager)
-            .then_with(|| left.name.cmp(&right.name))
-    });
-
-    all_packages
+    ManagerRegistry::detect().list_packages()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::{with_thread_runner, ReplayRunner};
+
+    #[test]
+    fn package_operations_go_through_the_active_runner() {
+        let fixture = include_str!("../../fixtures/commands/npm.jsonl");
+        let runner = ReplayRunner::parse(fixture, "npm.jsonl").unwrap();
+
+        let result =
+            with_thread_runner(Arc::new(runner), || uninstall_package("npm", "typescript"))
+                .unwrap();
+        assert_eq!(
+            result.command.as_deref(),
+            Some("npm uninstall -g typescript --force")
+        );
+        assert!(result
+            .output
+            .unwrap()
+            .starts_with("removed 1 package in 143ms"));
+
+        // Nothing recorded for this package, so a real npm would have been needed
+        let runner = ReplayRunner::parse(fixture, "npm.jsonl").unwrap();
+        let error = with_thread_runner(Arc::new(runner), || uninstall_package("npm", "left-pad"))
+            .unwrap_err();
+        assert_eq!(error.code(), "tool_missing");
+    }
+
+    #[test]
+    fn registry_holds_available_managers_and_their_commands() {
+        let runner = ReplayRunner::default()
+            .with_output("npm", &["--version"], "10.8.2\n")
+            .with_output(
+                "cargo",
+                &["--version"],
+                "cargo 1.97.1 (2b4f3ec41 2026-08-02)\n",
+            )
+            .with_output("brew", &["--version"], "Homebrew 4.6.3\n")
+            .with_output("conda", &["--version"], "conda 25.7.0\n")
+            .with_output("gem", &["--version"], "3.4.20\n")
+            .with_output("dotnet", &["--version"], "8.0.401\n");
+        let registry = ManagerRegistry::with_runner(Arc::new(runner));
+
+        let names: Vec<&str> = registry.managers().iter().map(|m| m.name()).collect();
+        assert_eq!(
+            names,
+            ["npm", "cargo", "homebrew", "conda", "gem", "dotnet"]
+        );
+        assert!(registry.get("pip").is_none());
+
+        // A declared capability always comes with its command
+        for manager in registry.managers() {
+            let capabilities = manager.capabilities();
+            let name = manager.name();
+            assert_eq!(
+                capabilities.install,
+                manager.install_command("x").is_some(),
+                "{name}"
+            );
+            assert_eq!(
+                capabilities.pin,
+                manager.pin_command("x", "1.0.0").is_some(),
+                "{name}"
+            );
+            assert_eq!(
+                capabilities.info,
+                manager.info_command("x").is_some(),
+                "{name}"
+            );
+            assert_eq!(
+                capabilities.batch_update,
+                manager.update_all_command().is_some(),
+                "{name}"
+            );
+        }
+
+        let cargo = registry.get("cargo").unwrap();
+        assert_eq!(
+            cargo.pin_command("ripgrep", "14.1.0").unwrap().command_line,
+            "cargo install ripgrep@14.1.0 --force"
+        );
+        assert!(!cargo.capabilities().batch_update);
+        assert_eq!(
+            registry
+                .get("homebrew")
+                .unwrap()
+                .update_all_command()
+                .unwrap()
+                .command_line,
+            "brew upgrade"
+        );
+    }
 }
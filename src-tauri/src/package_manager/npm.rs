@@ -1,13 +1,16 @@
 //! npm package manager support
 
-use super::{PackageInfo, PackageManager};
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
 use serde::Deserialize;
 
-use crate::utils::command::command_output_with_timeout;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub struct NpmManager {
     version: String,
+    runner: Arc<dyn CommandRunner>,
 }
 
 #[derive(Deserialize)]
@@ -28,9 +31,13 @@ struct NpmOutdatedPackage {
 
 impl NpmManager {
     pub fn new() -> Option<Self> {
-        let output = run_npm_command(&["--version"])?;
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
+        let output = run_npm_command(runner.as_ref(), &["--version"])?;
         let version = output.trim().to_string();
-        Some(Self { version })
+        Some(Self { version, runner })
     }
 }
 
@@ -51,18 +58,22 @@ impl PackageManager for NpmManager {
         let mut packages = Vec::new();
 
         // Get global packages
-        let output = match run_npm_command(&["list", "-g", "--depth=0", "--json"]) {
-            Some(o) => o,
-            None => return packages,
-        };
+        let output =
+            match run_npm_command(self.runner.as_ref(), &["list", "-g", "--depth=0", "--json"]) {
+                Some(o) => o,
+                None => return packages,
+            };
 
         let list: NpmListOutput = match serde_json::from_str(&output) {
             Ok(l) => l,
             Err(_) => return packages,
         };
 
-        let outdated_output = run_npm_command(&["outdated", "-g", "--json", "--long", "--depth=0"])
-            .unwrap_or_default();
+        let outdated_output = run_npm_command(
+            self.runner.as_ref(),
+            &["outdated", "-g", "--json", "--long", "--depth=0"],
+        )
+        .unwrap_or_default();
         let outdated: std::collections::HashMap<String, NpmOutdatedPackage> =
             serde_json::from_str(&outdated_output).unwrap_or_default();
 
@@ -86,6 +97,7 @@ impl PackageManager for NpmManager {
                     manager: "npm".to_string(),
                     is_outdated,
                     description: None,
+                    scope: None,
                 });
             }
         }
@@ -93,23 +105,51 @@ impl PackageManager for NpmManager {
         packages
     }
 
-    fn update_package(&self, name: &str) -> Result<String, String> {
-        match run_npm_command(&["update", "-g", name]) {
-            Some(output) => Ok(format!("Updated {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to update {}", name)),
-        }
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command("npm", &[], &["update", "-g", name])
     }
 
-    fn uninstall_package(&self, name: &str) -> Result<String, String> {
-        match run_npm_command(&["uninstall", "-g", name, "--force"]) {
-            Some(output) => Ok(format!("Uninstalled {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to uninstall {}", name)),
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "npm",
+            &[],
+            &["uninstall", "-g", name, "--force"],
+        ))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: true,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: true,
         }
     }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("npm", &[], &["install", "-g", name]))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            "npm",
+            &[],
+            &["install", "-g", &format!("{name}@{version}")],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command("npm", &[], &["view", name]))
+    }
+
+    fn update_all_command(&self) -> Option<PlannedCommand> {
+        Some(planned_command("npm", &[], &["update", "-g"]))
+    }
 }
 
-fn run_npm_command(args: &[&str]) -> Option<String> {
-    let output = command_output_with_timeout("npm", args, Duration::from_secs(30)).ok()?;
+fn run_npm_command(runner: &dyn CommandRunner, args: &[&str]) -> Option<String> {
+    let output = runner.output("npm", args, Duration::from_secs(30)).ok()?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).to_string())
@@ -122,3 +162,27 @@ fn run_npm_command(args: &[&str]) -> Option<String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner::ReplayRunner;
+
+    #[test]
+    fn lists_global_packages_from_recorded_output() {
+        let fixture = include_str!("../../fixtures/commands/npm.jsonl");
+        let runner = ReplayRunner::parse(fixture, "npm.jsonl").unwrap();
+        let manager = NpmManager::with_runner(Arc::new(runner)).unwrap();
+
+        let mut packages = manager.list_packages();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(manager.get_version().as_deref(), Some("10.8.2"));
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["@openai/codex", "corepack", "typescript"]);
+        let typescript = &packages[2];
+        assert!(typescript.is_outdated);
+        assert_eq!(typescript.latest.as_deref(), Some("5.9.3"));
+        assert!(!packages[0].is_outdated);
+    }
+}
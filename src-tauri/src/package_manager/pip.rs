@@ -1,14 +1,17 @@
 //! pip package manager support
 
-use super::{PackageInfo, PackageManager};
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
 use serde::Deserialize;
 
-use crate::utils::command::command_output_with_timeout_vec;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub struct PipManager {
     version: String,
     command: PipCommand,
+    runner: Arc<dyn CommandRunner>,
 }
 
 #[derive(Clone)]
@@ -41,6 +44,10 @@ struct PipOutdatedPackage {
 
 impl PipManager {
     pub fn new() -> Option<Self> {
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
         // Prefer invoking pip via the Python launcher/interpreter when available.
         // This avoids ambiguity when multiple Python installs exist.
         #[cfg(target_os = "windows")]
@@ -61,7 +68,7 @@ impl PipManager {
         ];
 
         for cmd in &candidates {
-            if let Some(output) = run_pip_command(cmd, &["--version"]) {
+            if let Some(output) = run_pip_command(runner.as_ref(), cmd, &["--version"]) {
                 // Extract version from "pip X.Y.Z from ..."
                 let version = output
                     .split_whitespace()
@@ -71,6 +78,7 @@ impl PipManager {
                 return Some(Self {
                     version,
                     command: cmd.clone(),
+                    runner,
                 });
             }
         }
@@ -95,7 +103,11 @@ impl PackageManager for PipManager {
         let mut packages = Vec::new();
 
         // Get installed packages
-        let output = match run_pip_command(&self.command, &["list", "--format=json"]) {
+        let output = match run_pip_command(
+            self.runner.as_ref(),
+            &self.command,
+            &["list", "--format=json"],
+        ) {
             Some(o) => o,
             None => return packages,
         };
@@ -108,7 +120,7 @@ impl PackageManager for PipManager {
         // Skip outdated check for now - it requires network and is very slow
         // TODO: Move to async background task
         // let outdated_output =
-        //     run_pip_command(&self.command, &["list", "--outdated", "--format=json"])
+        //     run_pip_command(self.runner.as_ref(), &self.command, &["list", "--outdated", "--format=json"])
         //         .unwrap_or_default();
         // let outdated: Vec<PipOutdatedPackage> =
         //     serde_json::from_str(&outdated_output).unwrap_or_default();
@@ -136,35 +148,76 @@ impl PackageManager for PipManager {
                 manager: "pip".to_string(),
                 is_outdated,
                 description: None,
+                scope: None,
             });
         }
 
         packages
     }
 
-    fn update_package(&self, name: &str) -> Result<String, String> {
-        match run_pip_command(&self.command, &["install", "--upgrade", name]) {
-            Some(output) => Ok(format!("Updated {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to update {}", name)),
-        }
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["install", "--upgrade", name],
+        )
     }
 
-    fn uninstall_package(&self, name: &str) -> Result<String, String> {
-        match run_pip_command(&self.command, &["uninstall", "-y", name]) {
-            Some(output) => Ok(format!("Uninstalled {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to uninstall {}", name)),
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["uninstall", "-y", name],
+        ))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: false,
         }
     }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["install", name],
+        ))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["install", &format!("{name}=={version}")],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["show", name],
+        ))
+    }
 }
 
-fn run_pip_command(command: &PipCommand, args: &[&str]) -> Option<String> {
+fn run_pip_command(
+    runner: &dyn CommandRunner,
+    command: &PipCommand,
+    args: &[&str],
+) -> Option<String> {
     let mut full_args: Vec<String> = Vec::new();
     full_args.extend(command.prefix_args.iter().cloned());
     full_args.extend(args.iter().map(|s| s.to_string()));
 
-    let output =
-        command_output_with_timeout_vec(&command.program, &full_args, Duration::from_secs(30))
-            .ok()?;
+    let output = runner
+        .output_vec(&command.program, &full_args, Duration::from_secs(30))
+        .ok()?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).to_string())
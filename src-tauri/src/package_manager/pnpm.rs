@@ -1,16 +1,19 @@
 //! pnpm package manager support
 
-use super::{PackageInfo, PackageManager};
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
 use serde::Deserialize;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::time::Duration;
 
-use crate::utils::command::command_output_with_timeout_vec;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
 
 pub struct PnpmManager {
     version: String,
     command: NodePackageCommand,
+    runner: Arc<dyn CommandRunner>,
 }
 
 #[derive(Clone)]
@@ -41,16 +44,21 @@ struct PnpmOutdatedPackage {
 
 impl PnpmManager {
     pub fn new() -> Option<Self> {
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
         let candidates = [
             NodePackageCommand::new("pnpm", &[]),
             NodePackageCommand::new("corepack", &["pnpm"]),
         ];
 
         for command in candidates {
-            if let Some(output) = run_pnpm_command(&command, &["--version"]) {
+            if let Some(output) = run_pnpm_command(runner.as_ref(), &command, &["--version"]) {
                 return Some(Self {
                     version: output.trim().to_string(),
                     command,
+                    runner,
                 });
             }
         }
@@ -73,13 +81,21 @@ impl PackageManager for PnpmManager {
     }
 
     fn list_packages(&self) -> Vec<PackageInfo> {
-        let output = match run_pnpm_command(&self.command, &["list", "-g", "--depth=0", "--json"]) {
+        let output = match run_pnpm_command(
+            self.runner.as_ref(),
+            &self.command,
+            &["list", "-g", "--depth=0", "--json"],
+        ) {
             Some(output) => output,
             None => return Vec::new(),
         };
 
-        let outdated_output = run_pnpm_command(&self.command, &["outdated", "-g", "--format=json"])
-            .unwrap_or_default();
+        let outdated_output = run_pnpm_command(
+            self.runner.as_ref(),
+            &self.command,
+            &["outdated", "-g", "--format=json"],
+        )
+        .unwrap_or_default();
         let outdated: HashMap<String, PnpmOutdatedPackage> =
             serde_json::from_str(&outdated_output).unwrap_or_default();
 
@@ -97,24 +113,69 @@ impl PackageManager for PnpmManager {
                     manager: "pnpm".to_string(),
                     is_outdated: latest.is_some(),
                     description: pkg.path,
+                    scope: None,
                 }
             })
             .collect()
     }
 
-    fn update_package(&self, name: &str) -> Result<String, String> {
-        match run_pnpm_command(&self.command, &["update", "-g", name]) {
-            Some(output) => Ok(format!("Updated {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to update {}", name)),
-        }
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["update", "-g", name],
+        )
     }
 
-    fn uninstall_package(&self, name: &str) -> Result<String, String> {
-        match run_pnpm_command(&self.command, &["remove", "-g", name]) {
-            Some(output) => Ok(format!("Uninstalled {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to uninstall {}", name)),
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["remove", "-g", name],
+        ))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: true,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: true,
         }
     }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["add", "-g", name],
+        ))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["add", "-g", &format!("{name}@{version}")],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["view", name],
+        ))
+    }
+
+    fn update_all_command(&self) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["update", "-g"],
+        ))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -183,13 +244,17 @@ fn parse_pnpm_list(output: &str) -> Vec<ParsedPnpmPackage> {
     packages
 }
 
-fn run_pnpm_command(command: &NodePackageCommand, args: &[&str]) -> Option<String> {
+fn run_pnpm_command(
+    runner: &dyn CommandRunner,
+    command: &NodePackageCommand,
+    args: &[&str],
+) -> Option<String> {
     let mut full_args = command.prefix_args.clone();
     full_args.extend(args.iter().map(|arg| arg.to_string()));
 
-    let output =
-        command_output_with_timeout_vec(&command.program, &full_args, Duration::from_secs(30))
-            .ok()?;
+    let output = runner
+        .output_vec(&command.program, &full_args, Duration::from_secs(30))
+        .ok()?;
 
     if output.status.success() || args.contains(&"outdated") {
         Some(String::from_utf8_lossy(&output.stdout).to_string())
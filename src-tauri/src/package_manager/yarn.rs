@@ -1,15 +1,18 @@
 //! Yarn package manager support
 
-use super::{PackageInfo, PackageManager};
+use super::{planned_command, Capabilities, PackageInfo, PackageManager};
 use serde::Deserialize;
 use serde_json::Value;
 use std::time::Duration;
 
-use crate::utils::command::command_output_with_timeout_vec;
+use crate::plan::PlannedCommand;
+use crate::utils::runner::{active_runner, CommandRunner};
+use std::sync::Arc;
 
 pub struct YarnManager {
     version: String,
     command: YarnCommand,
+    runner: Arc<dyn CommandRunner>,
 }
 
 #[derive(Clone)]
@@ -36,16 +39,21 @@ struct YarnJsonLine {
 
 impl YarnManager {
     pub fn new() -> Option<Self> {
+        Self::with_runner(active_runner())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Option<Self> {
         let candidates = [
             YarnCommand::new("yarn", &[]),
             YarnCommand::new("corepack", &["yarn"]),
         ];
 
         for command in candidates {
-            if let Some(output) = run_yarn_command(&command, &["--version"]) {
+            if let Some(output) = run_yarn_command(runner.as_ref(), &command, &["--version"]) {
                 return Some(Self {
                     version: output.trim().to_string(),
                     command,
+                    runner,
                 });
             }
         }
@@ -68,11 +76,14 @@ impl PackageManager for YarnManager {
     }
 
     fn list_packages(&self) -> Vec<PackageInfo> {
-        let output =
-            match run_yarn_command(&self.command, &["global", "list", "--depth=0", "--json"]) {
-                Some(output) => output,
-                None => return Vec::new(),
-            };
+        let output = match run_yarn_command(
+            self.runner.as_ref(),
+            &self.command,
+            &["global", "list", "--depth=0", "--json"],
+        ) {
+            Some(output) => output,
+            None => return Vec::new(),
+        };
 
         parse_yarn_global_list(&output)
             .into_iter()
@@ -83,23 +94,68 @@ impl PackageManager for YarnManager {
                 manager: "yarn".to_string(),
                 is_outdated: false,
                 description: None,
+                scope: None,
             })
             .collect()
     }
 
-    fn update_package(&self, name: &str) -> Result<String, String> {
-        match run_yarn_command(&self.command, &["global", "add", &format!("{name}@latest")]) {
-            Some(output) => Ok(format!("Updated {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to update {}", name)),
-        }
+    fn update_command(&self, name: &str) -> PlannedCommand {
+        planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["global", "add", &format!("{name}@latest")],
+        )
+    }
+
+    fn uninstall_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["global", "remove", name],
+        ))
     }
 
-    fn uninstall_package(&self, name: &str) -> Result<String, String> {
-        match run_yarn_command(&self.command, &["global", "remove", name]) {
-            Some(output) => Ok(format!("Uninstalled {} successfully:\n{}", name, output)),
-            None => Err(format!("Failed to uninstall {}", name)),
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            outdated_check: false,
+            install: true,
+            pin: true,
+            info: true,
+            batch_update: true,
         }
     }
+
+    fn install_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["global", "add", name],
+        ))
+    }
+
+    fn pin_command(&self, name: &str, version: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["global", "add", &format!("{name}@{version}")],
+        ))
+    }
+
+    fn info_command(&self, name: &str) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["info", name],
+        ))
+    }
+
+    fn update_all_command(&self) -> Option<PlannedCommand> {
+        Some(planned_command(
+            &self.command.program,
+            &self.command.prefix_args,
+            &["global", "upgrade"],
+        ))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -157,13 +213,17 @@ fn split_yarn_name_version(input: &str) -> Option<(String, String)> {
     Some((name.to_string(), version.to_string()))
 }
 
-fn run_yarn_command(command: &YarnCommand, args: &[&str]) -> Option<String> {
+fn run_yarn_command(
+    runner: &dyn CommandRunner,
+    command: &YarnCommand,
+    args: &[&str],
+) -> Option<String> {
     let mut full_args = command.prefix_args.clone();
     full_args.extend(args.iter().map(|arg| arg.to_string()));
 
-    let output =
-        command_output_with_timeout_vec(&command.program, &full_args, Duration::from_secs(30))
-            .ok()?;
+    let output = runner
+        .output_vec(&command.program, &full_args, Duration::from_secs(30))
+        .ok()?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).to_string())
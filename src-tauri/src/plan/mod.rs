@@ -0,0 +1,322 @@
+//! Dry-run plans for cleanup and uninstall operations
+//! Plans run the same validation as the real actions and can be approved afterwards
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::ai_cleanup::{delete_ai_junk, plan_delete_ai_junk};
+use crate::ai_cli::{plan_uninstall_ai_tool as ai_tool_uninstall_commands, uninstall_ai_tool};
+use crate::cache::{clean_cache, plan_clean_cache};
+use crate::chat_history::{delete_chat_file, plan_delete_project_chat_history as chat_removals};
+use crate::detection::uninstall::{plan_uninstall_tool as tool_uninstall_commands, uninstall_tool};
+use crate::disk_usage::{format_size, path_size};
+use crate::error::DevJanitorError;
+use crate::operation::OperationResult;
+use crate::package_manager::{self, get_manager};
+use crate::runtimes::{plan_remove_runtime_version, remove_runtime_version};
+use crate::settings::active_settings;
+
+/// What a plan would do when approved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupPlan {
+    /// Operation executed on approval: clean_cache, delete_ai_junk, delete_chat_history,
+    /// uninstall_package, uninstall_tool, uninstall_ai_tool or remove_runtime_version
+    pub operation: String,
+    pub steps: Vec<PlanStep>,
+    /// Targets that failed validation and would be skipped
+    pub rejected: Vec<RejectedTarget>,
+    pub total_size: u64,
+    pub total_size_display: String,
+}
+
+/// One target of a plan with the paths it removes and the commands it runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// Path, package name or tool id as requested
+    pub target: String,
+    /// Package manager for package uninstalls, tool path for tool uninstalls
+    pub context: Option<String>,
+    pub removals: Vec<PlannedRemoval>,
+    /// Commands tried in order until one succeeds
+    pub commands: Vec<PlannedCommand>,
+    pub size: u64,
+    pub size_display: String,
+}
+
+/// A path that would be moved to quarantine, or deleted if settings say so
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlannedRemoval {
+    pub path: String,
+    pub size: u64,
+    pub size_display: String,
+    pub is_directory: bool,
+    /// Deleted outright instead of quarantined (the `delete` deletion mode)
+    pub permanent: bool,
+}
+
+/// An external command exactly as it would be spawned
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlannedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub command_line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedTarget {
+    pub target: String,
+    /// `DevJanitorError` code, e.g. "unsafe_path" or "not_a_target"
+    pub code: String,
+    pub reason: String,
+}
+
+impl PlannedRemoval {
+    pub fn new(path: &Path, size: u64) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            size,
+            size_display: format_size(size),
+            is_directory: path.is_dir(),
+            permanent: !active_settings().quarantines_deletions(),
+        }
+    }
+}
+
+impl PlannedCommand {
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self::owned(program, args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    pub fn owned(program: &str, args: Vec<String>) -> Self {
+        let command_line = std::iter::once(program.to_string())
+            .chain(args.iter().map(|arg| quote_arg(arg)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            program: program.to_string(),
+            args,
+            command_line,
+        }
+    }
+
+    /// Script passed to `sh -c`
+    pub fn shell(script: &str) -> Self {
+        Self::owned("sh", vec!["-c".to_string(), script.to_string()])
+    }
+}
+
+/// Quote an argument for a POSIX shell unless it only holds characters no shell treats specially
+pub(crate) fn quote_arg(arg: &str) -> String {
+    let plain = arg
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_./:@%+=,-".contains(c));
+    if !arg.is_empty() && plain {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+impl CleanupPlan {
+    fn new(operation: &str) -> Self {
+        Self {
+            operation: operation.to_string(),
+            steps: Vec::new(),
+            rejected: Vec::new(),
+            total_size: 0,
+            total_size_display: format_size(0),
+        }
+    }
+
+    fn push_step(&mut self, target: &str, context: Option<&str>, removals: Vec<PlannedRemoval>) {
+        let size = removals.iter().map(|removal| removal.size).sum();
+        self.push(PlanStep {
+            target: target.to_string(),
+            context: context.map(str::to_string),
+            removals,
+            commands: Vec::new(),
+            size,
+            size_display: format_size(size),
+        });
+    }
+
+    fn push_commands(
+        &mut self,
+        target: &str,
+        context: Option<&str>,
+        commands: Vec<PlannedCommand>,
+    ) {
+        self.push(PlanStep {
+            target: target.to_string(),
+            context: context.map(str::to_string),
+            removals: Vec::new(),
+            commands,
+            size: 0,
+            size_display: format_size(0),
+        });
+    }
+
+    fn push(&mut self, step: PlanStep) {
+        self.total_size += step.size;
+        self.total_size_display = format_size(self.total_size);
+        self.steps.push(step);
+    }
+
+    fn reject(&mut self, target: &str, error: DevJanitorError) {
+        self.rejected.push(RejectedTarget {
+            target: target.to_string(),
+            code: error.code().to_string(),
+            reason: error.to_string(),
+        });
+    }
+}
+
+/// Plan cleaning cache directories
+pub fn plan_clean_caches(paths: &[String]) -> CleanupPlan {
+    let mut plan = CleanupPlan::new("clean_cache");
+    for path in paths {
+        match plan_clean_cache(path) {
+            Ok(removal) => plan.push_step(path, None, vec![removal]),
+            Err(error) => plan.reject(path, error),
+        }
+    }
+    plan
+}
+
+/// Plan deleting AI junk files
+pub fn plan_delete_multiple_ai_junk(paths: &[String]) -> CleanupPlan {
+    let mut plan = CleanupPlan::new("delete_ai_junk");
+    for path in paths {
+        match plan_delete_ai_junk(path) {
+            Ok(removal) => plan.push_step(path, None, vec![removal]),
+            Err(error) => plan.reject(path, error),
+        }
+    }
+    plan
+}
+
+/// Plan deleting all chat history of a project
+pub fn plan_delete_project_chat_history(
+    project_path: &str,
+) -> Result<CleanupPlan, DevJanitorError> {
+    let mut plan = CleanupPlan::new("delete_chat_history");
+    for (path, removal) in chat_removals(project_path)? {
+        match removal {
+            Ok(removal) => plan.push_step(&path, None, vec![removal]),
+            Err(error) => plan.reject(&path, error),
+        }
+    }
+    Ok(plan)
+}
+
+/// Plan uninstalling a global package
+pub fn plan_uninstall_package(manager: &str, name: &str) -> Result<CleanupPlan, DevJanitorError> {
+    let found = get_manager(manager)?;
+    let mut plan = CleanupPlan::new("uninstall_package");
+    match found.uninstall_command(name) {
+        Some(command) => plan.push_commands(name, Some(manager), vec![command]),
+        None => {
+            let path = found.installed_file(name)?;
+            let removal = PlannedRemoval::new(&path, path_size(&path));
+            plan.push_step(name, Some(manager), vec![removal]);
+        }
+    }
+    Ok(plan)
+}
+
+/// Plan uninstalling a detected development tool
+pub fn plan_uninstall_tool(tool_id: &str, path: &str) -> Result<CleanupPlan, DevJanitorError> {
+    let commands = tool_uninstall_commands(tool_id, path)?;
+    let mut plan = CleanupPlan::new("uninstall_tool");
+    plan.push_commands(tool_id, Some(path), commands);
+    Ok(plan)
+}
+
+/// Plan uninstalling an AI CLI tool
+pub fn plan_uninstall_ai_tool(tool_id: &str) -> Result<CleanupPlan, DevJanitorError> {
+    let commands = ai_tool_uninstall_commands(tool_id)?;
+    let mut plan = CleanupPlan::new("uninstall_ai_tool");
+    plan.push_commands(tool_id, None, commands);
+    Ok(plan)
+}
+
+/// Plan removing installed runtime versions
+pub fn plan_remove_runtime_versions(paths: &[String]) -> CleanupPlan {
+    let mut plan = CleanupPlan::new("remove_runtime_version");
+    for path in paths {
+        match plan_remove_runtime_version(path) {
+            Ok(removal) => plan.push_step(path, None, vec![removal]),
+            Err(error) => plan.reject(path, error),
+        }
+    }
+    plan
+}
+
+/// Execute an approved plan, one result per step
+///
+/// Each step goes through the regular action again, so targets are re-validated and
+/// commands are rebuilt rather than taken from the (possibly edited) plan.
+pub fn execute_plan(plan: &CleanupPlan) -> Vec<Result<OperationResult, DevJanitorError>> {
+    plan.steps
+        .iter()
+        .map(|step| execute_step(&plan.operation, step))
+        .collect()
+}
+
+fn execute_step(operation: &str, step: &PlanStep) -> Result<OperationResult, DevJanitorError> {
+    let context = step.context.as_deref();
+    match operation {
+        "clean_cache" => clean_cache(&step.target),
+        "delete_ai_junk" => delete_ai_junk(&step.target),
+        "delete_chat_history" => delete_chat_file(&step.target),
+        "uninstall_package" => {
+            let manager = context.ok_or_else(|| {
+                DevJanitorError::InvalidInput("Package plan step has no manager".to_string())
+            })?;
+            package_manager::uninstall_package(manager, &step.target)
+        }
+        "uninstall_tool" => uninstall_tool(&step.target, context.unwrap_or_default()),
+        "uninstall_ai_tool" => uninstall_ai_tool(&step.target),
+        "remove_runtime_version" => remove_runtime_version(&step.target),
+        other => Err(DevJanitorError::InvalidInput(format!(
+            "Unknown plan operation: {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_exact_command_lines() {
+        let command = PlannedCommand::new("npm", &["uninstall", "-g", "@scope/pkg"]);
+        assert_eq!(command.command_line, "npm uninstall -g @scope/pkg");
+
+        let shell = PlannedCommand::shell("curl -fsSL https://example.com | bash");
+        assert_eq!(shell.args[0], "-c");
+        assert_eq!(
+            shell.command_line,
+            "sh -c 'curl -fsSL https://example.com | bash'"
+        );
+        assert_eq!(quote_arg("it's"), r"'it'\''s'");
+        assert_eq!(quote_arg("v1`id`(x)<y>*"), "'v1`id`(x)<y>*'");
+    }
+
+    #[test]
+    fn rejected_targets_do_not_count_towards_totals() {
+        let plan = plan_clean_caches(&["/definitely/not/a/cache".to_string()]);
+        assert!(plan.steps.is_empty());
+        assert_eq!(plan.rejected.len(), 1);
+        assert_eq!(plan.rejected[0].code, "not_found");
+        assert_eq!(plan.total_size, 0);
+        assert!(execute_plan(&plan).is_empty());
+    }
+
+    #[test]
+    fn unknown_operations_are_refused() {
+        let mut plan = CleanupPlan::new("format_disk");
+        plan.push_step("/", None, Vec::new());
+        assert!(execute_plan(&plan)[0].is_err());
+    }
+}
@@ -0,0 +1,689 @@
+//! Quarantine (trash) for Dev Janitor cleanups
+//! Destructive operations move their targets here so they can be restored
+//!
+//! The layout follows the freedesktop.org Trash specification: items live in
+//! `files/` and each has a matching `info/<name>.trashinfo`. On Linux this is
+//! the user's home trash, so items also show up in the desktop file manager.
+//! Targets on other filesystems go to that filesystem's `$topdir/.Trash-$uid`,
+//! so quarantining is always a rename and never copies data onto the home partition.
+
+use chrono::{Duration as ChronoDuration, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::disk_usage::format_size;
+use crate::error::DevJanitorError;
+use crate::journal::{self, JournalEntry};
+use crate::operation::OperationResult;
+use crate::settings::active_settings;
+use crate::utils::fs::{move_path, remove_path};
+use crate::utils::paths;
+
+const TRASH_INFO_EXTENSION: &str = "trashinfo";
+const DELETION_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+/// Marks entries created by Dev Janitor; other trash items are left alone
+const SOURCE_KEY: &str = "X-DevJanitor-Source";
+const SIZE_KEY: &str = "X-DevJanitor-Size";
+
+/// An item that Dev Janitor moved into quarantine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    /// Name of the item inside the trash `files/` directory
+    pub id: String,
+    /// Where the item lived before it was quarantined
+    pub original_path: String,
+    /// Where the item lives now
+    pub quarantined_path: String,
+    /// Which cleanup moved it: "cache", "ai_junk", "chat_history", "runtime" or "package"
+    pub source: String,
+    pub size: u64,
+    pub size_display: String,
+    /// Local time of the move, `YYYY-MM-DDThh:mm:ss`
+    pub deleted_at: String,
+    pub is_directory: bool,
+}
+
+/// Outcome of a purge run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeSummary {
+    pub purged: u32,
+    pub failed: u32,
+    pub freed: u64,
+    pub freed_display: String,
+    /// Problems that did not stop the purge, such as journal write failures
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// A trash directory: items in `files/`, their `.trashinfo` files in `info/`
+struct TrashDir {
+    files: PathBuf,
+    info: PathBuf,
+}
+
+impl TrashDir {
+    fn at(root: &Path) -> Self {
+        Self {
+            files: root.join("files"),
+            info: root.join("info"),
+        }
+    }
+}
+
+/// Root of the home trash used for quarantine
+#[cfg(all(target_os = "linux", not(test)))]
+fn trash_root() -> Option<PathBuf> {
+    paths::user_data_base().map(|base| base.join("Trash"))
+}
+
+#[cfg(not(all(target_os = "linux", not(test))))]
+fn trash_root() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join("Trash"))
+}
+
+fn home_trash() -> Result<TrashDir, DevJanitorError> {
+    let root = trash_root().ok_or_else(|| {
+        DevJanitorError::NotFound("Could not determine the trash directory".to_string())
+    })?;
+    Ok(TrashDir::at(&root))
+}
+
+/// The home trash followed by every per-mount trash that exists
+fn all_trashes() -> Vec<TrashDir> {
+    home_trash()
+        .into_iter()
+        .chain(
+            mount_trash_roots()
+                .into_iter()
+                .map(|root| TrashDir::at(&root)),
+        )
+        .collect()
+}
+
+/// Trash on the same filesystem as `path`, so the move is a rename
+fn trash_for(path: &Path) -> Result<TrashDir, DevJanitorError> {
+    let home = home_trash()?;
+    if same_filesystem(path, &home.files) {
+        return Ok(home);
+    }
+    mount_trash_for(path)
+}
+
+/// Whether `path` and `other` (or its nearest existing ancestor) share a filesystem
+#[cfg(unix)]
+fn same_filesystem(path: &Path, other: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return false;
+    };
+    other
+        .ancestors()
+        .find_map(|ancestor| fs::metadata(ancestor).ok())
+        .is_some_and(|other| other.dev() == metadata.dev())
+}
+
+// Without device numbers the rename itself reports a cross-device move
+#[cfg(not(unix))]
+fn same_filesystem(_path: &Path, _other: &Path) -> bool {
+    true
+}
+
+/// Top directory of the filesystem `path` lives on
+#[cfg(target_os = "linux")]
+fn mount_top_dir(path: &Path) -> io::Result<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+
+    let dev = fs::symlink_metadata(path)?.dev();
+    let mut top = path;
+    while let Some(parent) = top.parent() {
+        if fs::metadata(parent)?.dev() != dev {
+            break;
+        }
+        top = parent;
+    }
+    Ok(top.to_path_buf())
+}
+
+#[cfg(all(target_os = "linux", not(test)))]
+fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+/// `$topdir/.Trash-$uid` of the filesystem `path` lives on, created if needed
+#[cfg(all(target_os = "linux", not(test)))]
+fn mount_trash_for(path: &Path) -> Result<TrashDir, DevJanitorError> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    let uid = current_uid();
+    let top = mount_top_dir(path).map_err(|error| cross_device_error(path, &error))?;
+    let root = top.join(format!(".Trash-{}", uid));
+    match fs::DirBuilder::new().mode(0o700).create(&root) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(error) => return Err(cross_device_error(path, &error)),
+    }
+    // The spec requires a real directory owned by the user, never a symlink
+    let metadata = fs::symlink_metadata(&root).map_err(|error| cross_device_error(path, &error))?;
+    if !metadata.is_dir() || metadata.uid() != uid {
+        return Err(cross_device_error(
+            path,
+            &format!("{} is not a private trash directory", root.display()),
+        ));
+    }
+    Ok(TrashDir::at(&root))
+}
+
+#[cfg(not(all(target_os = "linux", not(test))))]
+fn mount_trash_for(path: &Path) -> Result<TrashDir, DevJanitorError> {
+    Err(cross_device_error(
+        path,
+        &"the trash is on another filesystem",
+    ))
+}
+
+/// Roots of the per-mount trashes that exist for this user
+#[cfg(all(target_os = "linux", not(test)))]
+fn mount_trash_roots() -> Vec<PathBuf> {
+    let uid = current_uid();
+    let Ok(mounts) = fs::read_to_string("/proc/self/mounts") else {
+        return Vec::new();
+    };
+    let mut roots: Vec<PathBuf> = mounts
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|mount_point| {
+            // Spaces and tabs in mount points are octal-escaped
+            let mount_point = mount_point
+                .replace("\\040", " ")
+                .replace("\\011", "\t")
+                .replace("\\134", "\\");
+            Path::new(&mount_point).join(format!(".Trash-{}", uid))
+        })
+        .filter(|root| fs::symlink_metadata(root).is_ok_and(|metadata| metadata.is_dir()))
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+#[cfg(not(all(target_os = "linux", not(test))))]
+fn mount_trash_roots() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+fn cross_device_error(path: &Path, reason: &dyn std::fmt::Display) -> DevJanitorError {
+    DevJanitorError::QuarantineUnavailable(format!(
+        "Cannot quarantine {} without copying it across filesystems ({}); \
+         set the deletion mode to delete to remove it permanently",
+        path.display(),
+        reason
+    ))
+}
+
+/// Where the `.trashinfo` file of a quarantined item lives
+fn info_path(entry: &QuarantineEntry) -> PathBuf {
+    let quarantined = Path::new(&entry.quarantined_path);
+    let root = quarantined
+        .parent()
+        .and_then(Path::parent)
+        .unwrap_or(quarantined);
+    root.join("info")
+        .join(format!("{}.{}", entry.id, TRASH_INFO_EXTENSION))
+}
+
+/// Move a validated cleanup target into quarantine
+///
+/// `size` is the size the caller measured before the move; it is recorded so
+/// listings do not need to walk the quarantined tree again.
+pub fn move_to_quarantine(
+    path: &Path,
+    source: &str,
+    size: u64,
+) -> Result<QuarantineEntry, DevJanitorError> {
+    let original_path = path.canonicalize().map_err(|error| {
+        DevJanitorError::io(format!("Failed to resolve {}", path.display()), error)
+    })?;
+    let trash = trash_for(&original_path)?;
+    fs::create_dir_all(&trash.files)
+        .and_then(|_| fs::create_dir_all(&trash.info))
+        .map_err(|error| DevJanitorError::io("Failed to prepare quarantine", error))?;
+    let is_directory = original_path.is_dir();
+    let deleted_at = Local::now().format(DELETION_DATE_FORMAT).to_string();
+    let base_name = original_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "item".to_string());
+
+    let (id, info_path) = reserve_info_file(&trash, &base_name)?;
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n{}={}\n{}={}\n",
+        encode_path(&original_path.to_string_lossy()),
+        deleted_at,
+        SOURCE_KEY,
+        source,
+        SIZE_KEY,
+        size
+    );
+
+    let quarantined_path = trash.files.join(&id);
+    let result =
+        write_info(&info_path, &info).and_then(|_| fs::rename(&original_path, &quarantined_path));
+    if let Err(error) = result {
+        let _ = fs::remove_file(&info_path);
+        if error.kind() == io::ErrorKind::CrossesDevices {
+            return Err(cross_device_error(&original_path, &error));
+        }
+        return Err(DevJanitorError::io(
+            format!("Failed to quarantine {}", original_path.display()),
+            error,
+        ));
+    }
+
+    Ok(QuarantineEntry {
+        id,
+        original_path: original_path.to_string_lossy().to_string(),
+        quarantined_path: quarantined_path.to_string_lossy().to_string(),
+        source: source.to_string(),
+        size,
+        size_display: format_size(size),
+        deleted_at,
+        is_directory,
+    })
+}
+
+/// Remove a validated cleanup target the way the deletion preference asks
+///
+/// Returns the quarantine entry, or `None` when the target was deleted outright.
+pub fn remove_cleanup_target(
+    path: &Path,
+    source: &str,
+    size: u64,
+) -> Result<Option<QuarantineEntry>, DevJanitorError> {
+    if active_settings().quarantines_deletions() {
+        return move_to_quarantine(path, source, size).map(Some);
+    }
+
+    remove_path(path).map_err(|error| {
+        DevJanitorError::io(format!("Failed to delete {}", path.display()), error)
+    })?;
+    Ok(None)
+}
+
+/// Describe how `remove_cleanup_target` removed something, for result messages
+pub fn removal_description(entry: &Option<QuarantineEntry>) -> &'static str {
+    match entry {
+        Some(_) => "moved to quarantine",
+        None => "deleted permanently",
+    }
+}
+
+/// Claim a trash name by creating its info file exclusively
+///
+/// Names already used in any other trash are skipped too, so ids stay unique.
+fn reserve_info_file(
+    trash: &TrashDir,
+    base_name: &str,
+) -> Result<(String, PathBuf), DevJanitorError> {
+    let others = all_trashes();
+    for attempt in 1..=10_000u32 {
+        let candidate = if attempt == 1 {
+            base_name.to_string()
+        } else {
+            format!("{}.{}", base_name, attempt)
+        };
+        if trash.files.join(&candidate).exists()
+            || others
+                .iter()
+                .any(|other| other.files.join(&candidate).exists())
+        {
+            continue;
+        }
+
+        let info_path = trash
+            .info
+            .join(format!("{}.{}", candidate, TRASH_INFO_EXTENSION));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(_) => return Ok((candidate, info_path)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(DevJanitorError::io("Failed to prepare quarantine", error)),
+        }
+    }
+
+    Err(DevJanitorError::AlreadyExists(format!(
+        "Too many quarantined items named {}",
+        base_name
+    )))
+}
+
+fn write_info(info_path: &Path, info: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(info_path)?;
+    file.write_all(info.as_bytes())
+}
+
+/// List items Dev Janitor has quarantined, newest first
+pub fn list_quarantine() -> Vec<QuarantineEntry> {
+    let mut items: Vec<QuarantineEntry> = all_trashes()
+        .iter()
+        .filter_map(|trash| Some((trash, fs::read_dir(&trash.info).ok()?)))
+        .flat_map(|(trash, entries)| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    if path.extension().and_then(|ext| ext.to_str()) != Some(TRASH_INFO_EXTENSION) {
+                        return None;
+                    }
+                    let id = path.file_stem()?.to_string_lossy().to_string();
+                    let content = fs::read_to_string(&path).ok()?;
+                    parse_info(&id, &content, &trash.files)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    items.sort_by_key(|item| Reverse(item.deleted_at.clone()));
+    items
+}
+
+fn parse_info(id: &str, content: &str, files_dir: &Path) -> Option<QuarantineEntry> {
+    let mut original_path = None;
+    let mut deleted_at = None;
+    let mut source = None;
+    let mut size = 0;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "Path" => original_path = Some(decode_path(value.trim())),
+            "DeletionDate" => deleted_at = Some(value.trim().to_string()),
+            SOURCE_KEY => source = Some(value.trim().to_string()),
+            SIZE_KEY => size = value.trim().parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    let quarantined_path = files_dir.join(id);
+    Some(QuarantineEntry {
+        id: id.to_string(),
+        original_path: original_path?,
+        is_directory: quarantined_path.is_dir(),
+        quarantined_path: quarantined_path.to_string_lossy().to_string(),
+        source: source?,
+        size,
+        size_display: format_size(size),
+        deleted_at: deleted_at.unwrap_or_default(),
+    })
+}
+
+fn find_entry(id: &str) -> Result<QuarantineEntry, DevJanitorError> {
+    // Ids are plain names inside the trash; reject anything that could escape it
+    if id.is_empty() || id.contains(['/', '\\']) || id == "." || id == ".." {
+        return Err(DevJanitorError::InvalidInput(format!(
+            "Invalid quarantine id: {}",
+            id
+        )));
+    }
+
+    list_quarantine()
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| DevJanitorError::NotFound(format!("Quarantined item not found: {}", id)))
+}
+
+/// Move a quarantined item back to where it came from
+pub fn restore_quarantined(id: &str) -> Result<OperationResult, DevJanitorError> {
+    let entry = find_entry(id)?;
+    let original = PathBuf::from(&entry.original_path);
+
+    if fs::symlink_metadata(&original).is_ok() {
+        return Err(DevJanitorError::AlreadyExists(format!(
+            "Cannot restore {}: the path already exists",
+            entry.original_path
+        )));
+    }
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent).map_err(|error| {
+            DevJanitorError::io(format!("Failed to recreate {}", parent.display()), error)
+        })?;
+    }
+
+    let result = move_path(Path::new(&entry.quarantined_path), &original).map_err(|error| {
+        DevJanitorError::io(format!("Failed to restore {}", entry.original_path), error)
+    });
+    let journaled = journal::record(&JournalEntry::from_result(
+        "restore_quarantined",
+        &entry.original_path,
+        &result,
+    ));
+    result?;
+    let _ = fs::remove_file(info_path(&entry));
+
+    Ok(OperationResult::new(
+        &entry.original_path,
+        format!("Restored {}", entry.original_path),
+    )
+    .journaled(journaled))
+}
+
+/// Permanently delete quarantined items older than `older_than_days`
+///
+/// Passing 0 empties Dev Janitor's quarantine. Items trashed by other
+/// applications are never touched.
+pub fn purge_quarantine(older_than_days: u32) -> Result<PurgeSummary, DevJanitorError> {
+    let cutoff = Local::now().naive_local() - ChronoDuration::days(i64::from(older_than_days));
+
+    let mut summary = PurgeSummary {
+        purged: 0,
+        failed: 0,
+        freed: 0,
+        freed_display: String::new(),
+        warnings: Vec::new(),
+    };
+
+    for entry in list_quarantine() {
+        let deleted_at = NaiveDateTime::parse_from_str(&entry.deleted_at, DELETION_DATE_FORMAT);
+        // Entries with an unreadable date are only removed by a full purge
+        let expired = match deleted_at {
+            Ok(deleted_at) => deleted_at <= cutoff,
+            Err(_) => older_than_days == 0,
+        };
+        if !expired {
+            continue;
+        }
+
+        let result = purge_entry(&entry)
+            .map_err(|error| DevJanitorError::io(format!("Failed to purge {}", entry.id), error));
+        let journaled = journal::record_removal(
+            "purge_quarantine",
+            Path::new(&entry.original_path),
+            entry.size,
+            &result,
+        );
+        if let Err(error) = journaled {
+            let warning = error.to_string();
+            if !summary.warnings.contains(&warning) {
+                summary.warnings.push(warning);
+            }
+        }
+        if result.is_ok() {
+            summary.purged += 1;
+            summary.freed += entry.size;
+        } else {
+            summary.failed += 1;
+        }
+    }
+
+    summary.freed_display = format_size(summary.freed);
+    Ok(summary)
+}
+
+fn purge_entry(entry: &QuarantineEntry) -> io::Result<()> {
+    let quarantined = Path::new(&entry.quarantined_path);
+    if fs::symlink_metadata(quarantined).is_ok() {
+        remove_path(quarantined)?;
+    }
+    fs::remove_file(info_path(entry))
+}
+
+/// Percent-encode a path for the `Path=` key (RFC 2396, keeping `/`)
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn decode_path(encoded: &str) -> String {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let byte = encoded
+                .get(index + 1..index + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = byte {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_item(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("dev-janitor-quarantine-{name}-{nanos}"));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested/payload.txt"), "payload").unwrap();
+        dir
+    }
+
+    #[test]
+    fn encodes_and_decodes_trash_paths() {
+        let path = "/home/dev/my project/ü%.txt";
+        let encoded = encode_path(path);
+        assert!(!encoded.contains(' '));
+        assert!(encoded.starts_with("/home/dev/my%20project/"));
+        assert_eq!(decode_path(&encoded), path);
+    }
+
+    #[test]
+    fn quarantines_lists_and_restores_directories() {
+        let item = temp_item("restore");
+        let original = item.canonicalize().unwrap();
+
+        let entry = move_to_quarantine(&item, "cache", 7).unwrap();
+        assert!(!item.exists());
+        assert_eq!(entry.original_path, original.to_string_lossy());
+        assert!(entry.is_directory);
+
+        let listed = list_quarantine();
+        let listed = listed.iter().find(|e| e.id == entry.id).unwrap();
+        assert_eq!(listed.source, "cache");
+        assert_eq!(listed.size, 7);
+
+        restore_quarantined(&entry.id).unwrap();
+        assert_eq!(
+            fs::read_to_string(original.join("nested/payload.txt")).unwrap(),
+            "payload"
+        );
+        assert!(list_quarantine().iter().all(|e| e.id != entry.id));
+
+        fs::remove_dir_all(original).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_restore_over_existing_path() {
+        let item = temp_item("conflict");
+        let entry = move_to_quarantine(&item, "ai_junk", 0).unwrap();
+        fs::create_dir_all(&item).unwrap();
+
+        let error = restore_quarantined(&entry.id).unwrap_err();
+        assert_eq!(error.code(), "already_exists");
+
+        fs::remove_dir_all(&item).unwrap();
+        restore_quarantined(&entry.id).unwrap();
+        fs::remove_dir_all(&item).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn finds_the_top_of_the_filesystem() {
+        use std::os::unix::fs::MetadataExt;
+
+        let item = temp_item("mount-top").canonicalize().unwrap();
+        let top = mount_top_dir(&item.join("nested")).unwrap();
+
+        assert!(item.starts_with(&top));
+        let dev = fs::metadata(&item).unwrap().dev();
+        assert_eq!(fs::metadata(&top).unwrap().dev(), dev);
+        if let Some(parent) = top.parent() {
+            assert_ne!(fs::metadata(parent).unwrap().dev(), dev);
+        }
+        assert!(same_filesystem(&item, &top.join("missing/trash")));
+
+        fs::remove_dir_all(item).unwrap();
+    }
+
+    #[test]
+    fn rejects_ids_that_escape_the_trash() {
+        assert_eq!(
+            restore_quarantined("../etc").unwrap_err().code(),
+            "invalid_input"
+        );
+        assert!(restore_quarantined("").is_err());
+    }
+
+    #[test]
+    fn purge_only_removes_expired_entries() {
+        let fresh = temp_item("purge-fresh");
+        let fresh_entry = move_to_quarantine(&fresh, "chat_history", 3).unwrap();
+
+        let summary = purge_quarantine(30).unwrap();
+        assert!(Path::new(&fresh_entry.quarantined_path).exists());
+        assert_eq!(summary.failed, 0);
+
+        let info_path = info_path(&fresh_entry);
+        let aged = fs::read_to_string(&info_path)
+            .unwrap()
+            .replace(&fresh_entry.deleted_at, "2000-01-01T00:00:00");
+        fs::write(&info_path, aged).unwrap();
+
+        purge_quarantine(30).unwrap();
+        assert!(!Path::new(&fresh_entry.quarantined_path).exists());
+        assert!(!info_path.exists());
+    }
+}
@@ -0,0 +1,529 @@
+//! Machine inventory report
+//! Collects every scan into one versioned document and renders it as JSON, Markdown or HTML
+
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use sysinfo::System;
+
+use crate::ai_cli::{get_ai_cli_tools, AiCliSupportStatus, AiCliTool};
+use crate::cache::{scan_package_manager_caches, CacheInfo};
+use crate::config::{diagnose_path_and_shell, lifecycle_issues, toolchain_issues, EnvDiagnosis};
+use crate::detection::{scan_all_tools, ToolInfo};
+use crate::disk_usage::format_size;
+use crate::error::DevJanitorError;
+use crate::package_manager::{scan_all_packages, PackageInfo};
+use crate::runtimes::installed_runtime_versions;
+use crate::security_scan::{scan_ai_tool_security, SecurityScanResult};
+
+/// Bumped whenever a field of `InventoryReport` is renamed or removed
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// The machine a report was generated on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostInfo {
+    pub hostname: Option<String>,
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+}
+
+/// Everything Dev Janitor knows about the machine, in one document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryReport {
+    pub schema_version: u32,
+    pub generated_at: String,
+    pub app_version: String,
+    pub host: HostInfo,
+    pub tools: Vec<ToolInfo>,
+    pub packages: Vec<PackageInfo>,
+    pub caches: Vec<CacheInfo>,
+    pub ai_cli_tools: Vec<AiCliTool>,
+    /// Shell config contents are left out; they often hold tokens
+    pub environment: EnvDiagnosis,
+    pub security: SecurityScanResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn parse(name: &str) -> Result<Self, DevJanitorError> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            other => Err(DevJanitorError::InvalidInput(format!(
+                "Unknown report format: {} (expected json, markdown or html)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Run every scan and assemble the report; the scans run in parallel
+pub fn collect_inventory() -> InventoryReport {
+    let (tools, packages, caches, ai_cli_tools, mut environment, security) =
+        std::thread::scope(|scope| {
+            let tools = scope.spawn(scan_all_tools);
+            let packages = scope.spawn(scan_all_packages);
+            let caches = scope.spawn(scan_package_manager_caches);
+            let ai_cli_tools = scope.spawn(get_ai_cli_tools);
+            let environment = scope.spawn(diagnose_path_and_shell);
+            let security = scope.spawn(scan_ai_tool_security);
+            (
+                join(tools),
+                join(packages),
+                join(caches),
+                join(ai_cli_tools),
+                join(environment),
+                join(security),
+            )
+        });
+
+    environment
+        .issues
+        .extend(lifecycle_issues(&tools, &installed_runtime_versions()));
+    environment.issues.extend(toolchain_issues(&tools));
+    for config in &mut environment.shell_configs {
+        config.content = None;
+    }
+
+    InventoryReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        generated_at: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        host: HostInfo {
+            hostname: System::host_name(),
+            os: std::env::consts::OS.to_string(),
+            os_version: System::long_os_version(),
+            arch: std::env::consts::ARCH.to_string(),
+        },
+        tools,
+        packages,
+        caches,
+        ai_cli_tools,
+        environment,
+        security,
+    }
+}
+
+fn join<T>(handle: std::thread::ScopedJoinHandle<'_, T>) -> T {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// Collect the inventory and render it in the named format
+pub fn generate_report(format: &str) -> Result<String, DevJanitorError> {
+    let format = ReportFormat::parse(format)?;
+    render_report(&collect_inventory(), format)
+}
+
+pub fn render_report(
+    report: &InventoryReport,
+    format: ReportFormat,
+) -> Result<String, DevJanitorError> {
+    match format {
+        ReportFormat::Json => serde_json::to_string_pretty(report)
+            .map_err(|error| DevJanitorError::Internal(error.to_string())),
+        ReportFormat::Markdown => Ok(render_markdown(report)),
+        ReportFormat::Html => Ok(render_html(report)),
+    }
+}
+
+/// One table of the rendered report
+struct Section {
+    title: &'static str,
+    headers: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+    notes: Vec<String>,
+}
+
+fn metadata(report: &InventoryReport) -> Vec<(&'static str, String)> {
+    let host = &report.host;
+    vec![
+        ("Generated", report.generated_at.clone()),
+        (
+            "Host",
+            host.hostname.clone().unwrap_or_else(|| "unknown".into()),
+        ),
+        (
+            "System",
+            format!(
+                "{} ({})",
+                host.os_version.clone().unwrap_or_else(|| host.os.clone()),
+                host.arch
+            ),
+        ),
+        ("Dev Janitor", report.app_version.clone()),
+        ("Schema version", report.schema_version.to_string()),
+    ]
+}
+
+fn sections(report: &InventoryReport) -> Vec<Section> {
+    let tools = report
+        .tools
+        .iter()
+        .map(|tool| {
+            let active = tool
+                .versions
+                .iter()
+                .find(|version| version.is_active)
+                .or_else(|| tool.versions.first());
+            vec![
+                tool.name.clone(),
+                tool.category.clone(),
+                active.map(|v| v.version.clone()).unwrap_or_default(),
+                tool.status.clone(),
+                active.map(|v| v.path.clone()).unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let packages = report
+        .packages
+        .iter()
+        .map(|package| {
+            vec![
+                package.manager.clone(),
+                package.name.clone(),
+                package.version.clone(),
+                package.latest.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let total_cache_size: u64 = report.caches.iter().map(|cache| cache.size).sum();
+    let caches = report
+        .caches
+        .iter()
+        .map(|cache| {
+            vec![
+                cache.name.clone(),
+                cache.size_display.clone(),
+                cache.path.clone(),
+            ]
+        })
+        .collect();
+
+    let ai_cli_tools = report
+        .ai_cli_tools
+        .iter()
+        .map(|tool| {
+            vec![
+                tool.name.clone(),
+                if tool.installed { "yes" } else { "no" }.to_string(),
+                tool.version.clone().unwrap_or_default(),
+                match tool.support_status {
+                    AiCliSupportStatus::Active => "active",
+                    AiCliSupportStatus::Legacy => "legacy",
+                }
+                .to_string(),
+            ]
+        })
+        .collect();
+
+    let environment = &report.environment;
+    let issues = environment
+        .issues
+        .iter()
+        .map(|issue| {
+            vec![
+                issue.severity.clone(),
+                issue.category.clone(),
+                if issue.evidence.is_empty() {
+                    issue.message.clone()
+                } else {
+                    format!("{} ({})", issue.message, issue.evidence.join("; "))
+                },
+                issue.suggestion.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let mut environment_notes = vec![format!(
+        "{} PATH entries, {} missing",
+        environment.path_entries.len(),
+        environment
+            .path_entries
+            .iter()
+            .filter(|entry| !entry.exists)
+            .count()
+    )];
+    environment_notes.extend(environment.suggestions.iter().cloned());
+
+    let security = &report.security;
+    let findings = security
+        .findings
+        .iter()
+        .map(|finding| {
+            vec![
+                finding.risk_level.as_str().to_string(),
+                finding.tool_name.clone(),
+                finding.issue.clone(),
+                finding.details.clone(),
+                finding.remediation.clone(),
+            ]
+        })
+        .collect();
+    let summary = &security.summary;
+
+    vec![
+        Section {
+            title: "Development Tools",
+            headers: &["Name", "Category", "Version", "Status", "Path"],
+            rows: tools,
+            notes: Vec::new(),
+        },
+        Section {
+            title: "Global Packages",
+            headers: &["Manager", "Name", "Version", "Latest"],
+            rows: packages,
+            notes: Vec::new(),
+        },
+        Section {
+            title: "Package Manager Caches",
+            headers: &["Name", "Size", "Path"],
+            rows: caches,
+            notes: vec![format!("Total: {}", format_size(total_cache_size))],
+        },
+        Section {
+            title: "AI CLI Tools",
+            headers: &["Name", "Installed", "Version", "Support"],
+            rows: ai_cli_tools,
+            notes: Vec::new(),
+        },
+        Section {
+            title: "Environment Diagnosis",
+            headers: &["Severity", "Category", "Message", "Suggestion"],
+            rows: issues,
+            notes: environment_notes,
+        },
+        Section {
+            title: "Security Findings",
+            headers: &["Risk", "Tool", "Issue", "Details", "Remediation"],
+            rows: findings,
+            notes: vec![format!(
+                "{} findings ({} critical, {} high, {} medium, {} low)",
+                summary.total_findings, summary.critical, summary.high, summary.medium, summary.low
+            )],
+        },
+    ]
+}
+
+/// Render the report as GitHub-flavored Markdown
+pub fn render_markdown(report: &InventoryReport) -> String {
+    let mut out = String::from("# Dev Janitor Inventory Report\n\n");
+    for (label, value) in metadata(report) {
+        let _ = writeln!(out, "- **{}:** {}", label, markdown_cell(&value));
+    }
+
+    for section in sections(report) {
+        let _ = write!(out, "\n## {}\n\n", section.title);
+        if section.rows.is_empty() {
+            out.push_str("_None found._\n");
+        } else {
+            let _ = writeln!(out, "| {} |", section.headers.join(" | "));
+            let _ = writeln!(out, "|{}", " --- |".repeat(section.headers.len()));
+            for row in &section.rows {
+                let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+                let _ = writeln!(out, "| {} |", cells.join(" | "));
+            }
+        }
+        if !section.notes.is_empty() {
+            out.push('\n');
+            for note in &section.notes {
+                let _ = writeln!(out, "- {}", markdown_cell(note));
+            }
+        }
+    }
+    out
+}
+
+/// Keep a value on one table line and stop it from closing the cell
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+const HTML_STYLE: &str = "\
+body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;margin:2rem;color:#1f2937}\
+h1{margin-bottom:.5rem}h2{margin-top:2rem;border-bottom:1px solid #e5e7eb;padding-bottom:.25rem}\
+dl{display:grid;grid-template-columns:max-content auto;gap:.25rem 1rem}dt{font-weight:600}dd{margin:0}\
+table{border-collapse:collapse;width:100%;font-size:.9rem}\
+th,td{border:1px solid #e5e7eb;padding:.35rem .6rem;text-align:left;vertical-align:top;word-break:break-all}\
+th{background:#f3f4f6}.empty{color:#6b7280;font-style:italic}";
+
+/// Render the report as a standalone HTML page with inline styles
+pub fn render_html(report: &InventoryReport) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    out.push_str("<meta charset=\"utf-8\">\n");
+    let _ = writeln!(
+        out,
+        "<title>Dev Janitor Inventory Report - {}</title>",
+        escape_html(report.host.hostname.as_deref().unwrap_or("unknown"))
+    );
+    let _ = writeln!(out, "<style>{}</style>", HTML_STYLE);
+    out.push_str("</head>\n<body>\n<h1>Dev Janitor Inventory Report</h1>\n<dl>\n");
+    for (label, value) in metadata(report) {
+        let _ = writeln!(
+            out,
+            "<dt>{}</dt><dd>{}</dd>",
+            escape_html(label),
+            escape_html(&value)
+        );
+    }
+    out.push_str("</dl>\n");
+
+    for section in sections(report) {
+        let _ = writeln!(out, "<h2>{}</h2>", escape_html(section.title));
+        if section.rows.is_empty() {
+            out.push_str("<p class=\"empty\">None found.</p>\n");
+        } else {
+            out.push_str("<table>\n<thead><tr>");
+            for header in section.headers {
+                let _ = write!(out, "<th>{}</th>", escape_html(header));
+            }
+            out.push_str("</tr></thead>\n<tbody>\n");
+            for row in &section.rows {
+                out.push_str("<tr>");
+                for cell in row {
+                    let _ = write!(out, "<td>{}</td>", escape_html(cell));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</tbody>\n</table>\n");
+        }
+        if !section.notes.is_empty() {
+            out.push_str("<ul>\n");
+            for note in &section.notes {
+                let _ = writeln!(out, "<li>{}</li>", escape_html(note));
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_report() -> InventoryReport {
+        let security = serde_json::from_value(json!({
+            "scan_time": "2026-10-18 09:00:00",
+            "tools_scanned": ["openclaw"],
+            "findings": [{
+                "tool_id": "openclaw",
+                "tool_name": "OpenClaw",
+                "issue": "Gateway exposed | <all interfaces>",
+                "description": "",
+                "risk_level": "High",
+                "remediation": "Bind to 127.0.0.1",
+                "details": "Port 18789 bound to 0.0.0.0"
+            }],
+            "summary": {"total_findings": 1, "critical": 0, "high": 1, "medium": 0, "low": 0}
+        }))
+        .unwrap();
+        let environment = serde_json::from_value(json!({
+            "path_entries": [
+                {"path": "/usr/bin", "exists": true, "is_dev_related": false, "category": "system", "issues": []},
+                {"path": "/opt/gone", "exists": false, "is_dev_related": false, "category": "other", "issues": []}
+            ],
+            "shell_configs": [],
+            "issues": [],
+            "suggestions": []
+        }))
+        .unwrap();
+
+        InventoryReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            generated_at: "2026-10-18T09:00:00+00:00".to_string(),
+            app_version: "0.0.0".to_string(),
+            host: HostInfo {
+                hostname: Some("build-01".to_string()),
+                os: "linux".to_string(),
+                os_version: None,
+                arch: "x86_64".to_string(),
+            },
+            tools: Vec::new(),
+            packages: vec![PackageInfo {
+                name: "typescript".to_string(),
+                version: "5.4.0".to_string(),
+                latest: Some("5.6.2".to_string()),
+                manager: "npm".to_string(),
+                is_outdated: true,
+                description: None,
+                scope: None,
+            }],
+            caches: Vec::new(),
+            ai_cli_tools: Vec::new(),
+            environment,
+            security,
+        }
+    }
+
+    #[test]
+    fn json_carries_the_schema_version() {
+        let rendered = render_report(&sample_report(), ReportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+
+        assert_eq!(value["schema_version"], REPORT_SCHEMA_VERSION);
+        assert_eq!(value["packages"][0]["name"], "typescript");
+        assert_eq!(value["host"]["hostname"], "build-01");
+    }
+
+    #[test]
+    fn markdown_renders_tables_and_escapes_cells() {
+        let markdown = render_markdown(&sample_report());
+
+        assert!(markdown.starts_with("# Dev Janitor Inventory Report\n"));
+        assert!(markdown.contains("| npm | typescript | 5.4.0 | 5.6.2 |"));
+        assert!(markdown.contains("Gateway exposed \\| <all interfaces>"));
+        assert!(markdown.contains("## Development Tools\n\n_None found._"));
+        assert!(markdown.contains("- 2 PATH entries, 1 missing"));
+    }
+
+    #[test]
+    fn html_is_standalone_and_escaped() {
+        let html = render_html(&sample_report());
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+        assert!(html.contains("Gateway exposed | &lt;all interfaces&gt;"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert_eq!(ReportFormat::parse("MD").unwrap(), ReportFormat::Markdown);
+        assert_eq!(
+            ReportFormat::parse("pdf").unwrap_err().code(),
+            "invalid_input"
+        );
+    }
+}
@@ -0,0 +1,736 @@
+//! Retention policies and their scheduled runner
+//! Policies clean caches and chat history once they grow too large or go untouched
+
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, TryLockError};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+use crate::cache::{
+    builtin_project_cache_patterns, clean_cache, scan_package_manager_caches, scan_project_caches,
+};
+use crate::chat_history::{delete_chat_file, scan_chat_history};
+use crate::disk_usage::format_size;
+use crate::error::DevJanitorError;
+use crate::settings::{active_settings, Settings};
+use crate::utils::paths;
+
+/// Values accepted for `RetentionPolicy::kind`
+pub const POLICY_KINDS: &[&str] = &["package_cache", "project_cache", "chat_history"];
+
+const RUNS_FILE_NAME: &str = "retention-runs.jsonl";
+
+/// How often the scheduler checks whether a run is due
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+/// Bytes read at a time when looking for the last recorded run
+const TAIL_CHUNK: u64 = 8 * 1024;
+
+/// Held while a run applies its policies, so runs never overlap
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// When the last run finished, once known (`Some(None)`: never). Kept in memory so a run log
+/// that cannot be written does not make every scheduler tick look due.
+static LAST_FINISHED: Mutex<Option<Option<String>>> = Mutex::new(None);
+
+/// A rule such as "clean the npm cache above 5 GB"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub id: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// package_cache, project_cache or chat_history
+    pub kind: String,
+    /// Package cache id (`npm`), project cache directory (`node_modules`) or
+    /// chat history tool (`Aider`); every item of the kind when unset
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Only items larger than this many bytes
+    #[serde(default)]
+    pub min_size: Option<u64>,
+    /// Only items untouched for more than this many days
+    #[serde(default)]
+    pub older_than_days: Option<u32>,
+    /// Directories searched by project_cache and chat_history policies;
+    /// the configured scan roots when empty
+    #[serde(default)]
+    pub roots: Vec<String>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl RetentionPolicy {
+    /// Check the policy at `index` of the settings list
+    pub fn validate(&self, index: usize) -> Result<(), DevJanitorError> {
+        let invalid = |detail: String| {
+            DevJanitorError::InvalidInput(format!("retention_policies[{}]: {}", index, detail))
+        };
+
+        if self.id.trim().is_empty() {
+            return Err(invalid("id is empty".to_string()));
+        }
+        if !POLICY_KINDS.contains(&self.kind.as_str()) {
+            return Err(invalid(format!(
+                "kind must be one of {}, not `{}`",
+                POLICY_KINDS.join(", "),
+                self.kind
+            )));
+        }
+        // A policy without conditions would empty every cache on each run
+        if self.min_size.is_none() && self.older_than_days.is_none() {
+            return Err(invalid("set min_size, older_than_days or both".to_string()));
+        }
+        if self.kind == "package_cache" && !self.roots.is_empty() {
+            return Err(invalid(
+                "package_cache policies do not take roots".to_string(),
+            ));
+        }
+        if let Some(root) = self
+            .roots
+            .iter()
+            .find(|root| !Path::new(root).is_absolute())
+        {
+            return Err(invalid(format!("root `{}` must be absolute", root)));
+        }
+        Ok(())
+    }
+
+    fn selects(&self, value: &str) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|filter| filter.eq_ignore_ascii_case(value))
+    }
+
+    fn conditions_hold(&self, size: u64, age_days: Option<u64>) -> bool {
+        self.min_size.is_none_or(|min_size| size > min_size)
+            && self
+                .older_than_days
+                .is_none_or(|days| age_days.is_some_and(|age_days| age_days > u64::from(days)))
+    }
+}
+
+/// One item a policy selected, and what happened to it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetentionAction {
+    pub policy_id: String,
+    pub target: String,
+    pub size: u64,
+    pub size_display: String,
+    /// Days since the item (for project caches, its project) was last modified
+    pub age_days: Option<u64>,
+    /// Whether the cleanup succeeded; `None` in previews
+    pub success: Option<bool>,
+    pub error: Option<String>,
+    /// Stable `DevJanitorError` code of a failed cleanup
+    pub error_code: Option<String>,
+}
+
+/// Everything one evaluation of the policies selected or cleaned
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetentionRun {
+    /// RFC 3339 local times
+    pub started_at: String,
+    pub finished_at: String,
+    /// manual or schedule
+    pub trigger: String,
+    /// Previews select items without cleaning them and are not recorded
+    pub dry_run: bool,
+    pub actions: Vec<RetentionAction>,
+    /// Policies that could not be evaluated, with the reason
+    pub errors: Vec<String>,
+    pub bytes_freed: u64,
+    pub bytes_freed_display: String,
+    /// Bytes moved into quarantine instead of freed
+    #[serde(default)]
+    pub bytes_quarantined: u64,
+    #[serde(default)]
+    pub bytes_quarantined_display: String,
+}
+
+fn now() -> String {
+    Local::now().to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// Version control directories; only their HEAD and index files say when work happened
+const VCS_DIRS: &[&str] = &[".git", ".hg", ".svn", ".jj"];
+const VCS_ACTIVITY_FILES: &[&str] = &[".git/HEAD", ".git/index", ".hg/dirstate"];
+
+/// Bounds of the walk over a project's own files
+const PROJECT_WALK_MAX_DEPTH: usize = 8;
+const PROJECT_WALK_MAX_ENTRIES: usize = 20_000;
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn days_since(time: SystemTime) -> u64 {
+    let elapsed = SystemTime::now().duration_since(time).unwrap_or_default();
+    elapsed.as_secs() / 86_400
+}
+
+/// Whole days since `path` was last modified
+fn age_days(path: &Path) -> Option<u64> {
+    modified(path).map(days_since)
+}
+
+/// Days since the project holding `cache_path` was touched
+///
+/// Uses the newest file or directory anywhere in the project, leaving out project caches,
+/// which builds rewrite without anyone working on the project, and version control internals
+/// apart from the files a commit, checkout or staging updates.
+fn project_age_days(cache_path: &Path) -> Option<u64> {
+    let project = cache_path.parent()?;
+    let caches = builtin_project_cache_patterns();
+    let skipped = |entry: &walkdir::DirEntry| {
+        let name = entry.file_name().to_string_lossy();
+        entry.file_type().is_dir()
+            && (entry.path() == cache_path
+                || VCS_DIRS.contains(&name.as_ref())
+                || caches.contains(&name.as_ref()))
+    };
+    let newest_file = WalkDir::new(project)
+        .min_depth(1)
+        .max_depth(PROJECT_WALK_MAX_DEPTH)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| !skipped(entry))
+        .filter_map(|entry| entry.ok())
+        .take(PROJECT_WALK_MAX_ENTRIES)
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok());
+    let vcs_activity = VCS_ACTIVITY_FILES
+        .iter()
+        .filter_map(|file| modified(&project.join(file)));
+
+    newest_file
+        .chain(vcs_activity)
+        .max()
+        .map(days_since)
+        .or_else(|| age_days(project))
+}
+
+fn policy_roots(
+    policy: &RetentionPolicy,
+    settings: &Settings,
+) -> Result<Vec<String>, DevJanitorError> {
+    if policy.roots.is_empty() {
+        settings.scan_roots(None)
+    } else {
+        Ok(policy.roots.clone())
+    }
+}
+
+fn action(
+    policy: &RetentionPolicy,
+    target: &str,
+    size: u64,
+    age_days: Option<u64>,
+) -> RetentionAction {
+    RetentionAction {
+        policy_id: policy.id.clone(),
+        target: target.to_string(),
+        size,
+        size_display: format_size(size),
+        age_days,
+        success: None,
+        error: None,
+        error_code: None,
+    }
+}
+
+/// Items `policy` would clean right now
+fn find_candidates(
+    policy: &RetentionPolicy,
+    settings: &Settings,
+) -> Result<Vec<RetentionAction>, DevJanitorError> {
+    let depth = settings.scan_depth(None);
+    let mut candidates = Vec::new();
+
+    match policy.kind.as_str() {
+        "package_cache" => {
+            for cache in scan_package_manager_caches() {
+                let age = age_days(Path::new(&cache.path));
+                if policy.selects(&cache.id) && policy.conditions_hold(cache.size, age) {
+                    candidates.push(action(policy, &cache.path, cache.size, age));
+                }
+            }
+        }
+        "project_cache" => {
+            for root in policy_roots(policy, settings)? {
+                for cache in scan_project_caches(&root, depth) {
+                    let path = PathBuf::from(&cache.path);
+                    let dir_name = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let age = project_age_days(&path);
+                    if policy.selects(&dir_name) && policy.conditions_hold(cache.size, age) {
+                        candidates.push(action(policy, &cache.path, cache.size, age));
+                    }
+                }
+            }
+        }
+        _ => {
+            for root in policy_roots(policy, settings)? {
+                for project in scan_chat_history(&root, depth) {
+                    for file in project.chat_files {
+                        let age = age_days(Path::new(&file.path));
+                        if policy.selects(&file.ai_tool) && policy.conditions_hold(file.size, age) {
+                            candidates.push(action(policy, &file.path, file.size, age));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(candidates)
+}
+
+fn evaluate(trigger: &str, dry_run: bool) -> RetentionRun {
+    let settings = active_settings();
+    let started_at = now();
+    let mut actions = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    let mut bytes_freed = 0;
+    let mut bytes_quarantined = 0;
+
+    for policy in settings
+        .retention_policies
+        .iter()
+        .filter(|policy| policy.enabled)
+    {
+        let candidates = match find_candidates(policy, &settings) {
+            Ok(candidates) => candidates,
+            Err(error) => {
+                errors.push(format!("{}: {}", policy.id, error));
+                continue;
+            }
+        };
+
+        // An item selected by several policies is cleaned once
+        for mut candidate in candidates {
+            if !seen.insert(candidate.target.clone()) {
+                continue;
+            }
+            if !dry_run {
+                let result = if policy.kind == "chat_history" {
+                    delete_chat_file(&candidate.target)
+                } else {
+                    clean_cache(&candidate.target)
+                };
+                candidate.success = Some(result.is_ok());
+                match result {
+                    Ok(result) => {
+                        bytes_freed += result.bytes_freed;
+                        bytes_quarantined += result.bytes_quarantined;
+                    }
+                    Err(error) => {
+                        candidate.error = Some(error.to_string());
+                        candidate.error_code = Some(error.code().to_string());
+                    }
+                }
+            }
+            actions.push(candidate);
+        }
+    }
+
+    RetentionRun {
+        started_at,
+        finished_at: now(),
+        trigger: trigger.to_string(),
+        dry_run,
+        actions,
+        errors,
+        bytes_freed,
+        bytes_freed_display: format_size(bytes_freed),
+        bytes_quarantined,
+        bytes_quarantined_display: format_size(bytes_quarantined),
+    }
+}
+
+/// What the enabled policies would clean, without cleaning anything
+pub fn preview_retention() -> RetentionRun {
+    evaluate("manual", true)
+}
+
+/// Apply the enabled policies and record the run
+///
+/// `trigger` is manual or schedule. Fails with `already_exists` while
+/// another run is in progress.
+pub fn run_retention(trigger: &str) -> Result<RetentionRun, DevJanitorError> {
+    let _guard = match RUN_LOCK.try_lock() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => {
+            return Err(DevJanitorError::AlreadyExists(
+                "A retention run is already in progress".to_string(),
+            ))
+        }
+    };
+
+    let mut run = evaluate(trigger, false);
+    *lock(&LAST_FINISHED) = Some(Some(run.finished_at.clone()));
+    if let Err(error) = append_run(&run) {
+        run.errors
+            .push(format!("Failed to record retention run: {}", error));
+    }
+    Ok(run)
+}
+
+fn runs_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(RUNS_FILE_NAME))
+}
+
+fn append_run(run: &RetentionRun) -> Result<(), String> {
+    let path = runs_path().ok_or_else(|| "Could not determine the data directory".to_string())?;
+    let mut line = serde_json::to_string(run).map_err(|error| error.to_string())?;
+    line.push('\n');
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+/// Recorded runs, newest first, at most `limit` of them
+pub fn list_retention_runs(limit: Option<usize>) -> Result<Vec<RetentionRun>, DevJanitorError> {
+    let path = runs_path().ok_or_else(|| {
+        DevJanitorError::NotFound("Could not determine the data directory".to_string())
+    })?;
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(DevJanitorError::io(
+                format!("Failed to read {}", path.display()),
+                error,
+            ))
+        }
+    };
+
+    let mut runs = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|error| {
+            DevJanitorError::io(format!("Failed to read {}", path.display()), error)
+        })?;
+        // Skip a torn final line rather than hiding the whole history
+        if let Ok(run) = serde_json::from_str::<RetentionRun>(&line) {
+            runs.push(run);
+        }
+    }
+
+    runs.reverse();
+    if let Some(limit) = limit {
+        runs.truncate(limit);
+    }
+    Ok(runs)
+}
+
+/// The newest run in the log, read backwards from the end so the log's length does not matter
+fn read_last_run(path: &Path) -> Result<Option<RetentionRun>, DevJanitorError> {
+    let read_error =
+        |error| DevJanitorError::io(format!("Failed to read {}", path.display()), error);
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(read_error(error)),
+    };
+
+    let mut end = file.metadata().map_err(read_error)?.len();
+    // Start of the file's unread part that ends in the middle of a line
+    let mut partial: Vec<u8> = Vec::new();
+    while end > 0 {
+        let start = end.saturating_sub(TAIL_CHUNK);
+        let mut chunk = vec![0; (end - start) as usize];
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.read_exact(&mut chunk))
+            .map_err(read_error)?;
+        chunk.extend_from_slice(&partial);
+        end = start;
+
+        // Only lines after the first newline are known to be complete, until the start
+        let split = match chunk.iter().position(|&byte| byte == b'\n') {
+            _ if start == 0 => 0,
+            Some(newline) => newline + 1,
+            None => {
+                partial = chunk;
+                continue;
+            }
+        };
+        // Skip a torn final line rather than hiding the whole history
+        let newest = chunk[split..]
+            .rsplit(|&byte| byte == b'\n')
+            .find_map(|line| serde_json::from_slice::<RetentionRun>(line).ok());
+        if newest.is_some() {
+            return Ok(newest);
+        }
+        chunk.truncate(split);
+        partial = chunk;
+    }
+    Ok(None)
+}
+
+/// When the last run finished, from memory or else the run log
+fn last_finished() -> Result<Option<String>, DevJanitorError> {
+    let mut cached = lock(&LAST_FINISHED);
+    if let Some(last) = cached.as_ref() {
+        return Ok(last.clone());
+    }
+    let path = runs_path().ok_or_else(|| {
+        DevJanitorError::NotFound("Could not determine the data directory".to_string())
+    })?;
+    let last = read_last_run(&path)?.map(|run| run.finished_at);
+    *cached = Some(last.clone());
+    Ok(last)
+}
+
+/// Whether a scheduled run is due, given when the last run finished
+fn run_is_due(interval_hours: u32, last_finished: Option<&str>, now: DateTime<Local>) -> bool {
+    if interval_hours == 0 {
+        return false;
+    }
+    let Some(last) = last_finished.and_then(|last| DateTime::parse_from_rfc3339(last).ok()) else {
+        return true;
+    };
+    now.signed_duration_since(last) >= chrono::Duration::hours(i64::from(interval_hours))
+}
+
+/// Start the background runner that applies the policies every
+/// `retention_interval_hours`; later calls do nothing
+///
+/// `on_problem` receives skipped runs and the errors of scheduled runs, which
+/// have no caller of their own to report to.
+pub fn spawn_scheduler(on_problem: impl Fn(DevJanitorError) + Send + 'static) {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SCHEDULER_TICK);
+
+        let settings = active_settings();
+        if settings.retention_policies.is_empty() {
+            continue;
+        }
+        // Without knowing when the last run was, a run could repeat every tick
+        let last_finished = match last_finished() {
+            Ok(last) => last,
+            Err(error) => {
+                on_problem(error);
+                continue;
+            }
+        };
+        if !run_is_due(
+            settings.retention_interval_hours,
+            last_finished.as_deref(),
+            Local::now(),
+        ) {
+            continue;
+        }
+        match run_retention("schedule") {
+            Ok(run) if !run.errors.is_empty() => {
+                on_problem(DevJanitorError::Failed(run.errors.join("; ")))
+            }
+            Ok(_) => {}
+            Err(error) => on_problem(error),
+        }
+    });
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn policy(kind: &str) -> RetentionPolicy {
+        RetentionPolicy {
+            id: "test".to_string(),
+            enabled: true,
+            kind: kind.to_string(),
+            filter: None,
+            min_size: None,
+            older_than_days: None,
+            roots: Vec::new(),
+        }
+    }
+
+    fn temp_project(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("dev-janitor-retention-{name}-{nanos}"));
+        fs::create_dir_all(dir.join("app/node_modules/pkg")).unwrap();
+        fs::write(
+            dir.join("app/node_modules/pkg/index.js"),
+            vec![b'x'; 2 * 1024 * 1024],
+        )
+        .unwrap();
+        let manifest = dir.join("app/package.json");
+        fs::write(&manifest, "{}\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&manifest)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(90 * 86_400))
+            .unwrap();
+        dir
+    }
+
+    #[test]
+    fn selects_untouched_and_oversized_project_caches() {
+        let root = temp_project("select");
+        let settings = Settings::default();
+        let untouched = RetentionPolicy {
+            filter: Some("node_modules".to_string()),
+            older_than_days: Some(60),
+            roots: vec![root.to_string_lossy().to_string()],
+            ..policy("project_cache")
+        };
+
+        let candidates = find_candidates(&untouched, &settings).unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert!(candidates[0].target.ends_with("node_modules"));
+        assert_eq!(candidates[0].age_days, Some(90));
+        assert_eq!(candidates[0].success, None);
+
+        let recent = RetentionPolicy {
+            older_than_days: Some(120),
+            ..untouched.clone()
+        };
+        assert!(find_candidates(&recent, &settings).unwrap().is_empty());
+
+        let too_small = RetentionPolicy {
+            older_than_days: None,
+            min_size: Some(5 * 1024 * 1024),
+            ..untouched
+        };
+        assert!(find_candidates(&too_small, &settings).unwrap().is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn counts_nested_edits_towards_project_age() {
+        let root = temp_project("nested");
+        let app = root.join("app");
+        let set_age = |path: &Path, days: u64| {
+            File::open(path)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(days * 86_400))
+                .unwrap();
+        };
+        fs::create_dir_all(app.join("src/components")).unwrap();
+        fs::write(app.join("src/components/button.js"), "export {}\n").unwrap();
+        set_age(&app.join("src/components"), 90);
+        set_age(&app.join("src"), 90);
+
+        let cache = app.join("node_modules");
+        assert_eq!(project_age_days(&cache), Some(0));
+
+        // Build output and version control internals say nothing about edits
+        fs::remove_dir_all(app.join("src")).unwrap();
+        fs::create_dir_all(app.join("dist")).unwrap();
+        fs::write(app.join("dist/bundle.js"), "").unwrap();
+        fs::create_dir_all(app.join(".git/objects")).unwrap();
+        fs::write(app.join(".git/objects/pack"), "").unwrap();
+        for dir in ["dist", ".git/objects", ".git"] {
+            set_age(&app.join(dir), 90);
+        }
+        assert_eq!(project_age_days(&cache), Some(90));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rejects_policies_without_conditions() {
+        let error = policy("project_cache").validate(2).unwrap_err();
+        assert_eq!(error.code(), "invalid_input");
+        assert!(error.to_string().contains("retention_policies[2]"));
+
+        let unknown = RetentionPolicy {
+            min_size: Some(1),
+            ..policy("docker_images")
+        };
+        assert!(unknown.validate(0).is_err());
+
+        let valid = RetentionPolicy {
+            min_size: Some(5 * 1024 * 1024 * 1024),
+            filter: Some("npm".to_string()),
+            ..policy("package_cache")
+        };
+        assert!(valid.validate(0).is_ok());
+    }
+
+    #[test]
+    fn schedules_runs_by_interval() {
+        let now = DateTime::parse_from_rfc3339("2026-10-18T12:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert!(!run_is_due(0, None, now));
+        assert!(run_is_due(24, None, now));
+        assert!(!run_is_due(24, Some("2026-10-18T01:00:00+00:00"), now));
+        assert!(run_is_due(24, Some("2026-10-17T11:00:00+00:00"), now));
+    }
+
+    #[test]
+    fn reads_the_last_run_from_the_end_of_the_log() {
+        let path = temp_project("log").join(RUNS_FILE_NAME);
+        assert_eq!(read_last_run(&path).unwrap(), None);
+
+        let run = |finished_at: String| RetentionRun {
+            started_at: finished_at.clone(),
+            finished_at,
+            trigger: "schedule".to_string(),
+            dry_run: false,
+            actions: Vec::new(),
+            errors: vec!["x".repeat(100)],
+            bytes_freed: 0,
+            bytes_freed_display: format_size(0),
+            bytes_quarantined: 0,
+            bytes_quarantined_display: format_size(0),
+        };
+        // Longer than several chunks, ending in a line torn by an interrupted write
+        let mut log = String::new();
+        for minute in 0..200 {
+            log.push_str(
+                &serde_json::to_string(&run(format!("2026-10-18T12:{:02}:00+00:00", minute % 60)))
+                    .unwrap(),
+            );
+            log.push('\n');
+        }
+        log.push_str(
+            &serde_json::to_string(&run("2026-10-18T23:00:00+00:00".to_string())).unwrap()[..40],
+        );
+        assert!(log.len() as u64 > 3 * TAIL_CHUNK);
+        fs::write(&path, log).unwrap();
+
+        let last = read_last_run(&path).unwrap().unwrap();
+        assert_eq!(last.finished_at, "2026-10-18T12:19:00+00:00");
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
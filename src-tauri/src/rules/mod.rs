@@ -0,0 +1,662 @@
+//! User rule overlays for the built-in cleanup patterns
+//! Adds and disables cache, AI junk, chat history, process and tool rules from rules.toml or rules.json
+
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::ai_cleanup::{builtin_ai_tool_patterns, builtin_temp_file_patterns};
+use crate::cache::{builtin_package_cache_ids, builtin_project_cache_patterns};
+use crate::chat_history::builtin_chat_history_patterns;
+use crate::detection::catalog::{builtin_tool_ids, validate_tool_rule, ToolRule};
+use crate::error::DevJanitorError;
+use crate::services::builtin_dev_process_patterns;
+use crate::utils::paths;
+
+/// Rule files looked up in the config directory, in order of preference
+const RULES_FILE_NAMES: &[&str] = &["rules.toml", "rules.json"];
+
+/// Rules in effect, loaded on first use
+static ACTIVE_RULES: Mutex<Option<Arc<UserRules>>> = Mutex::new(None);
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// One section of a rules file: rules to add and built-in patterns to turn off
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SectionFile<T> {
+    add: Vec<T>,
+    disable: Vec<String>,
+}
+
+impl<T> Default for SectionFile<T> {
+    fn default() -> Self {
+        Self {
+            add: Vec::new(),
+            disable: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectCacheRuleFile {
+    pattern: String,
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackageCacheRuleFile {
+    id: String,
+    name: String,
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReasonRuleFile {
+    pattern: String,
+    reason: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChatHistoryRuleFile {
+    pattern: String,
+    tool: String,
+    #[serde(default = "default_chat_file_type")]
+    file_type: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProcessRuleFile {
+    pattern: String,
+    category: String,
+}
+
+fn default_chat_file_type() -> String {
+    "chat_history".to_string()
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RulesFile {
+    project_caches: SectionFile<ProjectCacheRuleFile>,
+    package_caches: SectionFile<PackageCacheRuleFile>,
+    ai_tools: SectionFile<ReasonRuleFile>,
+    temp_files: SectionFile<ReasonRuleFile>,
+    chat_history: SectionFile<ChatHistoryRuleFile>,
+    dev_processes: SectionFile<ProcessRuleFile>,
+    tools: SectionFile<ToolRule>,
+}
+
+/// A validated glob from a rules file
+///
+/// Patterns without `/` match a file or directory name. Patterns with `/`
+/// match the same number of trailing components of a path or its ancestors.
+/// Matching ignores case.
+#[derive(Debug, Clone)]
+pub struct RulePattern {
+    raw: String,
+    glob: Pattern,
+    components: usize,
+}
+
+impl RulePattern {
+    fn parse(raw: &str) -> Result<Self, String> {
+        let trimmed = raw.trim().trim_matches('/');
+        if trimmed.is_empty() {
+            return Err("pattern is empty".to_string());
+        }
+        if trimmed.contains("**") {
+            return Err(format!(
+                "`**` is not supported in `{}`; path patterns already match at any depth",
+                raw
+            ));
+        }
+        if trimmed.chars().all(|c| matches!(c, '*' | '?' | '/')) {
+            return Err(format!("`{}` would match everything", raw));
+        }
+        let glob = Pattern::new(trimmed)
+            .map_err(|error| format!("invalid glob `{}`: {}", raw, error.msg))?;
+
+        Ok(Self {
+            raw: trimmed.to_string(),
+            glob,
+            components: trimmed.split('/').count(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Match a bare file, directory or process name
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.components == 1 && self.glob.matches_with(name, MATCH_OPTIONS)
+    }
+
+    /// Match `path` by name, or by trailing components of it or an ancestor
+    pub fn matches_path(&self, path: &Path) -> bool {
+        let parts: Vec<String> = path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect();
+
+        if self.components == 1 {
+            return parts.last().is_some_and(|name| self.matches_name(name));
+        }
+
+        (self.components..=parts.len()).any(|end| {
+            let tail = parts[end - self.components..end].join("/");
+            self.glob.matches_with(&tail, MATCH_OPTIONS)
+        })
+    }
+}
+
+/// A user rule: a pattern and what it stands for (cache name, reason, tool or category)
+#[derive(Debug, Clone)]
+pub struct PatternRule {
+    pub pattern: RulePattern,
+    pub label: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatHistoryRule {
+    pub pattern: RulePattern,
+    pub tool: String,
+    pub file_type: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PackageCacheRule {
+    pub id: String,
+    pub name: String,
+    pub paths: Vec<PathBuf>,
+}
+
+/// User additions to one built-in rule list, plus the built-ins they turn off
+#[derive(Debug, Clone)]
+pub struct Overlay<T> {
+    pub added: Vec<T>,
+    disabled: HashSet<String>,
+}
+
+impl<T> Default for Overlay<T> {
+    fn default() -> Self {
+        Self {
+            added: Vec::new(),
+            disabled: HashSet::new(),
+        }
+    }
+}
+
+impl<T> Overlay<T> {
+    /// Whether the built-in pattern (or package cache id) was disabled
+    pub fn is_disabled(&self, builtin: &str) -> bool {
+        self.disabled.contains(&builtin.to_ascii_lowercase())
+    }
+}
+
+/// Validated user rules
+#[derive(Debug, Clone, Default)]
+pub struct UserRules {
+    /// File the rules came from; `None` when no rules file exists
+    pub source: Option<PathBuf>,
+    pub project_caches: Overlay<PatternRule>,
+    pub package_caches: Overlay<PackageCacheRule>,
+    pub ai_tools: Overlay<PatternRule>,
+    pub temp_files: Overlay<PatternRule>,
+    pub chat_history: Overlay<ChatHistoryRule>,
+    pub dev_processes: Overlay<PatternRule>,
+    /// Tool detection rules; an added rule replaces the catalog entry with the same id
+    pub tools: Overlay<ToolRule>,
+}
+
+/// What a rules file contributes, for display
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RulesSummary {
+    /// Rules file in effect, if any
+    pub path: Option<String>,
+    /// Where a rules file is looked for
+    pub search_paths: Vec<String>,
+    pub rules_added: usize,
+    pub builtins_disabled: usize,
+}
+
+impl UserRules {
+    pub fn summary(&self) -> RulesSummary {
+        RulesSummary {
+            path: self
+                .source
+                .as_ref()
+                .map(|path| path.to_string_lossy().to_string()),
+            search_paths: rules_file_candidates()
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            rules_added: self.project_caches.added.len()
+                + self.package_caches.added.len()
+                + self.ai_tools.added.len()
+                + self.temp_files.added.len()
+                + self.chat_history.added.len()
+                + self.dev_processes.added.len()
+                + self.tools.added.len(),
+            builtins_disabled: self.project_caches.disabled.len()
+                + self.package_caches.disabled.len()
+                + self.ai_tools.disabled.len()
+                + self.temp_files.disabled.len()
+                + self.chat_history.disabled.len()
+                + self.dev_processes.disabled.len()
+                + self.tools.disabled.len(),
+        }
+    }
+}
+
+fn rules_file_candidates() -> Vec<PathBuf> {
+    paths::config_dir()
+        .map(|dir| RULES_FILE_NAMES.iter().map(|name| dir.join(name)).collect())
+        .unwrap_or_default()
+}
+
+/// Parse and validate rules file contents; `source` names the file in errors
+/// and picks the format by extension (`.json`, otherwise TOML)
+pub fn parse_rules(contents: &str, source: &Path) -> Result<UserRules, DevJanitorError> {
+    let file_label = source.display();
+    let is_json = source
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+
+    let file: RulesFile = if is_json {
+        serde_json::from_str(contents)
+            .map_err(|error| DevJanitorError::ParseError(format!("{}: {}", file_label, error)))?
+    } else {
+        toml::from_str(contents).map_err(|error| {
+            DevJanitorError::ParseError(format!("{}: {}", file_label, error.message()))
+        })?
+    };
+
+    let invalid = |section: &str, index: usize, detail: String| {
+        DevJanitorError::InvalidInput(format!(
+            "{}: {}.add[{}]: {}",
+            file_label, section, index, detail
+        ))
+    };
+
+    // A name that matches no built-in would silently disable nothing
+    let disabled_set = |section: &str,
+                        disable: Vec<String>,
+                        builtins: Vec<&str>|
+     -> Result<HashSet<String>, DevJanitorError> {
+        let known: HashSet<String> = builtins
+            .iter()
+            .map(|builtin| builtin.to_ascii_lowercase())
+            .collect();
+        disable
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let name = name.trim();
+                let lowered = name.to_ascii_lowercase();
+                if known.contains(&lowered) {
+                    Ok(lowered)
+                } else {
+                    Err(DevJanitorError::InvalidInput(format!(
+                        "{}: {}.disable[{}]: `{}` matches no built-in rule",
+                        file_label, section, index, name
+                    )))
+                }
+            })
+            .collect()
+    };
+
+    let pattern_overlay = |section: &str,
+                           entries: Vec<(String, String)>,
+                           disable: Vec<String>,
+                           builtins: Vec<&str>|
+     -> Result<Overlay<PatternRule>, DevJanitorError> {
+        let added = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, (pattern, label))| {
+                let pattern = RulePattern::parse(&pattern)
+                    .map_err(|detail| invalid(section, index, detail))?;
+                if label.trim().is_empty() {
+                    return Err(invalid(section, index, "label is empty".to_string()));
+                }
+                Ok(PatternRule { pattern, label })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Overlay {
+            added,
+            disabled: disabled_set(section, disable, builtins)?,
+        })
+    };
+
+    let project_caches = pattern_overlay(
+        "project_caches",
+        file.project_caches
+            .add
+            .into_iter()
+            .map(|rule| (rule.pattern, rule.name))
+            .collect(),
+        file.project_caches.disable,
+        builtin_project_cache_patterns(),
+    )?;
+    let ai_tools = pattern_overlay(
+        "ai_tools",
+        file.ai_tools
+            .add
+            .into_iter()
+            .map(|rule| (rule.pattern, rule.reason))
+            .collect(),
+        file.ai_tools.disable,
+        builtin_ai_tool_patterns(),
+    )?;
+    let temp_files = pattern_overlay(
+        "temp_files",
+        file.temp_files
+            .add
+            .into_iter()
+            .map(|rule| (rule.pattern, rule.reason))
+            .collect(),
+        file.temp_files.disable,
+        builtin_temp_file_patterns(),
+    )?;
+    let dev_processes = pattern_overlay(
+        "dev_processes",
+        file.dev_processes
+            .add
+            .into_iter()
+            .map(|rule| (rule.pattern, rule.category))
+            .collect(),
+        file.dev_processes.disable,
+        builtin_dev_process_patterns(),
+    )?;
+
+    let chat_history = Overlay {
+        added: file
+            .chat_history
+            .add
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                let pattern = RulePattern::parse(&rule.pattern)
+                    .map_err(|detail| invalid("chat_history", index, detail))?;
+                Ok(ChatHistoryRule {
+                    pattern,
+                    tool: rule.tool,
+                    file_type: rule.file_type,
+                })
+            })
+            .collect::<Result<_, DevJanitorError>>()?,
+        disabled: disabled_set(
+            "chat_history",
+            file.chat_history.disable,
+            builtin_chat_history_patterns(),
+        )?,
+    };
+
+    let package_caches = Overlay {
+        added: file
+            .package_caches
+            .add
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                if rule.id.trim().is_empty() {
+                    return Err(invalid("package_caches", index, "id is empty".to_string()));
+                }
+                let paths = rule
+                    .paths
+                    .iter()
+                    .map(|path| expand_cache_path(path))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|detail| invalid("package_caches", index, detail))?;
+                Ok(PackageCacheRule {
+                    id: rule.id,
+                    name: rule.name,
+                    paths,
+                })
+            })
+            .collect::<Result<_, DevJanitorError>>()?,
+        disabled: disabled_set(
+            "package_caches",
+            file.package_caches.disable,
+            builtin_package_cache_ids(),
+        )?,
+    };
+
+    let tools = Overlay {
+        added: file
+            .tools
+            .add
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                validate_tool_rule(&rule).map_err(|detail| invalid("tools", index, detail))?;
+                Ok(rule)
+            })
+            .collect::<Result<_, DevJanitorError>>()?,
+        disabled: disabled_set("tools", file.tools.disable, builtin_tool_ids())?,
+    };
+
+    Ok(UserRules {
+        source: Some(source.to_path_buf()),
+        project_caches,
+        package_caches,
+        ai_tools,
+        temp_files,
+        chat_history,
+        dev_processes,
+        tools,
+    })
+}
+
+/// Expand a leading `~` and require an absolute result
+fn expand_cache_path(path: &str) -> Result<PathBuf, String> {
+    let expanded = match path.strip_prefix("~") {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            let home = paths::home_dir()
+                .ok_or_else(|| format!("cannot expand `{}`: home directory unknown", path))?;
+            home.join(rest.trim_start_matches(['/', '\\']))
+        }
+        _ => PathBuf::from(path),
+    };
+
+    if !expanded.is_absolute() {
+        return Err(format!(
+            "cache path `{}` must be absolute or start with ~/",
+            path
+        ));
+    }
+    if expanded.parent().is_none() || Some(&expanded) == paths::home_dir().as_ref() {
+        return Err(format!(
+            "cache path `{}` is the root or home directory",
+            path
+        ));
+    }
+    Ok(expanded)
+}
+
+/// Read and validate the user rules file, or return empty rules when there is none
+pub fn load_user_rules() -> Result<UserRules, DevJanitorError> {
+    let Some(path) = rules_file_candidates()
+        .into_iter()
+        .find(|path| path.is_file())
+    else {
+        return Ok(UserRules::default());
+    };
+
+    let contents = fs::read_to_string(&path).map_err(|error| {
+        DevJanitorError::io(format!("Failed to read {}", path.display()), error)
+    })?;
+    parse_rules(&contents, &path)
+}
+
+/// Rules in effect; a broken rules file leaves only the built-ins, and
+/// `load_user_rules` or `reload_rules` report what is wrong with it
+pub fn active_rules() -> Arc<UserRules> {
+    let mut active = ACTIVE_RULES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    active
+        .get_or_insert_with(|| Arc::new(load_user_rules().unwrap_or_default()))
+        .clone()
+}
+
+/// Re-read the rules file; on error the rules in effect stay unchanged
+pub fn reload_rules() -> Result<RulesSummary, DevJanitorError> {
+    let rules = load_user_rules()?;
+    let summary = rules.summary();
+    *ACTIVE_RULES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(rules));
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML_RULES: &str = r#"
+[project_caches]
+disable = ["vendor"]
+
+[[project_caches.add]]
+pattern = ".acme-cache"
+name = "Acme Build Cache"
+
+[[package_caches.add]]
+id = "acme"
+name = "Acme Cache"
+paths = ["/opt/acme/cache"]
+
+[[ai_tools.add]]
+pattern = "acme-agent-*.log"
+reason = "Acme agent log"
+
+[[chat_history.add]]
+pattern = ".acme/sessions"
+tool = "Acme Agent"
+
+[dev_processes]
+disable = ["make"]
+
+[tools]
+disable = ["svn"]
+
+[[tools.add]]
+id = "terraform"
+name = "Terraform"
+category = "infrastructure"
+commands = ["terraform"]
+version_args = ["version"]
+version_regex = 'Terraform v(\d+\.\d+\.\d+)'
+uninstall = { manual = "Remove the terraform binary from your PATH" }
+"#;
+
+    #[test]
+    fn parses_toml_overlays() {
+        let rules = parse_rules(TOML_RULES, Path::new("rules.toml")).unwrap();
+
+        assert!(rules.project_caches.is_disabled("vendor"));
+        assert!(!rules.project_caches.is_disabled("target"));
+        assert!(rules.project_caches.added[0]
+            .pattern
+            .matches_name(".acme-cache"));
+        assert_eq!(
+            rules.package_caches.added[0].paths,
+            vec![PathBuf::from("/opt/acme/cache")]
+        );
+        assert_eq!(rules.chat_history.added[0].file_type, "chat_history");
+        assert!(rules.dev_processes.is_disabled("make"));
+        assert!(rules.tools.is_disabled("svn"));
+        assert_eq!(rules.tools.added[0].commands, ["terraform"]);
+
+        let summary = rules.summary();
+        assert_eq!(summary.rules_added, 5);
+        assert_eq!(summary.builtins_disabled, 3);
+    }
+
+    #[test]
+    fn parses_json_rules() {
+        let json =
+            r#"{"temp_files": {"add": [{"pattern": "*.acmetmp", "reason": "Acme scratch"}]}}"#;
+        let rules = parse_rules(json, Path::new("rules.json")).unwrap();
+
+        assert!(rules.temp_files.added[0]
+            .pattern
+            .matches_name("build.ACMETMP"));
+    }
+
+    #[test]
+    fn matches_path_patterns_by_trailing_components() {
+        let pattern = RulePattern::parse(".acme/sessions").unwrap();
+
+        assert!(pattern.matches_path(Path::new("/home/dev/app/.acme/sessions")));
+        assert!(pattern.matches_path(Path::new("/home/dev/app/.Acme/sessions/1.json")));
+        assert!(!pattern.matches_path(Path::new("/home/dev/app/.acme")));
+        assert!(!pattern.matches_name("sessions"));
+
+        let name = RulePattern::parse("acme-agent-*.log").unwrap();
+        assert!(name.matches_path(Path::new("/srv/acme-agent-42.log")));
+        assert!(!name.matches_path(Path::new("/srv/acme-agent-42.log/inner")));
+    }
+
+    #[test]
+    fn reports_bad_rules_with_their_location() {
+        let bad_glob = "[[ai_tools.add]]\npattern = \"ok\"\nreason = \"fine\"\n\n[[ai_tools.add]]\npattern = \"agent-[\"\nreason = \"broken\"\n";
+        let error = parse_rules(bad_glob, Path::new("rules.toml")).unwrap_err();
+        assert_eq!(error.code(), "invalid_input");
+        assert!(error.to_string().contains("ai_tools.add[1]"));
+        assert!(error.to_string().contains("invalid glob `agent-[`"));
+
+        let everything = "[[project_caches.add]]\npattern = \"*\"\nname = \"All\"\n";
+        let error = parse_rules(everything, Path::new("rules.toml")).unwrap_err();
+        assert!(error.to_string().contains("would match everything"));
+
+        let relative = "[[package_caches.add]]\nid = \"x\"\nname = \"X\"\npaths = [\"cache\"]\n";
+        let error = parse_rules(relative, Path::new("rules.toml")).unwrap_err();
+        assert!(error.to_string().contains("package_caches.add[0]"));
+
+        let no_group = "[[tools.add]]\nid = \"acme\"\nname = \"Acme\"\ncategory = \"internal\"\ncommands = [\"acme\"]\nversion_regex = \"v\\\\d+\"\n";
+        let error = parse_rules(no_group, Path::new("rules.toml")).unwrap_err();
+        assert!(error.to_string().contains("tools.add[0]"));
+        assert!(error.to_string().contains("capture group"));
+
+        let misspelled = "[project_caches]\ndisable = [\"vendor\", \"node_modles\"]\n";
+        let error = parse_rules(misspelled, Path::new("rules.toml")).unwrap_err();
+        assert_eq!(error.code(), "invalid_input");
+        assert!(error
+            .to_string()
+            .contains("project_caches.disable[1]: `node_modles` matches no built-in rule"));
+        let not_a_tool = "[tools]\ndisable = [\"Node\", \"nodejs\"]\n";
+        let error = parse_rules(not_a_tool, Path::new("rules.toml")).unwrap_err();
+        assert!(error.to_string().contains("tools.disable[1]"));
+
+        let typo = "[ai_tool]\ndisable = []\n";
+        let error = parse_rules(typo, Path::new("rules.toml")).unwrap_err();
+        assert_eq!(error.code(), "parse_error");
+    }
+
+    #[test]
+    fn missing_rules_file_means_builtins_only() {
+        let rules = load_user_rules().unwrap();
+
+        assert!(rules.source.is_none());
+        assert_eq!(rules.summary().rules_added, 0);
+    }
+}
@@ -0,0 +1,640 @@
+//! Runtime versions installed by version managers
+//! Lists every Node, Python, Java and Rust version kept by nvm, fnm, Volta, mise, asdf, pyenv, SDKMAN and rustup
+
+pub mod pins;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::disk_usage::{format_size, path_size, SizeIndex};
+use crate::error::DevJanitorError;
+use crate::journal;
+use crate::lifecycle::{self, Lifecycle};
+use crate::operation::OperationResult;
+use crate::plan::PlannedRemoval;
+use crate::quarantine::{removal_description, remove_cleanup_target};
+use crate::utils::paths::{self, env_path};
+use crate::utils::version::{compare_versions, matches_version_prefix};
+
+/// One installed runtime version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeVersion {
+    /// Version manager that installed it: nvm, fnm, volta, mise, asdf, pyenv, sdkman or rustup
+    pub manager: String,
+    /// Runtime or tool name, e.g. node, python, java or rust
+    pub runtime: String,
+    pub version: String,
+    pub path: String,
+    pub size: u64,
+    pub size_display: String,
+    #[serde(default)]
+    pub allocated_size: u64,
+    /// Selected by the manager's global default (alias, global version file or `current` link)
+    pub is_default: bool,
+    #[serde(default)]
+    pub lifecycle: Option<Lifecycle>,
+}
+
+impl RuntimeVersion {
+    fn new(manager: &str, runtime: &str, version: &str, path: &Path) -> Self {
+        Self {
+            manager: manager.to_string(),
+            runtime: runtime.to_string(),
+            version: version.to_string(),
+            path: path.to_string_lossy().to_string(),
+            size: 0,
+            size_display: format_size(0),
+            allocated_size: 0,
+            is_default: false,
+            lifecycle: lifecycle::assess(runtime, version),
+        }
+    }
+}
+
+/// Where each version manager keeps its data on this machine
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ManagerRoots {
+    pub(crate) nvm: Option<PathBuf>,
+    pub(crate) nvm_windows: Option<PathBuf>,
+    pub(crate) fnm: Option<PathBuf>,
+    pub(crate) volta: Option<PathBuf>,
+    pub(crate) mise: Option<PathBuf>,
+    pub(crate) mise_config: Option<PathBuf>,
+    pub(crate) asdf: Option<PathBuf>,
+    pub(crate) tool_versions: Option<PathBuf>,
+    pub(crate) pyenv: Option<PathBuf>,
+    pub(crate) sdkman: Option<PathBuf>,
+    pub(crate) rustup: Option<PathBuf>,
+}
+
+impl ManagerRoots {
+    pub(crate) fn detect() -> Self {
+        let home = paths::home_dir();
+        let in_home = |relative: &str| home.as_ref().map(|home| home.join(relative));
+        let data_base = paths::user_data_base();
+
+        let pyenv = env_path("PYENV_ROOT")
+            .or_else(|| in_home(".pyenv"))
+            .map(|root| {
+                // pyenv-win keeps its versions one level further down
+                let windows_root = root.join("pyenv-win");
+                if windows_root.is_dir() {
+                    windows_root
+                } else {
+                    root
+                }
+            });
+        let fnm = env_path("FNM_DIR").or_else(|| {
+            [
+                data_base.as_ref().map(|base| base.join("fnm")),
+                in_home(".fnm"),
+                env_path("APPDATA").map(|appdata| appdata.join("fnm")),
+            ]
+            .into_iter()
+            .flatten()
+            .find(|dir| dir.is_dir())
+        });
+        let volta = env_path("VOLTA_HOME").or_else(|| {
+            [
+                in_home(".volta"),
+                env_path("LOCALAPPDATA").map(|local| local.join("Volta")),
+            ]
+            .into_iter()
+            .flatten()
+            .find(|dir| dir.is_dir())
+        });
+        let mise_config = env_path("MISE_CONFIG_DIR")
+            .or_else(|| env_path("XDG_CONFIG_HOME").map(|config| config.join("mise")))
+            .or_else(|| in_home(".config/mise"))
+            .map(|dir| dir.join("config.toml"));
+
+        Self {
+            nvm: env_path("NVM_DIR").or_else(|| in_home(".nvm")),
+            nvm_windows: env_path("NVM_HOME")
+                .or_else(|| env_path("APPDATA").map(|appdata| appdata.join("nvm"))),
+            fnm,
+            volta,
+            mise: env_path("MISE_DATA_DIR")
+                .or_else(|| env_path("XDG_DATA_HOME").map(|data| data.join("mise")))
+                .or_else(|| in_home(".local/share/mise")),
+            mise_config,
+            asdf: env_path("ASDF_DATA_DIR").or_else(|| in_home(".asdf")),
+            tool_versions: in_home(
+                &std::env::var("ASDF_DEFAULT_TOOL_VERSIONS_FILENAME")
+                    .unwrap_or_else(|_| ".tool-versions".to_string()),
+            ),
+            pyenv,
+            sdkman: env_path("SDKMAN_DIR").or_else(|| in_home(".sdkman")),
+            rustup: env_path("RUSTUP_HOME").or_else(|| in_home(".rustup")),
+        }
+    }
+}
+
+/// Installed versions of every version manager, without sizes
+fn collect_versions(roots: &ManagerRoots) -> Vec<RuntimeVersion> {
+    let mut versions = Vec::new();
+    if let Some(root) = &roots.nvm {
+        versions.extend(nvm_versions(root));
+    }
+    if let Some(root) = &roots.nvm_windows {
+        versions.extend(nvm_windows_versions(root));
+    }
+    if let Some(root) = &roots.fnm {
+        versions.extend(fnm_versions(root));
+    }
+    if let Some(root) = &roots.volta {
+        versions.extend(volta_versions(root));
+    }
+    let global_tools = roots
+        .tool_versions
+        .as_deref()
+        .map(read_tool_versions)
+        .unwrap_or_default();
+    if let Some(root) = &roots.mise {
+        let mut defaults = roots
+            .mise_config
+            .as_deref()
+            .map(read_mise_config)
+            .unwrap_or_default();
+        defaults.extend(global_tools.iter().cloned());
+        versions.extend(plugin_versions("mise", root, &defaults));
+    }
+    if let Some(root) = &roots.asdf {
+        versions.extend(plugin_versions("asdf", root, &global_tools));
+    }
+    if let Some(root) = &roots.pyenv {
+        versions.extend(pyenv_versions(root));
+    }
+    if let Some(root) = &roots.sdkman {
+        versions.extend(sdkman_versions(root));
+    }
+    if let Some(root) = &roots.rustup {
+        versions.extend(rustup_versions(root));
+    }
+    versions
+}
+
+/// Real subdirectories of `dir`; symlinks are aliases of versions listed elsewhere
+fn version_dirs(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path(),
+            )
+        })
+        .collect();
+    dirs.sort_by(|a, b| compare_versions(&a.0, &b.0));
+    dirs
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let trimmed = contents.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Whether `link` resolves to `dir` or somewhere inside it
+fn links_into(link: &Path, dir: &Path) -> bool {
+    match (fs::canonicalize(link), fs::canonicalize(dir)) {
+        (Ok(target), Ok(dir)) => target.starts_with(dir),
+        _ => false,
+    }
+}
+
+/// Mark the newest version selected by `request` (exact name, prefix, or `latest`) as default
+fn mark_requested(versions: &mut [RuntimeVersion], request: &str) {
+    let request = request.trim();
+    let newest = versions
+        .iter_mut()
+        .filter(|candidate| {
+            candidate.version == request
+                || matches!(request, "latest" | "node" | "stable")
+                || matches_version_prefix(&candidate.version, request)
+        })
+        .max_by(|a, b| compare_versions(&a.version, &b.version));
+    if let Some(version) = newest {
+        version.is_default = true;
+    }
+}
+
+fn nvm_versions(root: &Path) -> Vec<RuntimeVersion> {
+    let mut versions: Vec<RuntimeVersion> = version_dirs(&root.join("versions/node"))
+        .into_iter()
+        .map(|(name, path)| RuntimeVersion::new("nvm", "node", &name, &path))
+        .collect();
+
+    // Aliases point at versions or at other aliases, e.g. default -> lts/* -> lts/iron -> v20.11.1
+    let mut alias = "default".to_string();
+    for _ in 0..8 {
+        match read_trimmed(&root.join("alias").join(&alias)) {
+            Some(target) => alias = target,
+            None => break,
+        }
+    }
+    if alias != "default" {
+        mark_requested(&mut versions, &alias);
+    }
+    versions
+}
+
+fn nvm_windows_versions(root: &Path) -> Vec<RuntimeVersion> {
+    let current = env_path("NVM_SYMLINK");
+    version_dirs(root)
+        .into_iter()
+        .filter(|(name, _)| name.starts_with('v'))
+        .map(|(name, path)| RuntimeVersion {
+            is_default: current
+                .as_deref()
+                .is_some_and(|link| links_into(link, &path)),
+            ..RuntimeVersion::new("nvm", "node", &name, &path)
+        })
+        .collect()
+}
+
+fn fnm_versions(root: &Path) -> Vec<RuntimeVersion> {
+    let default = root.join("aliases/default");
+    version_dirs(&root.join("node-versions"))
+        .into_iter()
+        .map(|(name, path)| RuntimeVersion {
+            is_default: links_into(&default, &path),
+            ..RuntimeVersion::new("fnm", "node", &name, &path)
+        })
+        .collect()
+}
+
+fn volta_versions(root: &Path) -> Vec<RuntimeVersion> {
+    let platform: serde_json::Value = fs::read_to_string(root.join("tools/user/platform.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+    let default_of = |tool: &str| -> Option<String> {
+        let value = match tool {
+            "node" => &platform["node"]["runtime"],
+            "npm" => &platform["node"]["npm"],
+            _ => &platform[tool],
+        };
+        value.as_str().map(str::to_string)
+    };
+
+    ["node", "npm", "yarn", "pnpm"]
+        .iter()
+        .flat_map(|tool| {
+            let default = default_of(tool);
+            version_dirs(&root.join("tools/image").join(tool))
+                .into_iter()
+                .map(move |(name, path)| RuntimeVersion {
+                    is_default: default.as_deref() == Some(name.as_str()),
+                    ..RuntimeVersion::new("volta", tool, &name, &path)
+                })
+        })
+        .collect()
+}
+
+/// `tool version...` lines of a `.tool-versions` file
+fn read_tool_versions(path: &Path) -> Vec<(String, String)> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(|line| {
+            let mut words = line.split_whitespace();
+            let tool = words.next().unwrap_or_default().to_string();
+            words
+                .map(move |version| (tool.clone(), version.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// `[tools]` of mise's global config: `node = "20"`, `python = ["3.12", "3.11"]` or `{ version = "..." }`
+fn read_mise_config(path: &Path) -> Vec<(String, String)> {
+    let Some(config) = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| contents.parse::<toml::Table>().ok())
+    else {
+        return Vec::new();
+    };
+    let Some(tools) = config.get("tools").and_then(toml::Value::as_table) else {
+        return Vec::new();
+    };
+
+    let mut defaults = Vec::new();
+    for (tool, value) in tools {
+        let requests = match value {
+            toml::Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        };
+        for request in requests {
+            let version = request
+                .as_str()
+                .or_else(|| request.get("version").and_then(toml::Value::as_str));
+            if let Some(version) = version {
+                defaults.push((tool.clone(), version.to_string()));
+            }
+        }
+    }
+    defaults
+}
+
+/// mise and asdf both keep `installs/<tool>/<version>`
+fn plugin_versions(
+    manager: &str,
+    root: &Path,
+    defaults: &[(String, String)],
+) -> Vec<RuntimeVersion> {
+    version_dirs(&root.join("installs"))
+        .into_iter()
+        .flat_map(|(tool, tool_dir)| {
+            let mut versions: Vec<RuntimeVersion> = version_dirs(&tool_dir)
+                .into_iter()
+                .map(|(name, path)| RuntimeVersion::new(manager, &tool, &name, &path))
+                .collect();
+            for (_, request) in defaults.iter().filter(|(name, _)| *name == tool) {
+                mark_requested(&mut versions, request);
+            }
+            versions
+        })
+        .collect()
+}
+
+fn pyenv_versions(root: &Path) -> Vec<RuntimeVersion> {
+    let defaults: Vec<String> = read_trimmed(&root.join("version"))
+        .map(|contents| {
+            contents
+                .lines()
+                .map(|line| line.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    version_dirs(&root.join("versions"))
+        .into_iter()
+        .map(|(name, path)| RuntimeVersion {
+            is_default: defaults.contains(&name),
+            ..RuntimeVersion::new("pyenv", "python", &name, &path)
+        })
+        .collect()
+}
+
+fn sdkman_versions(root: &Path) -> Vec<RuntimeVersion> {
+    version_dirs(&root.join("candidates"))
+        .into_iter()
+        .flat_map(|(candidate, candidate_dir)| {
+            let current = candidate_dir.join("current");
+            version_dirs(&candidate_dir)
+                .into_iter()
+                .map(move |(name, path)| RuntimeVersion {
+                    is_default: links_into(&current, &path),
+                    ..RuntimeVersion::new("sdkman", &candidate, &name, &path)
+                })
+        })
+        .collect()
+}
+
+fn rustup_versions(root: &Path) -> Vec<RuntimeVersion> {
+    let default = fs::read_to_string(root.join("settings.toml"))
+        .ok()
+        .and_then(|contents| contents.parse::<toml::Table>().ok())
+        .and_then(|settings| {
+            settings
+                .get("default_toolchain")
+                .and_then(toml::Value::as_str)
+                .map(str::to_string)
+        });
+    version_dirs(&root.join("toolchains"))
+        .into_iter()
+        .map(|(name, path)| RuntimeVersion {
+            // `stable` selects `stable-x86_64-unknown-linux-gnu`
+            is_default: default.as_deref().is_some_and(|default| {
+                name == default || name.starts_with(&format!("{}-", default))
+            }),
+            ..RuntimeVersion::new("rustup", "rust", &name, &path)
+        })
+        .collect()
+}
+
+fn with_sizes(mut versions: Vec<RuntimeVersion>) -> Vec<RuntimeVersion> {
+    let index = SizeIndex::open();
+    versions.par_iter_mut().for_each(|version| {
+        let usage = index.disk_usage(Path::new(&version.path));
+        version.size = usage.apparent;
+        version.size_display = format_size(usage.apparent);
+        version.allocated_size = usage.allocated;
+    });
+    // The index only speeds up the next scan; the sizes above stand either way
+    let _ = index.save();
+    versions
+}
+
+/// Every runtime version kept by a version manager, without sizes
+pub fn installed_runtime_versions() -> Vec<RuntimeVersion> {
+    collect_versions(&ManagerRoots::detect())
+}
+
+/// Every runtime version kept by a version manager, with its size
+pub fn scan_runtime_versions() -> Vec<RuntimeVersion> {
+    with_sizes(installed_runtime_versions())
+}
+
+/// The installed version at `path`, refusing anything else and the manager's default
+fn resolve_runtime_version(
+    roots: &ManagerRoots,
+    path: &str,
+) -> Result<(PathBuf, RuntimeVersion), DevJanitorError> {
+    let requested = Path::new(path);
+    let canonical = fs::canonicalize(requested).map_err(|error| {
+        DevJanitorError::io(format!("Cannot resolve {}", requested.display()), error)
+    })?;
+    let version = collect_versions(roots)
+        .into_iter()
+        .find(|version| fs::canonicalize(&version.path).is_ok_and(|known| known == canonical))
+        .ok_or_else(|| {
+            DevJanitorError::NotATarget(format!(
+                "{} is not a runtime version installed by a version manager",
+                path
+            ))
+        })?;
+
+    if version.is_default {
+        return Err(DevJanitorError::NotATarget(format!(
+            "{} {} is the {} default; choose another default before removing it",
+            version.runtime, version.version, version.manager
+        )));
+    }
+    Ok((canonical, version))
+}
+
+/// Report what `remove_runtime_version` would remove without touching it
+pub fn plan_remove_runtime_version(path: &str) -> Result<PlannedRemoval, DevJanitorError> {
+    let (canonical, _) = resolve_runtime_version(&ManagerRoots::detect(), path)?;
+    Ok(PlannedRemoval::new(&canonical, path_size(&canonical)))
+}
+
+/// Remove an installed runtime version by moving it into quarantine (or deleting it, per settings)
+pub fn remove_runtime_version(path: &str) -> Result<OperationResult, DevJanitorError> {
+    remove_version_in(&ManagerRoots::detect(), path)
+}
+
+fn remove_version_in(roots: &ManagerRoots, path: &str) -> Result<OperationResult, DevJanitorError> {
+    let (canonical, version) = resolve_runtime_version(roots, path)?;
+    let size = path_size(&canonical);
+
+    let result = remove_cleanup_target(&canonical, "runtime", size);
+    let journaled = journal::record_cleanup("remove_runtime_version", &canonical, size, &result);
+    let entry = result?;
+
+    Ok(OperationResult::cleaned(
+        path,
+        size,
+        entry.is_some(),
+        format!(
+            "Removed {} {} from {} ({}, {})",
+            version.runtime,
+            version.version,
+            version.manager,
+            format_size(size),
+            removal_description(&entry)
+        ),
+    )
+    .journaled(journaled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_home(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("dev-janitor-runtimes-{name}-{nanos}"));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn install(dir: &Path) {
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::write(dir.join("bin/runtime"), vec![0u8; 2048]).unwrap();
+    }
+
+    fn find<'a>(
+        versions: &'a [RuntimeVersion],
+        manager: &str,
+        version: &str,
+    ) -> &'a RuntimeVersion {
+        versions
+            .iter()
+            .find(|found| found.manager == manager && found.version == version)
+            .unwrap()
+    }
+
+    #[test]
+    fn resolves_nvm_alias_chains() {
+        let home = temp_home("nvm");
+        for version in ["v18.19.0", "v20.10.0", "v20.11.1"] {
+            install(&home.join("versions/node").join(version));
+        }
+        fs::create_dir_all(home.join("alias/lts")).unwrap();
+        fs::write(home.join("alias/default"), "lts/*\n").unwrap();
+        fs::write(home.join("alias/lts/*"), "lts/iron\n").unwrap();
+        fs::write(home.join("alias/lts/iron"), "v20\n").unwrap();
+
+        let versions = nvm_versions(&home);
+
+        assert_eq!(versions.len(), 3);
+        assert!(find(&versions, "nvm", "v20.11.1").is_default);
+        assert!(!find(&versions, "nvm", "v20.10.0").is_default);
+        assert_eq!(versions[0].runtime, "node");
+        fs::remove_dir_all(home).unwrap();
+    }
+
+    #[test]
+    fn reads_defaults_of_plugin_managers() {
+        let home = temp_home("plugins");
+        for version in ["3.11.9", "3.12.4"] {
+            install(&home.join("mise/installs/python").join(version));
+            install(&home.join("pyenv/versions").join(version));
+        }
+        install(&home.join("rustup/toolchains/stable-x86_64-unknown-linux-gnu"));
+        install(&home.join("rustup/toolchains/nightly-x86_64-unknown-linux-gnu"));
+        fs::write(home.join("config.toml"), "[tools]\npython = [\"3.11\"]\n").unwrap();
+        fs::write(home.join("pyenv/version"), "3.12.4\n").unwrap();
+        fs::write(
+            home.join("rustup/settings.toml"),
+            "version = \"12\"\ndefault_toolchain = \"stable\"\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("3.12.4", home.join("mise/installs/python/3.12")).unwrap();
+
+        let roots = ManagerRoots {
+            mise: Some(home.join("mise")),
+            mise_config: Some(home.join("config.toml")),
+            pyenv: Some(home.join("pyenv")),
+            rustup: Some(home.join("rustup")),
+            ..ManagerRoots::default()
+        };
+        let versions = collect_versions(&roots);
+
+        assert_eq!(
+            versions.len(),
+            6,
+            "alias symlinks are not separate versions"
+        );
+        assert!(find(&versions, "mise", "3.11.9").is_default);
+        assert!(!find(&versions, "mise", "3.12.4").is_default);
+        assert!(find(&versions, "pyenv", "3.12.4").is_default);
+        assert!(find(&versions, "rustup", "stable-x86_64-unknown-linux-gnu").is_default);
+        assert!(!find(&versions, "rustup", "nightly-x86_64-unknown-linux-gnu").is_default);
+        fs::remove_dir_all(home).unwrap();
+    }
+
+    #[test]
+    fn sizes_versions() {
+        let home = temp_home("sizes");
+        install(&home.join("versions/3.12.4"));
+
+        let versions = with_sizes(pyenv_versions(&home));
+
+        assert_eq!(versions[0].size, 2048);
+        assert_eq!(versions[0].size_display, "2.00 KB");
+        fs::remove_dir_all(home).unwrap();
+    }
+
+    #[test]
+    fn removes_only_non_default_versions() {
+        let home = temp_home("remove");
+        install(&home.join("versions/3.11.9"));
+        install(&home.join("versions/3.12.4"));
+        fs::write(home.join("version"), "3.12.4\n").unwrap();
+        let roots = ManagerRoots {
+            pyenv: Some(home.clone()),
+            ..ManagerRoots::default()
+        };
+
+        let default = home.join("versions/3.12.4");
+        let error = remove_version_in(&roots, &default.to_string_lossy()).unwrap_err();
+        assert_eq!(error.code(), "not_a_target");
+        let error = remove_version_in(&roots, &home.to_string_lossy()).unwrap_err();
+        assert_eq!(error.code(), "not_a_target");
+
+        let old = home.join("versions/3.11.9");
+        let result = remove_version_in(&roots, &old.to_string_lossy()).unwrap();
+        assert_eq!(result.bytes_quarantined, 2048);
+        assert_eq!(result.bytes_freed, 0);
+        assert!(!old.exists());
+        assert!(default.exists());
+        fs::remove_dir_all(home).unwrap();
+    }
+}
@@ -0,0 +1,419 @@
+//! Runtime versions pinned by projects
+//! Reads `.nvmrc`, `.node-version`, `.python-version`, `.tool-versions`, `mise.toml`,
+//! `rust-toolchain(.toml)`, `go.mod` and `package.json` engines, and reconciles them with
+//! the versions installed by version managers or found on PATH
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::{read_mise_config, read_tool_versions, scan_runtime_versions, RuntimeVersion};
+use crate::detection::{scan_all_tools, ToolInfo};
+use crate::error::DevJanitorError;
+use crate::lifecycle::{self, canonical_runtime, Lifecycle, SupportStatus};
+use crate::settings::{active_settings, scan_each_root};
+use crate::utils::version::{compare_versions, requested_release, satisfies_version_request};
+
+/// Directories that never hold a project's own version files
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "venv",
+    "__pycache__",
+    "dist",
+    "build",
+];
+
+/// A runtime version a project asks for in one of its version files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionPin {
+    /// Directory holding the version file
+    pub project: String,
+    pub file: String,
+    pub runtime: String,
+    /// Version or range as written, e.g. `20`, `3.12.1`, `>=18 <21` or `stable`
+    pub request: String,
+    /// Newest installed version that satisfies the request
+    pub installed: Option<String>,
+    /// Lifecycle of the pinned release line, for requests that name one
+    pub lifecycle: Option<Lifecycle>,
+}
+
+/// Pinned versions reconciled with the installed versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinReport {
+    pub pins: Vec<VersionPin>,
+    /// Installed versions no scanned project pins, apart from each manager's default
+    pub unused: Vec<RuntimeVersion>,
+    /// Pins that no installed version satisfies
+    pub missing: Vec<VersionPin>,
+    /// Pins of a release line past its end of life
+    pub end_of_life: Vec<VersionPin>,
+}
+
+fn pin(dir: &Path, file: &Path, runtime: &str, request: &str) -> VersionPin {
+    let runtime = canonical_runtime(runtime);
+    VersionPin {
+        project: dir.to_string_lossy().to_string(),
+        file: file.to_string_lossy().to_string(),
+        runtime: runtime.to_string(),
+        request: request.to_string(),
+        installed: None,
+        lifecycle: requested_release(request)
+            .and_then(|release| lifecycle::assess(runtime, release)),
+    }
+}
+
+/// First line of a single-version file such as `.nvmrc`
+fn first_line(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// `channel` of `rust-toolchain.toml`, or the whole legacy `rust-toolchain` file
+fn rust_toolchain(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    match contents.parse::<toml::Table>() {
+        Ok(table) => table
+            .get("toolchain")?
+            .get("channel")?
+            .as_str()
+            .map(str::to_string),
+        Err(_) => first_line(path),
+    }
+}
+
+/// The `go` directive of `go.mod`
+fn go_directive(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    contents.lines().find_map(|line| {
+        let version = line.trim().strip_prefix("go ")?;
+        Some(version.trim().to_string())
+    })
+}
+
+/// `engines.node` of `package.json`
+fn node_engine(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    manifest["engines"]["node"].as_str().map(str::to_string)
+}
+
+/// Every pin declared by the version files directly inside `dir`
+fn pins_in(dir: &Path) -> Vec<VersionPin> {
+    let mut pins = Vec::new();
+    let mut push = |file: &str, runtime: &str, request: Option<String>| {
+        if let Some(request) = request.filter(|request| !request.is_empty()) {
+            pins.push(pin(dir, &dir.join(file), runtime, &request));
+        }
+    };
+
+    for file in [".nvmrc", ".node-version"] {
+        push(file, "node", first_line(&dir.join(file)));
+    }
+    push("go.mod", "go", go_directive(&dir.join("go.mod")));
+    push(
+        "package.json",
+        "node",
+        node_engine(&dir.join("package.json")),
+    );
+    for file in ["rust-toolchain.toml", "rust-toolchain"] {
+        push(file, "rust", rust_toolchain(&dir.join(file)));
+    }
+
+    // These may list several versions; each one is a pin
+    let python_versions = dir.join(".python-version");
+    if let Ok(contents) = fs::read_to_string(&python_versions) {
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            pins.push(pin(dir, &python_versions, "python", line));
+        }
+    }
+    let tool_versions = dir.join(".tool-versions");
+    for (tool, version) in read_tool_versions(&tool_versions) {
+        pins.push(pin(dir, &tool_versions, &tool, &version));
+    }
+    for file in ["mise.toml", ".mise.toml"] {
+        let config = dir.join(file);
+        for (tool, version) in read_mise_config(&config) {
+            pins.push(pin(dir, &config, &tool, &version));
+        }
+    }
+    pins
+}
+
+/// Version pins of the projects below `root_path`
+pub fn scan_version_pins(root_path: &str, max_depth: usize) -> Vec<VersionPin> {
+    let exclusions = active_settings().exclusions();
+    let mut pins = Vec::new();
+
+    let mut entries = WalkDir::new(root_path).max_depth(max_depth).into_iter();
+    while let Some(entry) = entries.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy();
+        let skipped =
+            entry.depth() > 0 && (name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()));
+        if skipped || exclusions.is_excluded(entry.path()) {
+            entries.skip_current_dir();
+            continue;
+        }
+        pins.extend(pins_in(entry.path()));
+    }
+    pins
+}
+
+/// Whether an installed version serves a pin; channels such as `stable` or `nightly` match
+/// rustup toolchains by name
+fn serves(installed: &RuntimeVersion, pin: &VersionPin) -> bool {
+    canonical_runtime(&installed.runtime) == pin.runtime
+        && (satisfies_version_request(&installed.version, &pin.request)
+            || installed.version == pin.request
+            || installed
+                .version
+                .strip_prefix(pin.request.as_str())
+                .is_some_and(|rest| rest.starts_with('-')))
+}
+
+/// Cross-reference pins with the versions installed by version managers and the tools
+/// detected on PATH. Detected tools (distribution packages, Homebrew, official installers) can
+/// satisfy a pin but are never reported as unused.
+pub fn reconcile_version_pins(
+    mut pins: Vec<VersionPin>,
+    installed: Vec<RuntimeVersion>,
+    detected: &[ToolInfo],
+) -> PinReport {
+    let system: Vec<RuntimeVersion> = detected
+        .iter()
+        .flat_map(|tool| {
+            tool.versions.iter().map(|version| {
+                RuntimeVersion::new(
+                    version.source.as_str(),
+                    &tool.id,
+                    &version.version,
+                    Path::new(&version.path),
+                )
+            })
+        })
+        .collect();
+
+    let mut used = vec![false; installed.len()];
+    for pin in &mut pins {
+        for (index, version) in installed.iter().enumerate() {
+            if serves(version, pin) {
+                used[index] = true;
+            }
+        }
+        pin.installed = installed
+            .iter()
+            .chain(&system)
+            .filter(|version| serves(version, pin))
+            .max_by(|a, b| compare_versions(&a.version, &b.version))
+            .map(|version| version.version.clone());
+    }
+
+    // Aliases such as `lts/*`, `latest` or `system` cannot be missing
+    let missing = pins
+        .iter()
+        .filter(|pin| pin.installed.is_none())
+        .filter(|pin| {
+            pin.request
+                .trim_start_matches(['^', '~', '=', '>', '<', 'v'])
+                .starts_with(|c: char| c.is_ascii_digit())
+        })
+        .cloned()
+        .collect();
+    let end_of_life = pins
+        .iter()
+        .filter(|pin| {
+            pin.lifecycle
+                .as_ref()
+                .is_some_and(|lifecycle| lifecycle.status == SupportStatus::Eol)
+        })
+        .cloned()
+        .collect();
+    let unused = installed
+        .into_iter()
+        .zip(used)
+        .filter(|(version, used)| !used && !version.is_default)
+        .map(|(version, _)| version)
+        .collect();
+
+    PinReport {
+        pins,
+        unused,
+        missing,
+        end_of_life,
+    }
+}
+
+/// Reconcile the pins of every project in `path` (or the configured scan roots)
+pub fn version_pin_report(
+    path: Option<&str>,
+    max_depth: Option<usize>,
+) -> Result<PinReport, DevJanitorError> {
+    let pins = scan_each_root(path, max_depth, scan_version_pins)?;
+    Ok(reconcile_version_pins(
+        pins,
+        scan_runtime_versions(),
+        &scan_all_tools(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detection::source::InstallSource;
+    use crate::detection::ToolVersion;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("dev-janitor-pins-{name}-{nanos}"));
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_every_kind_of_version_file() {
+        let root = temp_dir("scan");
+        let (web, api, tool) = (root.join("web"), root.join("api"), root.join("tool"));
+        for dir in [&web, &api, &tool, &web.join("node_modules/dep")] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(web.join(".nvmrc"), "v16.20.2\n").unwrap();
+        fs::write(
+            web.join("package.json"),
+            r#"{"name": "web", "engines": {"node": ">=16 <19"}}"#,
+        )
+        .unwrap();
+        fs::write(web.join("node_modules/dep/.nvmrc"), "12\n").unwrap();
+        fs::write(
+            api.join(".tool-versions"),
+            "python 3.12.1 3.8.18\ngolang 1.22.0\n",
+        )
+        .unwrap();
+        fs::write(api.join("go.mod"), "module example.com/api\n\ngo 1.22\n").unwrap();
+        fs::write(
+            tool.join("rust-toolchain.toml"),
+            "[toolchain]\nchannel = \"1.80.0\"\n",
+        )
+        .unwrap();
+
+        let mut pins = scan_version_pins(&root.to_string_lossy(), 3);
+        pins.sort_by(|a, b| (&a.runtime, &a.request).cmp(&(&b.runtime, &b.request)));
+        let found: Vec<(&str, &str)> = pins
+            .iter()
+            .map(|pin| (pin.runtime.as_str(), pin.request.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("go", "1.22"),
+                ("go", "1.22.0"),
+                ("node", ">=16 <19"),
+                ("node", "v16.20.2"),
+                ("python", "3.12.1"),
+                ("python", "3.8.18"),
+                ("rust", "1.80.0"),
+            ]
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn reports_unused_missing_and_end_of_life_versions() {
+        let project = Path::new("/work/web");
+        let installed = |runtime: &str, version: &str, is_default: bool| RuntimeVersion {
+            is_default,
+            ..RuntimeVersion::new("asdf", runtime, version, &project.join(version))
+        };
+        let pins = vec![
+            pin(project, &project.join(".nvmrc"), "node", "16"),
+            pin(project, &project.join(".python-version"), "python", "3.13"),
+            pin(project, &project.join("rust-toolchain"), "rust", "nightly"),
+        ];
+        let report = reconcile_version_pins(
+            pins,
+            vec![
+                installed("nodejs", "16.20.2", false),
+                installed("nodejs", "20.11.1", true),
+                installed("python", "3.11.7", false),
+                installed("rust", "nightly-x86_64-unknown-linux-gnu", false),
+            ],
+            &[],
+        );
+
+        assert_eq!(report.pins[0].installed.as_deref(), Some("16.20.2"));
+        let unused: Vec<&str> = report.unused.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(unused, ["3.11.7"]);
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].request, "3.13");
+        assert_eq!(report.end_of_life.len(), 1);
+        assert_eq!(report.end_of_life[0].runtime, "node");
+    }
+
+    #[test]
+    fn system_installs_satisfy_pins() {
+        let project = Path::new("/work/api");
+        let detected = |id: &str, version: &str, path: &str| ToolInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            category: "runtime".to_string(),
+            versions: vec![ToolVersion {
+                version: version.to_string(),
+                path: path.to_string(),
+                is_active: true,
+                real_path: path.to_string(),
+                shadowed_by: None,
+                lifecycle: None,
+                source: InstallSource::Apt,
+                source_package: None,
+            }],
+            status: "installed".to_string(),
+            health: Vec::new(),
+        };
+        let pins = vec![
+            pin(project, &project.join("go.mod"), "go", "1.22"),
+            pin(project, &project.join("package.json"), "node", ">=18"),
+            pin(project, &project.join(".python-version"), "python", "3.13"),
+        ];
+
+        let report = reconcile_version_pins(
+            pins,
+            Vec::new(),
+            &[
+                detected("go", "1.22.5", "/usr/local/go/bin/go"),
+                detected("node", "20.11.1", "/usr/bin/node"),
+                detected("python", "3.11.2", "/usr/bin/python3"),
+            ],
+        );
+
+        assert_eq!(report.pins[0].installed.as_deref(), Some("1.22.5"));
+        assert_eq!(report.pins[1].installed.as_deref(), Some("20.11.1"));
+        let missing: Vec<&str> = report
+            .missing
+            .iter()
+            .map(|pin| pin.request.as_str())
+            .collect();
+        assert_eq!(missing, ["3.13"]);
+        assert!(report.unused.is_empty());
+    }
+}
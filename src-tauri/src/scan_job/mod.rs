@@ -0,0 +1,377 @@
+//! Cancellable scan jobs with progress reporting
+//! Long directory walks report what they have visited and stop early when cancelled
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::ai_cleanup::{scan_ai_junk_with_progress, AiJunkFile};
+use crate::cache::{scan_project_caches_with_progress, CacheInfo};
+use crate::chat_history::{scan_chat_history_with_progress, ProjectChatHistory};
+use crate::detection::{scan_all_tools_with_progress, ToolInfo};
+use crate::error::DevJanitorError;
+use crate::settings::active_settings;
+
+/// Minimum time between two progress callbacks of one job
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Cancellation tokens of running jobs, by job id
+static RUNNING_JOBS: Mutex<Option<HashMap<String, CancellationToken>>> = Mutex::new(None);
+
+/// Shared flag a scan checks between directory entries
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Snapshot of a running scan
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScanProgress {
+    pub job_id: String,
+    /// project_caches, ai_junk, chat_history or tools
+    pub kind: String,
+    pub directories_visited: u64,
+    /// Directory examined most recently (the tool id for tool scans)
+    pub current_path: Option<String>,
+    pub items_found: u64,
+    /// Total size of the items found so far
+    pub bytes_counted: u64,
+    /// Set on the last snapshot of a job
+    pub finished: bool,
+}
+
+type ProgressCallback = Box<dyn Fn(ScanProgress) + Send + Sync>;
+
+/// Progress counters and cancellation for one scan, shared across worker threads
+pub struct ScanReporter {
+    job_id: String,
+    kind: String,
+    token: CancellationToken,
+    directories_visited: AtomicU64,
+    items_found: AtomicU64,
+    bytes_counted: AtomicU64,
+    current_path: Mutex<Option<String>>,
+    last_report: Mutex<Option<Instant>>,
+    callback: Option<ProgressCallback>,
+}
+
+impl ScanReporter {
+    /// Reporter for plain scans: never cancelled, reports nothing
+    pub fn silent() -> Self {
+        Self::build("", "", CancellationToken::default(), None)
+    }
+
+    pub fn new(
+        job_id: &str,
+        kind: &str,
+        token: CancellationToken,
+        callback: impl Fn(ScanProgress) + Send + Sync + 'static,
+    ) -> Self {
+        Self::build(job_id, kind, token, Some(Box::new(callback)))
+    }
+
+    fn build(
+        job_id: &str,
+        kind: &str,
+        token: CancellationToken,
+        callback: Option<ProgressCallback>,
+    ) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            kind: kind.to_string(),
+            token,
+            directories_visited: AtomicU64::new(0),
+            items_found: AtomicU64::new(0),
+            bytes_counted: AtomicU64::new(0),
+            current_path: Mutex::new(None),
+            last_report: Mutex::new(None),
+            callback,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Count a directory the walk entered
+    pub fn visit_dir(&self, path: &Path) {
+        self.directories_visited.fetch_add(1, Ordering::Relaxed);
+        if self.callback.is_some() {
+            self.set_current(path.to_string_lossy().to_string());
+        }
+    }
+
+    /// Record the item being examined by a scan that does not walk directories
+    pub fn visit(&self, label: &str) {
+        if self.callback.is_some() {
+            self.set_current(label.to_string());
+        }
+    }
+
+    /// Count an item the scan will report
+    pub fn found(&self, bytes: u64) {
+        self.items_found.fetch_add(1, Ordering::Relaxed);
+        self.bytes_counted.fetch_add(bytes, Ordering::Relaxed);
+        self.report();
+    }
+
+    pub fn snapshot(&self, finished: bool) -> ScanProgress {
+        ScanProgress {
+            job_id: self.job_id.clone(),
+            kind: self.kind.clone(),
+            directories_visited: self.directories_visited.load(Ordering::Relaxed),
+            current_path: lock(&self.current_path).clone(),
+            items_found: self.items_found.load(Ordering::Relaxed),
+            bytes_counted: self.bytes_counted.load(Ordering::Relaxed),
+            finished,
+        }
+    }
+
+    /// Send the final snapshot regardless of throttling
+    pub fn finish(&self) -> ScanProgress {
+        let progress = self.snapshot(true);
+        if let Some(callback) = &self.callback {
+            callback(progress.clone());
+        }
+        progress
+    }
+
+    fn set_current(&self, current: String) {
+        *lock(&self.current_path) = Some(current);
+        self.report();
+    }
+
+    fn report(&self) {
+        let Some(callback) = &self.callback else {
+            return;
+        };
+
+        {
+            let mut last_report = lock(&self.last_report);
+            let now = Instant::now();
+            let due = last_report.is_none_or(|last| now.duration_since(last) >= PROGRESS_INTERVAL);
+            if !due {
+                return;
+            }
+            *last_report = Some(now);
+        }
+        callback(self.snapshot(false));
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Items found by a scan job, tagged with the scan kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "items", rename_all = "snake_case")]
+pub enum ScanJobResult {
+    ProjectCaches(Vec<CacheInfo>),
+    AiJunk(Vec<AiJunkFile>),
+    ChatHistory(Vec<ProjectChatHistory>),
+    Tools(Vec<ToolInfo>),
+}
+
+/// Result of a finished or cancelled scan job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanJobOutcome {
+    pub job_id: String,
+    /// The job was cancelled; `result` holds what was found until then
+    pub cancelled: bool,
+    pub result: ScanJobResult,
+    pub progress: ScanProgress,
+}
+
+/// Removes a job from the registry when it ends, even by panic
+struct RegisteredJob<'a>(&'a str);
+
+impl Drop for RegisteredJob<'_> {
+    fn drop(&mut self) {
+        if let Some(jobs) = lock(&RUNNING_JOBS).as_mut() {
+            jobs.remove(self.0);
+        }
+    }
+}
+
+fn register_job(job_id: &str) -> Result<(CancellationToken, RegisteredJob<'_>), DevJanitorError> {
+    let mut jobs = lock(&RUNNING_JOBS);
+    let jobs = jobs.get_or_insert_with(HashMap::new);
+    if jobs.contains_key(job_id) {
+        return Err(DevJanitorError::AlreadyExists(format!(
+            "Scan job is already running: {}",
+            job_id
+        )));
+    }
+
+    let token = CancellationToken::default();
+    jobs.insert(job_id.to_string(), token.clone());
+    Ok((token, RegisteredJob(job_id)))
+}
+
+/// Ask a running scan job to stop; returns false when no such job is running
+pub fn cancel_scan_job(job_id: &str) -> bool {
+    match lock(&RUNNING_JOBS)
+        .as_ref()
+        .and_then(|jobs| jobs.get(job_id))
+    {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Run a scan under `job_id`, calling `on_progress` as it advances
+///
+/// `kind` is one of project_caches, ai_junk, chat_history or tools. Every kind
+/// except tools scans `path`, or each configured scan root when it is `None`;
+/// `max_depth` falls back to the configured default.
+pub fn run_scan_job(
+    job_id: &str,
+    kind: &str,
+    path: Option<&str>,
+    max_depth: Option<usize>,
+    on_progress: impl Fn(ScanProgress) + Send + Sync + 'static,
+) -> Result<ScanJobOutcome, DevJanitorError> {
+    if !matches!(
+        kind,
+        "project_caches" | "ai_junk" | "chat_history" | "tools"
+    ) {
+        return Err(DevJanitorError::InvalidInput(format!(
+            "Unknown scan kind: {}",
+            kind
+        )));
+    }
+    let settings = active_settings();
+    let roots = if kind == "tools" {
+        Vec::new()
+    } else {
+        settings.scan_roots(path)?
+    };
+    let depth = settings.scan_depth(max_depth);
+
+    let (token, _registered) = register_job(job_id)?;
+    let reporter = ScanReporter::new(job_id, kind, token, on_progress);
+
+    let result = match kind {
+        "project_caches" => ScanJobResult::ProjectCaches(
+            roots
+                .iter()
+                .flat_map(|root| scan_project_caches_with_progress(root, depth, &reporter))
+                .collect(),
+        ),
+        "ai_junk" => ScanJobResult::AiJunk(
+            roots
+                .iter()
+                .flat_map(|root| scan_ai_junk_with_progress(root, depth, &reporter))
+                .collect(),
+        ),
+        "chat_history" => ScanJobResult::ChatHistory(
+            roots
+                .iter()
+                .flat_map(|root| scan_chat_history_with_progress(root, depth, &reporter))
+                .collect(),
+        ),
+        _ => ScanJobResult::Tools(scan_all_tools_with_progress(&reporter)),
+    };
+
+    Ok(ScanJobOutcome {
+        job_id: job_id.to_string(),
+        cancelled: reporter.is_cancelled(),
+        result,
+        progress: reporter.finish(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_project(name: &str) -> std::path::PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("dev-janitor-scan-job-{name}-{nanos}"));
+        fs::create_dir_all(dir.join("target/debug")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"fixture\"\n").unwrap();
+        fs::write(
+            dir.join("target/debug/payload.bin"),
+            vec![b'x'; 2 * 1024 * 1024],
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn reports_progress_and_final_counts() {
+        let project = temp_project("progress");
+        let job_id = format!("progress-{}", project.display());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+
+        let outcome = run_scan_job(
+            &job_id,
+            "project_caches",
+            project.to_str(),
+            Some(4),
+            move |progress| lock(&recorded).push(progress),
+        )
+        .unwrap();
+
+        assert!(!outcome.cancelled);
+        let ScanJobResult::ProjectCaches(caches) = outcome.result else {
+            panic!("expected project caches");
+        };
+        assert_eq!(caches.len(), 1);
+        assert_eq!(outcome.progress.items_found, 1);
+        assert!(outcome.progress.bytes_counted >= 2 * 1024 * 1024);
+        assert!(outcome.progress.directories_visited >= 1);
+
+        let events = lock(&events);
+        assert!(events.last().unwrap().finished);
+        assert!(!cancel_scan_job(&job_id));
+
+        fs::remove_dir_all(project).unwrap();
+    }
+
+    #[test]
+    fn cancelled_scans_stop_early() {
+        let project = temp_project("cancel");
+        let token = CancellationToken::default();
+        token.cancel();
+        let reporter = ScanReporter::new("cancel", "project_caches", token, |_| {});
+
+        let caches = scan_project_caches_with_progress(project.to_str().unwrap(), 4, &reporter);
+
+        assert!(caches.is_empty());
+        assert_eq!(reporter.snapshot(false).directories_visited, 0);
+        fs::remove_dir_all(project).unwrap();
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        let error = run_scan_job("bad-kind", "disk", Some("/tmp"), None, |_| {}).unwrap_err();
+        assert_eq!(error.code(), "invalid_input");
+
+        let error = run_scan_job("no-path", "ai_junk", None, None, |_| {}).unwrap_err();
+        assert_eq!(error.code(), "invalid_input");
+    }
+}
@@ -7,6 +7,8 @@ mod definitions;
 pub mod scanner;
 
 #[cfg(feature = "desktop")]
-pub use definitions::{get_rules, SecurityScanResult};
+pub use definitions::get_rules;
+pub use definitions::{SecurityFinding, SecurityScanResult};
+pub use scanner::scan_ai_tool_security;
 #[cfg(feature = "desktop")]
-pub use scanner::{scan_ai_tool_security, scan_specific_tool};
+pub use scanner::scan_specific_tool;
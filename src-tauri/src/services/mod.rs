@@ -5,7 +5,12 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Reverse;
 use sysinfo::{Pid, ProcessStatus, System};
 
-use crate::utils::command::command_output_with_timeout;
+use crate::disk_usage::format_size;
+use crate::error::DevJanitorError;
+use crate::journal::{self, JournalEntry};
+use crate::operation::OperationResult;
+use crate::rules;
+use crate::utils::runner::{active_runner, CommandRunner};
 use std::time::Duration;
 
 /// Represents a running process
@@ -131,34 +136,32 @@ const DEV_PROCESS_PATTERNS: &[(&str, &str)] = &[
     ("astro", "Dev Server"),
 ];
 
-/// Format bytes to human readable
-fn format_memory(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
+/// Built-in development process name patterns, which user rules can disable
+pub(crate) fn builtin_dev_process_patterns() -> Vec<&'static str> {
+    DEV_PROCESS_PATTERNS
+        .iter()
+        .map(|(pattern, _)| *pattern)
+        .collect()
 }
 
-/// Get process category based on name
+/// Get process category based on name, with user rules applied
 fn get_process_category(name: &str) -> Option<String> {
     let name_lower = name.to_lowercase();
 
+    let rules = rules::active_rules();
+    let overlay = &rules.dev_processes;
+
     for (pattern, category) in DEV_PROCESS_PATTERNS {
-        if name_lower.contains(pattern) {
+        if name_lower.contains(pattern) && !overlay.is_disabled(pattern) {
             return Some(category.to_string());
         }
     }
 
-    None
+    overlay
+        .added
+        .iter()
+        .find(|rule| rule.pattern.matches_name(&name_lower))
+        .map(|rule| rule.label.clone())
 }
 
 /// Get all running development-related processes
@@ -194,7 +197,7 @@ pub fn get_dev_processes() -> Vec<ProcessInfo> {
                     name,
                     exe_path,
                     memory,
-                    memory_display: format_memory(memory),
+                    memory_display: format_size(memory),
                     cpu: process.cpu_usage(),
                     status: status.to_string(),
                     category,
@@ -242,7 +245,7 @@ pub fn get_all_processes() -> Vec<ProcessInfo> {
                 name,
                 exe_path,
                 memory,
-                memory_display: format_memory(memory),
+                memory_display: format_size(memory),
                 cpu: process.cpu_usage(),
                 status: status.to_string(),
                 category,
@@ -256,7 +259,7 @@ pub fn get_all_processes() -> Vec<ProcessInfo> {
 }
 
 /// Kill a process by PID
-pub fn kill_process(pid: u32) -> Result<String, String> {
+pub fn kill_process(pid: u32) -> Result<OperationResult, DevJanitorError> {
     let mut sys = System::new_all();
     sys.refresh_all();
 
@@ -264,39 +267,48 @@ pub fn kill_process(pid: u32) -> Result<String, String> {
 
     if let Some(process) = sys.process(pid_obj) {
         let name = process.name().to_string_lossy().to_string();
+        let target = format!("{} (PID: {})", name, pid);
 
-        if process.kill() {
-            Ok(format!(
-                "Successfully terminated process: {} (PID: {})",
-                name, pid
+        let result = if process.kill() {
+            Ok(OperationResult::new(
+                &target,
+                format!("Successfully terminated process: {}", target),
             ))
         } else {
-            Err(format!(
-                "Failed to terminate process: {} (PID: {})",
-                name, pid
-            ))
-        }
+            Err(DevJanitorError::Failed(format!(
+                "Failed to terminate process: {}",
+                target
+            )))
+        };
+        let journaled =
+            journal::record(&JournalEntry::from_result("kill_process", &target, &result));
+        result.map(|result| result.journaled(journaled))
     } else {
-        Err(format!("Process not found: PID {}", pid))
+        Err(DevJanitorError::NotFound(format!(
+            "Process not found: PID {}",
+            pid
+        )))
     }
 }
 
 /// Get ports in use (using netstat on Windows, ss/lsof on Unix)
 pub fn get_ports_in_use() -> Vec<PortInfo> {
+    let runner = active_runner();
+
     #[cfg(target_os = "windows")]
     {
-        get_ports_windows()
+        get_ports_windows(runner.as_ref())
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        get_ports_unix()
+        get_ports_unix(runner.as_ref())
     }
 }
 
 #[cfg(target_os = "windows")]
-fn get_ports_windows() -> Vec<PortInfo> {
-    let output = command_output_with_timeout("netstat", &["-ano"], Duration::from_secs(5));
+fn get_ports_windows(runner: &dyn CommandRunner) -> Vec<PortInfo> {
+    let output = runner.output("netstat", &["-ano"], Duration::from_secs(5));
 
     let output = match output {
         Ok(o) => o,
@@ -357,21 +369,18 @@ fn get_ports_windows() -> Vec<PortInfo> {
 }
 
 #[cfg(not(target_os = "windows"))]
-fn get_ports_unix() -> Vec<PortInfo> {
+fn get_ports_unix(runner: &dyn CommandRunner) -> Vec<PortInfo> {
     // Try ss first, then lsof
-    let output = command_output_with_timeout("ss", &["-tulpn"], Duration::from_secs(5));
+    let output = runner.output("ss", &["-tulpn"], Duration::from_secs(5));
 
     let (stdout, used_lsof) = match output {
         Ok(o) if o.status.success() => (o.stdout, false),
         _ => {
-            let lsof_output = match command_output_with_timeout(
-                "lsof",
-                &["-i", "-P", "-n"],
-                Duration::from_secs(5),
-            ) {
-                Ok(o) => o,
-                Err(_) => return Vec::new(),
-            };
+            let lsof_output =
+                match runner.output("lsof", &["-i", "-P", "-n"], Duration::from_secs(5)) {
+                    Ok(o) => o,
+                    Err(_) => return Vec::new(),
+                };
             (lsof_output.stdout, true)
         }
     };
@@ -491,3 +500,33 @@ pub fn get_common_dev_ports() -> Vec<PortInfo> {
         .filter(|p| common_ports.contains(&p.port))
         .collect()
 }
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod tests {
+    use super::*;
+    use crate::utils::runner::ReplayRunner;
+
+    #[test]
+    fn parses_recorded_ss_output() {
+        let fixture = include_str!("../../fixtures/commands/ss.jsonl");
+        let runner = ReplayRunner::parse(fixture, "ss.jsonl").unwrap();
+
+        let ports = get_ports_unix(&runner);
+
+        let summary: Vec<(u16, &str, u32, &str)> = ports
+            .iter()
+            .map(|p| (p.port, p.process_name.as_str(), p.pid, p.state.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (22, "sshd", 1020, "LISTEN"),
+                (53, "systemd-resolve", 612, "UNCONN"),
+                (5173, "node", 48213, "LISTEN"),
+                (18789, "openclaw", 9120, "LISTEN"),
+            ]
+        );
+        assert_eq!(ports[3].local_address, "0.0.0.0:18789");
+        assert_eq!(ports[1].protocol, "UDP");
+    }
+}
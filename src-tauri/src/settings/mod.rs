@@ -0,0 +1,400 @@
+//! Persistent user settings
+//! Default scan roots, excluded paths, depth limits, size thresholds, deletion preference
+//! and whether uninstalls may ask for root
+
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::error::DevJanitorError;
+use crate::retention::RetentionPolicy;
+use crate::utils::paths;
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Highest depth any scan may be configured to walk
+pub const SCAN_DEPTH_LIMIT: usize = 64;
+
+/// Values accepted for `Settings::deletion_mode`
+pub const DELETION_MODES: &[&str] = &["quarantine", "delete"];
+
+/// Settings in effect, loaded on first use
+static ACTIVE_SETTINGS: Mutex<Option<Arc<Settings>>> = Mutex::new(None);
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: !cfg!(any(target_os = "windows", target_os = "macos")),
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// User settings stored as settings.json in the config directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct Settings {
+    /// Directories scanned when a scan is started without a path; `~/` is expanded
+    pub scan_roots: Vec<String>,
+    /// Globs of paths every scanner skips. Globs without `/` match a file or
+    /// directory name, others the whole path (`~/` is expanded, `**` crosses directories).
+    pub excluded_paths: Vec<String>,
+    /// Depth used when a scan does not ask for one
+    pub default_scan_depth: usize,
+    /// Requested depths are clamped to this
+    pub max_scan_depth: usize,
+    /// Project caches must be larger than this many bytes to be reported
+    pub min_project_cache_size: u64,
+    /// AI junk smaller than this many bytes is not reported
+    pub min_ai_junk_size: u64,
+    /// Chat history files smaller than this many bytes are not reported
+    pub min_chat_history_size: u64,
+    /// quarantine (recoverable) or delete (permanent)
+    pub deletion_mode: String,
+    /// Policies applied by retention runs
+    pub retention_policies: Vec<RetentionPolicy>,
+    /// Hours between scheduled retention runs; 0 turns the schedule off
+    pub retention_interval_hours: u32,
+    /// Let tool uninstall remove distribution packages through pkexec or `sudo -n`
+    pub allow_privileged_uninstall: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            scan_roots: Vec::new(),
+            excluded_paths: Vec::new(),
+            default_scan_depth: 5,
+            max_scan_depth: 20,
+            min_project_cache_size: 1024 * 1024,
+            min_ai_junk_size: 0,
+            min_chat_history_size: 0,
+            deletion_mode: "quarantine".to_string(),
+            retention_policies: Vec::new(),
+            retention_interval_hours: 0,
+            allow_privileged_uninstall: false,
+        }
+    }
+}
+
+/// Compiled `excluded_paths`
+#[derive(Debug, Clone, Default)]
+pub struct Exclusions {
+    names: Vec<Pattern>,
+    paths: Vec<Pattern>,
+}
+
+impl Exclusions {
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        let name_excluded = path.file_name().is_some_and(|name| {
+            let name = name.to_string_lossy();
+            self.names
+                .iter()
+                .any(|pattern| pattern.matches_with(&name, MATCH_OPTIONS))
+        });
+
+        name_excluded
+            || self
+                .paths
+                .iter()
+                .any(|pattern| pattern.matches_path_with(path, MATCH_OPTIONS))
+    }
+}
+
+impl Settings {
+    /// Check every field, naming the first one that is wrong
+    pub fn validate(&self) -> Result<(), DevJanitorError> {
+        if !(1..=SCAN_DEPTH_LIMIT).contains(&self.max_scan_depth) {
+            return Err(DevJanitorError::InvalidInput(format!(
+                "max_scan_depth must be between 1 and {}",
+                SCAN_DEPTH_LIMIT
+            )));
+        }
+        if !(1..=self.max_scan_depth).contains(&self.default_scan_depth) {
+            return Err(DevJanitorError::InvalidInput(
+                "default_scan_depth must be between 1 and max_scan_depth".to_string(),
+            ));
+        }
+        if !DELETION_MODES.contains(&self.deletion_mode.as_str()) {
+            return Err(DevJanitorError::InvalidInput(format!(
+                "deletion_mode must be one of {}, not `{}`",
+                DELETION_MODES.join(", "),
+                self.deletion_mode
+            )));
+        }
+
+        for (index, root) in self.scan_roots.iter().enumerate() {
+            let expanded = expand_home(root);
+            if !Path::new(&expanded).is_absolute() {
+                return Err(DevJanitorError::InvalidInput(format!(
+                    "scan_roots[{}]: `{}` must be absolute or start with ~/",
+                    index, root
+                )));
+            }
+        }
+        for (index, glob) in self.excluded_paths.iter().enumerate() {
+            compile_exclusion(glob).map_err(|detail| {
+                DevJanitorError::InvalidInput(format!("excluded_paths[{}]: {}", index, detail))
+            })?;
+        }
+        for (index, policy) in self.retention_policies.iter().enumerate() {
+            policy.validate(index)?;
+            if self.retention_policies[..index]
+                .iter()
+                .any(|other| other.id == policy.id)
+            {
+                return Err(DevJanitorError::InvalidInput(format!(
+                    "retention_policies[{}]: duplicate id `{}`",
+                    index, policy.id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn quarantines_deletions(&self) -> bool {
+        self.deletion_mode != "delete"
+    }
+
+    /// Depth for a scan: the requested one or the default, clamped to the maximum
+    pub fn scan_depth(&self, requested: Option<usize>) -> usize {
+        requested
+            .unwrap_or(self.default_scan_depth)
+            .min(self.max_scan_depth)
+    }
+
+    /// The requested path, or every configured scan root when none was given
+    pub fn scan_roots(&self, requested: Option<&str>) -> Result<Vec<String>, DevJanitorError> {
+        if let Some(path) = requested.filter(|path| !path.is_empty()) {
+            return Ok(vec![path.to_string()]);
+        }
+        if self.scan_roots.is_empty() {
+            return Err(DevJanitorError::InvalidInput(
+                "No path given and no default scan roots are configured".to_string(),
+            ));
+        }
+        Ok(self
+            .scan_roots
+            .iter()
+            .map(|root| expand_home(root))
+            .collect())
+    }
+
+    /// Compile `excluded_paths`, skipping any that do not validate
+    pub fn exclusions(&self) -> Exclusions {
+        let mut exclusions = Exclusions::default();
+        for glob in &self.excluded_paths {
+            match compile_exclusion(glob) {
+                Ok(pattern) if glob.contains('/') => exclusions.paths.push(pattern),
+                Ok(pattern) => exclusions.names.push(pattern),
+                Err(_) => {}
+            }
+        }
+        exclusions
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), paths::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+fn compile_exclusion(glob: &str) -> Result<Pattern, String> {
+    let expanded = expand_home(glob.trim());
+    if expanded.is_empty() {
+        return Err("pattern is empty".to_string());
+    }
+    Pattern::new(&expanded).map_err(|error| format!("invalid glob `{}`: {}", glob, error.msg))
+}
+
+/// Run `scan` over the requested path, or over every configured scan root
+pub fn scan_each_root<T>(
+    path: Option<&str>,
+    max_depth: Option<usize>,
+    mut scan: impl FnMut(&str, usize) -> Vec<T>,
+) -> Result<Vec<T>, DevJanitorError> {
+    let settings = active_settings();
+    let depth = settings.scan_depth(max_depth);
+    Ok(settings
+        .scan_roots(path)?
+        .iter()
+        .flat_map(|root| scan(root, depth))
+        .collect())
+}
+
+fn settings_path() -> Result<PathBuf, DevJanitorError> {
+    paths::config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE_NAME))
+        .ok_or_else(|| {
+            DevJanitorError::NotFound("Could not determine the config directory".to_string())
+        })
+}
+
+/// Read and validate a settings file; a missing file yields the defaults
+fn read_settings_file(path: &Path) -> Result<Settings, DevJanitorError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Settings::default())
+        }
+        Err(error) => {
+            return Err(DevJanitorError::io(
+                format!("Failed to read {}", path.display()),
+                error,
+            ))
+        }
+    };
+
+    let settings: Settings = serde_json::from_str(&contents)
+        .map_err(|error| DevJanitorError::ParseError(format!("{}: {}", path.display(), error)))?;
+    settings
+        .validate()
+        .map_err(|error| DevJanitorError::InvalidInput(format!("{}: {}", path.display(), error)))?;
+    Ok(settings)
+}
+
+/// Write settings through a temporary file so a crash never leaves half a file
+fn write_settings_file(path: &Path, settings: &Settings) -> Result<(), DevJanitorError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| {
+            DevJanitorError::io(format!("Failed to create {}", parent.display()), error)
+        })?;
+    }
+
+    let contents = serde_json::to_string_pretty(settings)
+        .map_err(|error| DevJanitorError::Internal(error.to_string()))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, contents)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|error| {
+            let _ = fs::remove_file(&temp_path);
+            DevJanitorError::io(format!("Failed to write {}", path.display()), error)
+        })
+}
+
+/// Read the settings file
+pub fn load_settings() -> Result<Settings, DevJanitorError> {
+    read_settings_file(&settings_path()?)
+}
+
+/// Validate and store settings, applying them to later scans and cleanups
+pub fn save_settings(settings: Settings) -> Result<Settings, DevJanitorError> {
+    settings.validate()?;
+    write_settings_file(&settings_path()?, &settings)?;
+    *ACTIVE_SETTINGS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(settings.clone()));
+    Ok(settings)
+}
+
+/// Settings in effect; a broken settings file leaves the defaults, and
+/// `load_settings` reports what is wrong with it
+pub fn active_settings() -> Arc<Settings> {
+    let mut active = ACTIVE_SETTINGS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    active
+        .get_or_insert_with(|| Arc::new(load_settings().unwrap_or_default()))
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_settings_path(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("dev-janitor-settings-{name}-{nanos}"))
+            .join(SETTINGS_FILE_NAME)
+    }
+
+    #[test]
+    fn round_trips_and_fills_missing_fields() {
+        let path = temp_settings_path("round-trip");
+        assert_eq!(read_settings_file(&path).unwrap(), Settings::default());
+
+        let settings = Settings {
+            scan_roots: vec!["/srv/code".to_string()],
+            deletion_mode: "delete".to_string(),
+            ..Settings::default()
+        };
+        write_settings_file(&path, &settings).unwrap();
+        assert_eq!(read_settings_file(&path).unwrap(), settings);
+
+        fs::write(&path, r#"{"default_scan_depth": 3}"#).unwrap();
+        let partial = read_settings_file(&path).unwrap();
+        assert_eq!(partial.default_scan_depth, 3);
+        assert_eq!(partial.min_project_cache_size, 1024 * 1024);
+        assert!(partial.quarantines_deletions());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        let bad_glob = Settings {
+            excluded_paths: vec!["/srv/**/ok".to_string(), "/srv/[".to_string()],
+            ..Settings::default()
+        };
+        let error = bad_glob.validate().unwrap_err();
+        assert_eq!(error.code(), "invalid_input");
+        assert!(error.to_string().contains("excluded_paths[1]"));
+
+        let too_deep = Settings {
+            default_scan_depth: 30,
+            ..Settings::default()
+        };
+        assert!(too_deep.validate().is_err());
+
+        let unknown_mode = Settings {
+            deletion_mode: "shred".to_string(),
+            ..Settings::default()
+        };
+        assert!(unknown_mode.validate().is_err());
+
+        let relative_root = Settings {
+            scan_roots: vec!["code".to_string()],
+            ..Settings::default()
+        };
+        assert!(relative_root.validate().is_err());
+    }
+
+    #[test]
+    fn resolves_roots_and_depths() {
+        let settings = Settings {
+            scan_roots: vec!["/srv/a".to_string(), "/srv/b".to_string()],
+            default_scan_depth: 4,
+            max_scan_depth: 10,
+            ..Settings::default()
+        };
+
+        assert_eq!(settings.scan_depth(None), 4);
+        assert_eq!(settings.scan_depth(Some(99)), 10);
+        assert_eq!(settings.scan_roots(Some("/tmp")).unwrap(), vec!["/tmp"]);
+        assert_eq!(settings.scan_roots(None).unwrap(), vec!["/srv/a", "/srv/b"]);
+        assert_eq!(
+            Settings::default().scan_roots(None).unwrap_err().code(),
+            "invalid_input"
+        );
+    }
+
+    #[test]
+    fn excludes_names_and_paths() {
+        let exclusions = Settings {
+            excluded_paths: vec!["archive".to_string(), "/srv/**/legacy".to_string()],
+            ..Settings::default()
+        }
+        .exclusions();
+
+        assert!(exclusions.is_excluded(Path::new("/home/dev/archive")));
+        assert!(exclusions.is_excluded(Path::new("/srv/team/app/legacy")));
+        assert!(!exclusions.is_excluded(Path::new("/srv/team/app/current")));
+        assert!(!exclusions.is_excluded(Path::new("/home/dev/archive-2024")));
+    }
+}
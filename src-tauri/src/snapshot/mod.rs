@@ -0,0 +1,582 @@
+//! Timestamped inventory snapshots and the diff between two of them
+//! Answers "what changed on this machine since it last worked"
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::detection::ToolVersion;
+use crate::disk_usage::format_size;
+use crate::error::DevJanitorError;
+use crate::report::{collect_inventory, InventoryReport, REPORT_SCHEMA_VERSION};
+use crate::security_scan::SecurityFinding;
+use crate::utils::paths;
+use crate::utils::version::compare_versions;
+
+const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+
+/// A stored snapshot, without its contents
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub generated_at: String,
+    pub hostname: Option<String>,
+    pub tools: usize,
+    pub packages: usize,
+    pub path: String,
+}
+
+impl SnapshotInfo {
+    fn new(id: &str, path: &Path, report: &InventoryReport) -> Self {
+        Self {
+            id: id.to_string(),
+            generated_at: report.generated_at.clone(),
+            hostname: report.host.hostname.clone(),
+            tools: report.tools.len(),
+            packages: report.packages.len(),
+            path: path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// A tool present in only one of the two snapshots
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolPresenceChange {
+    pub id: String,
+    pub name: String,
+    pub versions: Vec<String>,
+}
+
+/// Installations of one tool that appeared or disappeared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolVersionChange {
+    pub id: String,
+    pub name: String,
+    pub added: Vec<ToolVersion>,
+    pub removed: Vec<ToolVersion>,
+    pub active_before: Option<String>,
+    pub active_after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PackageChange {
+    pub manager: String,
+    pub name: String,
+    /// Version in the older snapshot, if the package was there
+    pub from: Option<String>,
+    /// Version in the newer snapshot, if the package is still there
+    pub to: Option<String>,
+    pub latest: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CacheGrowth {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub size_before: u64,
+    pub size_after: u64,
+    pub growth_display: String,
+}
+
+/// What changed between two snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryDiff {
+    pub from_generated_at: String,
+    pub to_generated_at: String,
+    pub tools_added: Vec<ToolPresenceChange>,
+    pub tools_removed: Vec<ToolPresenceChange>,
+    pub tool_version_changes: Vec<ToolVersionChange>,
+    pub packages_added: Vec<PackageChange>,
+    pub packages_removed: Vec<PackageChange>,
+    pub packages_upgraded: Vec<PackageChange>,
+    pub packages_downgraded: Vec<PackageChange>,
+    /// Packages that were up to date and now have a newer release
+    pub packages_newly_outdated: Vec<PackageChange>,
+    pub caches_grown: Vec<CacheGrowth>,
+    pub new_security_findings: Vec<SecurityFinding>,
+    pub resolved_security_findings: Vec<SecurityFinding>,
+}
+
+impl InventoryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.tools_added.is_empty()
+            && self.tools_removed.is_empty()
+            && self.tool_version_changes.is_empty()
+            && self.packages_added.is_empty()
+            && self.packages_removed.is_empty()
+            && self.packages_upgraded.is_empty()
+            && self.packages_downgraded.is_empty()
+            && self.packages_newly_outdated.is_empty()
+            && self.caches_grown.is_empty()
+            && self.new_security_findings.is_empty()
+            && self.resolved_security_findings.is_empty()
+    }
+}
+
+fn snapshots_dir() -> Result<PathBuf, DevJanitorError> {
+    paths::data_dir()
+        .map(|dir| dir.join(SNAPSHOTS_DIR_NAME))
+        .ok_or_else(|| DevJanitorError::NotFound("Could not determine the data directory".into()))
+}
+
+/// Snapshot ids are file stems, so they may not name anything outside the directory
+fn snapshot_path(id: &str) -> Result<PathBuf, DevJanitorError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(DevJanitorError::InvalidInput(format!(
+            "Invalid snapshot id: {}",
+            id
+        )));
+    }
+    Ok(snapshots_dir()?.join(format!("{}.json", id)))
+}
+
+/// Collect the inventory now and store it as a new snapshot
+pub fn take_snapshot() -> Result<SnapshotInfo, DevJanitorError> {
+    save_snapshot(&collect_inventory())
+}
+
+pub fn save_snapshot(report: &InventoryReport) -> Result<SnapshotInfo, DevJanitorError> {
+    let dir = snapshots_dir()?;
+    fs::create_dir_all(&dir).map_err(|error| {
+        DevJanitorError::io(format!("Failed to create {}", dir.display()), error)
+    })?;
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut id = stamp.clone();
+    let mut suffix = 1;
+    while dir.join(format!("{}.json", id)).exists() {
+        suffix += 1;
+        id = format!("{}-{}", stamp, suffix);
+    }
+
+    let path = dir.join(format!("{}.json", id));
+    let contents = serde_json::to_string_pretty(report)
+        .map_err(|error| DevJanitorError::Internal(error.to_string()))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, contents)
+        .and_then(|_| fs::rename(&temp_path, &path))
+        .map_err(|error| {
+            let _ = fs::remove_file(&temp_path);
+            DevJanitorError::io(format!("Failed to write {}", path.display()), error)
+        })?;
+
+    Ok(SnapshotInfo::new(&id, &path, report))
+}
+
+fn read_report(path: &Path) -> Result<InventoryReport, DevJanitorError> {
+    let contents = fs::read_to_string(path).map_err(|error| {
+        if error.kind() == ErrorKind::NotFound {
+            DevJanitorError::NotFound(format!("No snapshot at {}", path.display()))
+        } else {
+            DevJanitorError::io(format!("Failed to read {}", path.display()), error)
+        }
+    })?;
+    let report: InventoryReport = serde_json::from_str(&contents)
+        .map_err(|error| DevJanitorError::ParseError(format!("{}: {}", path.display(), error)))?;
+    if report.schema_version > REPORT_SCHEMA_VERSION {
+        return Err(DevJanitorError::ParseError(format!(
+            "{} uses report schema {}, newer than the supported {}",
+            path.display(),
+            report.schema_version,
+            REPORT_SCHEMA_VERSION
+        )));
+    }
+    Ok(report)
+}
+
+/// Load a snapshot by id, or an exported JSON report by file path
+pub fn load_snapshot(id_or_path: &str) -> Result<InventoryReport, DevJanitorError> {
+    if id_or_path.ends_with(".json") || id_or_path.contains(std::path::MAIN_SEPARATOR) {
+        return read_report(Path::new(id_or_path));
+    }
+    read_report(&snapshot_path(id_or_path)?)
+}
+
+/// Stored snapshots, newest first
+pub fn list_snapshots() -> Result<Vec<SnapshotInfo>, DevJanitorError> {
+    let dir = snapshots_dir()?;
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(DevJanitorError::io(
+                format!("Failed to read {}", dir.display()),
+                error,
+            ))
+        }
+    };
+
+    let mut snapshots = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        // A damaged snapshot should not hide the others
+        if let Ok(report) = read_report(&path) {
+            snapshots.push(SnapshotInfo::new(id, &path, &report));
+        }
+    }
+    snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(snapshots)
+}
+
+pub fn delete_snapshot(id: &str) -> Result<(), DevJanitorError> {
+    let path = snapshot_path(id)?;
+    fs::remove_file(&path).map_err(|error| {
+        if error.kind() == ErrorKind::NotFound {
+            DevJanitorError::NotFound(format!("No snapshot named {}", id))
+        } else {
+            DevJanitorError::io(format!("Failed to delete {}", path.display()), error)
+        }
+    })
+}
+
+/// Diff two snapshots (ids or report file paths); `to` defaults to the current machine
+pub fn diff_snapshots(from: &str, to: Option<&str>) -> Result<InventoryDiff, DevJanitorError> {
+    let old = load_snapshot(from)?;
+    let new = match to {
+        Some(to) => load_snapshot(to)?,
+        None => collect_inventory(),
+    };
+    Ok(diff_reports(&old, &new))
+}
+
+pub fn diff_reports(old: &InventoryReport, new: &InventoryReport) -> InventoryDiff {
+    let mut diff = InventoryDiff {
+        from_generated_at: old.generated_at.clone(),
+        to_generated_at: new.generated_at.clone(),
+        tools_added: Vec::new(),
+        tools_removed: Vec::new(),
+        tool_version_changes: Vec::new(),
+        packages_added: Vec::new(),
+        packages_removed: Vec::new(),
+        packages_upgraded: Vec::new(),
+        packages_downgraded: Vec::new(),
+        packages_newly_outdated: Vec::new(),
+        caches_grown: Vec::new(),
+        new_security_findings: Vec::new(),
+        resolved_security_findings: Vec::new(),
+    };
+    diff_tools(old, new, &mut diff);
+    diff_packages(old, new, &mut diff);
+    diff_caches(old, new, &mut diff);
+    diff_security(old, new, &mut diff);
+    diff
+}
+
+fn diff_tools(old: &InventoryReport, new: &InventoryReport, diff: &mut InventoryDiff) {
+    let presence = |tool: &crate::detection::ToolInfo| ToolPresenceChange {
+        id: tool.id.clone(),
+        name: tool.name.clone(),
+        versions: tool.versions.iter().map(|v| v.version.clone()).collect(),
+    };
+    let active = |versions: &[ToolVersion]| {
+        versions
+            .iter()
+            .find(|version| version.is_active)
+            .map(|version| version.version.clone())
+    };
+
+    let old_tools: HashMap<&str, _> = old.tools.iter().map(|t| (t.id.as_str(), t)).collect();
+    let new_ids: HashSet<&str> = new.tools.iter().map(|t| t.id.as_str()).collect();
+
+    for tool in &new.tools {
+        let Some(before) = old_tools.get(tool.id.as_str()) else {
+            diff.tools_added.push(presence(tool));
+            continue;
+        };
+        let key = |version: &ToolVersion| (version.version.clone(), version.path.clone());
+        let old_keys: HashSet<_> = before.versions.iter().map(key).collect();
+        let new_keys: HashSet<_> = tool.versions.iter().map(key).collect();
+        let added: Vec<ToolVersion> = tool
+            .versions
+            .iter()
+            .filter(|version| !old_keys.contains(&key(version)))
+            .cloned()
+            .collect();
+        let removed: Vec<ToolVersion> = before
+            .versions
+            .iter()
+            .filter(|version| !new_keys.contains(&key(version)))
+            .cloned()
+            .collect();
+        let active_before = active(&before.versions);
+        let active_after = active(&tool.versions);
+
+        if !added.is_empty() || !removed.is_empty() || active_before != active_after {
+            diff.tool_version_changes.push(ToolVersionChange {
+                id: tool.id.clone(),
+                name: tool.name.clone(),
+                added,
+                removed,
+                active_before,
+                active_after,
+            });
+        }
+    }
+
+    diff.tools_removed = old
+        .tools
+        .iter()
+        .filter(|tool| !new_ids.contains(tool.id.as_str()))
+        .map(presence)
+        .collect();
+}
+
+fn diff_packages(old: &InventoryReport, new: &InventoryReport, diff: &mut InventoryDiff) {
+    let key = |package: &crate::package_manager::PackageInfo| {
+        (package.manager.clone(), package.name.clone())
+    };
+    let old_packages: HashMap<_, _> = old.packages.iter().map(|p| (key(p), p)).collect();
+    let new_keys: HashSet<_> = new.packages.iter().map(key).collect();
+
+    for package in &new.packages {
+        let before = old_packages.get(&key(package));
+        let change = PackageChange {
+            manager: package.manager.clone(),
+            name: package.name.clone(),
+            from: before.map(|p| p.version.clone()),
+            to: Some(package.version.clone()),
+            latest: package.latest.clone(),
+        };
+        let Some(before) = before else {
+            diff.packages_added.push(change);
+            continue;
+        };
+
+        if package.is_outdated && !before.is_outdated {
+            diff.packages_newly_outdated.push(change.clone());
+        }
+        match compare_versions(&before.version, &package.version) {
+            Ordering::Less => diff.packages_upgraded.push(change),
+            Ordering::Greater => diff.packages_downgraded.push(change),
+            Ordering::Equal => {}
+        }
+    }
+
+    diff.packages_removed = old
+        .packages
+        .iter()
+        .filter(|package| !new_keys.contains(&key(package)))
+        .map(|package| PackageChange {
+            manager: package.manager.clone(),
+            name: package.name.clone(),
+            from: Some(package.version.clone()),
+            to: None,
+            latest: package.latest.clone(),
+        })
+        .collect();
+}
+
+fn diff_caches(old: &InventoryReport, new: &InventoryReport, diff: &mut InventoryDiff) {
+    let old_sizes: HashMap<&str, u64> = old
+        .caches
+        .iter()
+        .map(|cache| (cache.path.as_str(), cache.size))
+        .collect();
+
+    for cache in &new.caches {
+        let size_before = old_sizes.get(cache.path.as_str()).copied().unwrap_or(0);
+        if cache.size > size_before {
+            diff.caches_grown.push(CacheGrowth {
+                id: cache.id.clone(),
+                name: cache.name.clone(),
+                path: cache.path.clone(),
+                size_before,
+                size_after: cache.size,
+                growth_display: format_size(cache.size - size_before),
+            });
+        }
+    }
+    diff.caches_grown
+        .sort_by_key(|cache| std::cmp::Reverse(cache.size_after - cache.size_before));
+}
+
+fn diff_security(old: &InventoryReport, new: &InventoryReport, diff: &mut InventoryDiff) {
+    let key = |finding: &SecurityFinding| {
+        (
+            finding.tool_id.clone(),
+            finding.issue.clone(),
+            finding.details.clone(),
+        )
+    };
+    let old_keys: HashSet<_> = old.security.findings.iter().map(key).collect();
+    let new_keys: HashSet<_> = new.security.findings.iter().map(key).collect();
+
+    diff.new_security_findings = new
+        .security
+        .findings
+        .iter()
+        .filter(|finding| !old_keys.contains(&key(finding)))
+        .cloned()
+        .collect();
+    diff.resolved_security_findings = old
+        .security
+        .findings
+        .iter()
+        .filter(|finding| !new_keys.contains(&key(finding)))
+        .cloned()
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn report(generated_at: &str, tools: Value, packages: Value, caches: Value) -> InventoryReport {
+        serde_json::from_value(json!({
+            "schema_version": REPORT_SCHEMA_VERSION,
+            "generated_at": generated_at,
+            "app_version": "0.0.0",
+            "host": {"hostname": "build-01", "os": "linux", "os_version": null, "arch": "x86_64"},
+            "tools": tools,
+            "packages": packages,
+            "caches": caches,
+            "ai_cli_tools": [],
+            "environment": {"path_entries": [], "shell_configs": [], "issues": [], "suggestions": []},
+            "security": {
+                "scan_time": generated_at,
+                "tools_scanned": [],
+                "findings": [],
+                "summary": {"total_findings": 0, "critical": 0, "high": 0, "medium": 0, "low": 0}
+            }
+        }))
+        .unwrap()
+    }
+
+    fn tool(id: &str, versions: &[(&str, &str, bool)]) -> Value {
+        let versions: Vec<Value> = versions
+            .iter()
+            .map(|(version, path, is_active)| {
+                json!({"version": version, "path": path, "is_active": is_active})
+            })
+            .collect();
+        json!({"id": id, "name": id, "category": "runtime", "versions": versions, "status": "installed"})
+    }
+
+    fn package(name: &str, version: &str, is_outdated: bool) -> Value {
+        json!({
+            "name": name, "version": version, "latest": "9.0.0", "manager": "npm",
+            "is_outdated": is_outdated, "description": null
+        })
+    }
+
+    fn cache(path: &str, size: u64) -> Value {
+        json!({
+            "id": path, "name": path, "path": path, "size": size,
+            "size_display": format_size(size), "cache_type": "package_manager"
+        })
+    }
+
+    #[test]
+    fn reports_tool_package_and_cache_changes() {
+        let old = report(
+            "2026-10-17T09:00:00+00:00",
+            json!([
+                tool("node", &[("18.20.0", "/usr/bin/node", true)]),
+                tool("go", &[("1.22.0", "/usr/bin/go", true)])
+            ]),
+            json!([
+                package("typescript", "5.4.0", false),
+                package("eslint", "9.1.0", false),
+                package("left-pad", "1.3.0", false)
+            ]),
+            json!([cache("/cache/npm", 100), cache("/cache/pip", 500)]),
+        );
+        let new = report(
+            "2026-10-18T09:00:00+00:00",
+            json!([
+                tool(
+                    "node",
+                    &[
+                        ("18.20.0", "/usr/bin/node", false),
+                        ("20.11.0", "/opt/node/bin/node", true)
+                    ]
+                ),
+                tool("rust", &[("1.80.0", "/root/.cargo/bin/rustc", true)])
+            ]),
+            json!([
+                package("typescript", "5.10.0", false),
+                package("eslint", "9.1.0", true),
+                package("prettier", "3.3.0", false)
+            ]),
+            json!([cache("/cache/npm", 4096), cache("/cache/pip", 500)]),
+        );
+
+        let diff = diff_reports(&old, &new);
+
+        assert_eq!(diff.tools_added[0].id, "rust");
+        assert_eq!(diff.tools_removed[0].id, "go");
+        let node = &diff.tool_version_changes[0];
+        assert_eq!(node.added[0].version, "20.11.0");
+        assert!(node.removed.is_empty());
+        assert_eq!(node.active_before.as_deref(), Some("18.20.0"));
+        assert_eq!(node.active_after.as_deref(), Some("20.11.0"));
+
+        assert_eq!(diff.packages_upgraded[0].name, "typescript");
+        assert_eq!(diff.packages_upgraded[0].from.as_deref(), Some("5.4.0"));
+        assert_eq!(diff.packages_newly_outdated[0].name, "eslint");
+        assert_eq!(diff.packages_added[0].name, "prettier");
+        assert_eq!(diff.packages_removed[0].name, "left-pad");
+        assert!(diff.packages_downgraded.is_empty());
+
+        assert_eq!(diff.caches_grown.len(), 1);
+        assert_eq!(diff.caches_grown[0].size_after, 4096);
+    }
+
+    #[test]
+    fn reports_new_and_resolved_findings() {
+        let finding = |issue: &str| {
+            serde_json::from_value::<SecurityFinding>(json!({
+                "tool_id": "openclaw", "tool_name": "OpenClaw", "issue": issue,
+                "description": "", "risk_level": "High", "remediation": "", "details": ""
+            }))
+            .unwrap()
+        };
+        let mut old = report("a", json!([]), json!([]), json!([]));
+        let mut new = old.clone();
+        old.security.findings = vec![finding("Token in config")];
+        new.security.findings = vec![finding("Gateway exposed")];
+
+        let diff = diff_reports(&old, &new);
+
+        assert_eq!(diff.new_security_findings[0].issue, "Gateway exposed");
+        assert_eq!(diff.resolved_security_findings[0].issue, "Token in config");
+        assert!(diff_reports(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn stores_lists_and_deletes_snapshots() {
+        let first = save_snapshot(&report("one", json!([]), json!([]), json!([]))).unwrap();
+        let second = save_snapshot(&report("two", json!([]), json!([]), json!([]))).unwrap();
+        assert_ne!(first.id, second.id);
+
+        let listed: Vec<String> = list_snapshots()
+            .unwrap()
+            .into_iter()
+            .map(|snapshot| snapshot.id)
+            .collect();
+        assert!(listed.contains(&first.id) && listed.contains(&second.id));
+        assert_eq!(load_snapshot(&second.id).unwrap().generated_at, "two");
+        assert_eq!(load_snapshot(&second.path).unwrap().generated_at, "two");
+
+        delete_snapshot(&first.id).unwrap();
+        delete_snapshot(&second.id).unwrap();
+        assert_eq!(load_snapshot(&first.id).unwrap_err().code(), "not_found");
+        assert_eq!(load_snapshot("..").unwrap_err().code(), "invalid_input");
+    }
+}
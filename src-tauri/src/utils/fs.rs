@@ -0,0 +1,190 @@
+//! Filesystem helpers shared by the cleanup modules
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Remove a file or directory, retrying once after clearing read-only bits
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    let result = remove_path_once(path);
+    if result.is_ok() {
+        return result;
+    }
+
+    if make_writable(path).is_ok() && remove_path_once(path).is_ok() {
+        return Ok(());
+    }
+
+    result
+}
+
+fn remove_path_once(path: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(unix)]
+fn make_writable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use walkdir::WalkDir;
+
+    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_dir() {
+            let _ = fs::set_permissions(entry.path(), fs::Permissions::from_mode(0o755));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+#[allow(clippy::permissions_set_readonly_false)]
+fn make_writable(path: &Path) -> io::Result<()> {
+    use std::os::windows::fs::MetadataExt;
+    use walkdir::WalkDir;
+
+    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+        if let Ok(metadata) = fs::metadata(entry.path()) {
+            // Clear Windows FILE_ATTRIBUTE_READONLY before deleting.
+            if metadata.file_attributes() & 1 != 0 {
+                let mut perms = metadata.permissions();
+                perms.set_readonly(false);
+                let _ = fs::set_permissions(entry.path(), perms);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn make_writable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Move a file or directory, copying across filesystems when rename cannot
+///
+/// A copy that fails part-way is removed again, leaving `from` untouched.
+pub fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::CrossesDevices => copy_then_remove(from, to),
+        Err(error) => Err(error),
+    }
+}
+
+fn copy_then_remove(from: &Path, to: &Path) -> io::Result<()> {
+    if let Err(error) = copy_tree(from, to) {
+        if fs::symlink_metadata(to).is_ok() {
+            let _ = remove_path(to);
+        }
+        return Err(error);
+    }
+    remove_path(from)
+}
+
+fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+    use walkdir::WalkDir;
+
+    for entry in WalkDir::new(from).follow_links(false) {
+        let entry = entry.map_err(io::Error::other)?;
+        let relative = entry.path().strip_prefix(from).map_err(io::Error::other)?;
+        let target = to.join(relative);
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+// Creating symlinks needs extra privileges on Windows; keep the link target's
+// contents instead so nothing is lost.
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("dev-janitor-fs-{name}-{nanos}"));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn copy_tree_preserves_nested_contents() {
+        let root = temp_dir("copy");
+        let source = root.join("source");
+        fs::create_dir_all(source.join("nested")).unwrap();
+        fs::write(source.join("nested/file.txt"), "payload").unwrap();
+
+        let target = root.join("target");
+        copy_tree(&source, &target).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(target.join("nested/file.txt")).unwrap(),
+            "payload"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_copy_leaves_no_partial_tree() {
+        use std::os::unix::net::UnixListener;
+
+        let root = temp_dir("partial");
+        let source = root.join("source");
+        fs::create_dir_all(source.join("nested")).unwrap();
+        fs::write(source.join("nested/file.txt"), "payload").unwrap();
+        // Sockets cannot be copied, so the copy fails after creating the target
+        let _listener = UnixListener::bind(source.join("socket")).unwrap();
+
+        let target = root.join("target");
+        assert!(copy_then_remove(&source, &target).is_err());
+
+        assert!(!target.exists());
+        assert!(source.join("nested/file.txt").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn remove_path_handles_read_only_directories() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = temp_dir("readonly");
+        let locked = root.join("locked");
+        fs::create_dir_all(&locked).unwrap();
+        fs::write(locked.join("file.txt"), "payload").unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+
+        remove_path(&locked).unwrap();
+
+        assert!(!locked.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1 +1,5 @@
 pub mod command;
+pub mod fs;
+pub mod paths;
+pub mod runner;
+pub mod version;
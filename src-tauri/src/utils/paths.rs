@@ -0,0 +1,81 @@
+//! Per-user directories that Dev Janitor reads and writes
+
+use std::path::PathBuf;
+
+const APP_DIR_NAME: &str = "dev-janitor";
+
+/// Path from an environment variable, ignoring it when unset or empty
+pub(crate) fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+pub fn home_dir() -> Option<PathBuf> {
+    env_path("HOME").or_else(|| env_path("USERPROFILE"))
+}
+
+/// Base directory for per-user application data (before the app folder)
+#[cfg(target_os = "windows")]
+fn platform_data_base() -> Option<PathBuf> {
+    env_path("LOCALAPPDATA").or_else(|| home_dir().map(|home| home.join("AppData/Local")))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_data_base() -> Option<PathBuf> {
+    home_dir().map(|home| home.join("Library/Application Support"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_data_base() -> Option<PathBuf> {
+    env_path("XDG_DATA_HOME").or_else(|| home_dir().map(|home| home.join(".local/share")))
+}
+
+/// Base directory for per-user configuration (before the app folder)
+#[cfg(target_os = "windows")]
+fn platform_config_base() -> Option<PathBuf> {
+    env_path("APPDATA").or_else(|| home_dir().map(|home| home.join("AppData/Roaming")))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_config_base() -> Option<PathBuf> {
+    home_dir().map(|home| home.join("Library/Application Support"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_config_base() -> Option<PathBuf> {
+    env_path("XDG_CONFIG_HOME").or_else(|| home_dir().map(|home| home.join(".config")))
+}
+
+/// Shared user data directory, e.g. `~/.local/share` on Linux
+pub fn user_data_base() -> Option<PathBuf> {
+    platform_data_base()
+}
+
+/// Dev Janitor's own data directory (journal, quarantine, indexes)
+#[cfg(not(test))]
+pub fn data_dir() -> Option<PathBuf> {
+    platform_data_base().map(|base| base.join(APP_DIR_NAME))
+}
+
+/// Dev Janitor's own configuration directory
+#[cfg(not(test))]
+pub fn config_dir() -> Option<PathBuf> {
+    platform_config_base().map(|base| base.join(APP_DIR_NAME))
+}
+
+// Unit tests must never touch the developer's real data or settings.
+#[cfg(test)]
+pub fn data_dir() -> Option<PathBuf> {
+    Some(test_root().join("data"))
+}
+
+#[cfg(test)]
+pub fn config_dir() -> Option<PathBuf> {
+    Some(test_root().join("config"))
+}
+
+#[cfg(test)]
+fn test_root() -> PathBuf {
+    std::env::temp_dir().join(format!("{}-test-{}", APP_DIR_NAME, std::process::id()))
+}
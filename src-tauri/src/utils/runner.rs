@@ -0,0 +1,354 @@
+//! Pluggable command execution for scanners
+//! Commands go through a `CommandRunner` so recorded output can stand in for a real machine
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::command::command_output_with_timeout_vec;
+
+static ACTIVE_RUNNER: Mutex<Option<Arc<dyn CommandRunner>>> = Mutex::new(None);
+
+// Tests run in parallel, so a test's runner must not leak into the others
+#[cfg(test)]
+thread_local! {
+    static THREAD_RUNNER: std::cell::RefCell<Option<Arc<dyn CommandRunner>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Runs an external program and captures its output
+pub trait CommandRunner: Send + Sync {
+    fn output_vec(&self, program: &str, args: &[String], timeout: Duration) -> io::Result<Output>;
+
+    fn output(&self, program: &str, args: &[&str], timeout: Duration) -> io::Result<Output> {
+        let owned_args: Vec<String> = args.iter().map(|arg| (*arg).to_string()).collect();
+        self.output_vec(program, &owned_args, timeout)
+    }
+}
+
+/// Spawns real processes
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn output_vec(&self, program: &str, args: &[String], timeout: Duration) -> io::Result<Output> {
+        command_output_with_timeout_vec(program, args, timeout)
+    }
+}
+
+/// One captured invocation, as stored in fixture files
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordedCommand {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Exit code; absent when the process was killed by a signal
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub stdout: String,
+    #[serde(default)]
+    pub stderr: String,
+    /// Set instead of output when the command could not run: not_found, timed_out,
+    /// permission_denied or a free-form message
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl RecordedCommand {
+    fn capture(program: &str, args: &[String], result: &io::Result<Output>) -> Self {
+        let mut recorded = Self {
+            program: program.to_string(),
+            args: args.to_vec(),
+            exit_code: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+        };
+        match result {
+            Ok(output) => {
+                recorded.exit_code = output.status.code();
+                recorded.stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                recorded.stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            }
+            Err(error) => {
+                recorded.error = Some(match error.kind() {
+                    io::ErrorKind::NotFound => "not_found".to_string(),
+                    io::ErrorKind::TimedOut => "timed_out".to_string(),
+                    io::ErrorKind::PermissionDenied => "permission_denied".to_string(),
+                    _ => error.to_string(),
+                });
+            }
+        }
+        recorded
+    }
+
+    fn replay(&self) -> io::Result<Output> {
+        if let Some(error) = &self.error {
+            let kind = match error.as_str() {
+                "not_found" => io::ErrorKind::NotFound,
+                "timed_out" => io::ErrorKind::TimedOut,
+                "permission_denied" => io::ErrorKind::PermissionDenied,
+                _ => io::ErrorKind::Other,
+            };
+            return Err(io::Error::new(kind, error.clone()));
+        }
+        Ok(Output {
+            status: exit_status(self.exit_code.unwrap_or(1)),
+            stdout: self.stdout.clone().into_bytes(),
+            stderr: self.stderr.clone().into_bytes(),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+/// Passes commands through to another runner and keeps what they printed
+pub struct RecordingRunner {
+    inner: Arc<dyn CommandRunner>,
+    recorded: Mutex<Vec<RecordedCommand>>,
+    /// Each command is also appended here as it finishes
+    file: Option<PathBuf>,
+    /// Commands that could not be appended to `file`
+    write_errors: Mutex<Vec<String>>,
+}
+
+impl RecordingRunner {
+    pub fn new(inner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            inner,
+            recorded: Mutex::new(Vec::new()),
+            file: None,
+            write_errors: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record into `path`, which is created now so an unwritable file fails early
+    pub fn to_file(inner: Arc<dyn CommandRunner>, path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|error| with_path(&path, error))?;
+        Ok(Self {
+            file: Some(path),
+            ..Self::new(inner)
+        })
+    }
+
+    pub fn recorded(&self) -> Vec<RecordedCommand> {
+        lock(&self.recorded).clone()
+    }
+
+    /// Commands that ran but could not be written to the recording file
+    pub fn write_errors(&self) -> Vec<String> {
+        lock(&self.write_errors).clone()
+    }
+
+    fn append(path: &Path, recorded: &RecordedCommand) -> io::Result<()> {
+        let mut line = serde_json::to_string(recorded)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(line.as_bytes())
+    }
+}
+
+impl CommandRunner for RecordingRunner {
+    fn output_vec(&self, program: &str, args: &[String], timeout: Duration) -> io::Result<Output> {
+        let result = self.inner.output_vec(program, args, timeout);
+        let recorded = RecordedCommand::capture(program, args, &result);
+        if let Some(path) = &self.file {
+            if let Err(error) = Self::append(path, &recorded) {
+                lock(&self.write_errors).push(format!(
+                    "Failed to record `{} {}` to {}: {}",
+                    program,
+                    args.join(" "),
+                    path.display(),
+                    error
+                ));
+            }
+        }
+        lock(&self.recorded).push(recorded);
+        result
+    }
+}
+
+/// Answers commands from recorded output; anything not recorded behaves like a
+/// program that is not installed
+#[derive(Debug, Clone, Default)]
+pub struct ReplayRunner {
+    commands: Vec<RecordedCommand>,
+}
+
+impl ReplayRunner {
+    pub fn new(commands: Vec<RecordedCommand>) -> Self {
+        Self { commands }
+    }
+
+    /// Parse a JSON Lines fixture, one `RecordedCommand` per line
+    pub fn parse(contents: &str, source: &str) -> io::Result<Self> {
+        let mut commands = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let command = serde_json::from_str(line).map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}:{}: {}", source, index + 1, error),
+                )
+            })?;
+            commands.push(command);
+        }
+        Ok(Self { commands })
+    }
+
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|error| with_path(path, error))?;
+        Self::parse(&contents, &path.to_string_lossy())
+    }
+
+    /// Record successful output for `program args`
+    pub fn with_output(mut self, program: &str, args: &[&str], stdout: &str) -> Self {
+        self.commands.push(RecordedCommand {
+            program: program.to_string(),
+            args: args.iter().map(|arg| (*arg).to_string()).collect(),
+            exit_code: Some(0),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            error: None,
+        });
+        self
+    }
+}
+
+impl CommandRunner for ReplayRunner {
+    fn output_vec(&self, program: &str, args: &[String], _timeout: Duration) -> io::Result<Output> {
+        self.commands
+            .iter()
+            .find(|command| command.program == program && command.args == args)
+            .map(RecordedCommand::replay)
+            .unwrap_or_else(|| {
+                Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("No recorded output for: {} {}", program, args.join(" ")),
+                ))
+            })
+    }
+}
+
+/// The runner scanners use unless they were handed one explicitly
+///
+/// Real processes by default; only an explicit `set_active_runner` (the CLI's
+/// `--record` and `--replay`) changes that.
+pub fn active_runner() -> Arc<dyn CommandRunner> {
+    #[cfg(test)]
+    if let Some(runner) = THREAD_RUNNER.with(|runner| runner.borrow().clone()) {
+        return runner;
+    }
+    Arc::clone(lock(&ACTIVE_RUNNER).get_or_insert_with(|| Arc::new(SystemRunner)))
+}
+
+/// Route every later scan through `runner`
+pub fn set_active_runner(runner: Arc<dyn CommandRunner>) {
+    *lock(&ACTIVE_RUNNER) = Some(runner);
+}
+
+/// Run `f` with `runner` as the active runner of the current thread
+#[cfg(test)]
+pub fn with_thread_runner<T>(runner: Arc<dyn CommandRunner>, f: impl FnOnce() -> T) -> T {
+    let previous = THREAD_RUNNER.with(|active| active.replace(Some(runner)));
+    let result = f();
+    THREAD_RUNNER.with(|active| *active.borrow_mut() = previous);
+    result
+}
+
+fn with_path(path: &Path, error: io::Error) -> io::Error {
+    io::Error::new(error.kind(), format!("{}: {}", path.display(), error))
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn replays_recorded_output_and_exit_codes() {
+        let fixture = concat!(
+            r#"{"program":"npm","args":["--version"],"exit_code":0,"stdout":"10.8.2\n"}"#,
+            "\n",
+            r#"{"program":"npm","args":["outdated","-g"],"exit_code":1,"stdout":"{}"}"#,
+            "\n",
+            r#"{"program":"brew","args":["--version"],"error":"timed_out"}"#,
+            "\n"
+        );
+        let replay = ReplayRunner::parse(fixture, "fixture.jsonl").unwrap();
+
+        let version = replay.output("npm", &["--version"], TIMEOUT).unwrap();
+        assert!(version.status.success());
+        assert_eq!(version.stdout, b"10.8.2\n");
+
+        let outdated = replay.output("npm", &["outdated", "-g"], TIMEOUT).unwrap();
+        assert_eq!(outdated.status.code(), Some(1));
+
+        let timed_out = replay.output("brew", &["--version"], TIMEOUT).unwrap_err();
+        assert_eq!(timed_out.kind(), io::ErrorKind::TimedOut);
+
+        let missing = replay.output("npm", &["ls"], TIMEOUT).unwrap_err();
+        assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn recordings_replay_identically() {
+        let recorder = RecordingRunner::new(Arc::new(SystemRunner));
+        let real = recorder
+            .output("sh", &["-c", "echo out; echo err >&2; exit 3"], TIMEOUT)
+            .unwrap();
+        let _ = recorder.output("dev-janitor-missing-program", &[], TIMEOUT);
+
+        let replay = ReplayRunner::new(recorder.recorded());
+        let replayed = replay
+            .output("sh", &["-c", "echo out; echo err >&2; exit 3"], TIMEOUT)
+            .unwrap();
+        assert_eq!(replayed.status.code(), real.status.code());
+        assert_eq!(replayed.stdout, real.stdout);
+        assert_eq!(replayed.stderr, real.stderr);
+        assert_eq!(
+            replay
+                .output("dev-janitor-missing-program", &[], TIMEOUT)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_fixtures() {
+        let error = ReplayRunner::parse("{\"args\":[]}\n", "bad.jsonl").unwrap_err();
+        assert!(error.to_string().starts_with("bad.jsonl:1:"));
+    }
+}
@@ -0,0 +1,158 @@
+//! Version string helpers shared by the scanners
+
+use std::cmp::Ordering;
+
+fn version_parts(version: &str) -> Vec<&str> {
+    version
+        .trim_start_matches('v')
+        .split(['.', '-', '+'])
+        .collect()
+}
+
+/// Compare dotted versions numerically where both parts are numbers
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_parts, b_parts) = (version_parts(a), version_parts(b));
+
+    for index in 0..a_parts.len().max(b_parts.len()) {
+        let a_part = a_parts.get(index).copied().unwrap_or("0");
+        let b_part = b_parts.get(index).copied().unwrap_or("0");
+        let ordering = match (a_part.parse::<u64>(), b_part.parse::<u64>()) {
+            (Ok(a_number), Ok(b_number)) => a_number.cmp(&b_number),
+            _ => a_part.cmp(b_part),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Whether `version` is selected by a partial request such as `20`, `v20.11` or `3.12`
+pub fn matches_version_prefix(version: &str, request: &str) -> bool {
+    let (parts, request_parts) = (version_parts(version), version_parts(request));
+    request_parts.len() <= parts.len()
+        && request_parts
+            .iter()
+            .zip(&parts)
+            .all(|(requested, part)| requested == part)
+}
+
+/// Version part of a single requirement such as `^18.2`, `~3.11`, `20.x` or `v16`, without
+/// wildcard parts; `None` for ranges, alternatives and names like `lts/*` or `stable`
+pub fn requested_release(request: &str) -> Option<&str> {
+    let request = request.trim();
+    if request.contains(char::is_whitespace) || request.contains("||") {
+        return None;
+    }
+    let version = request
+        .trim_start_matches(['^', '~', '='])
+        .trim_start_matches('v');
+    let end = version
+        .split('.')
+        .take_while(|part| !matches!(*part, "x" | "X" | "*"))
+        .map(|part| part.len() + 1)
+        .sum::<usize>()
+        .saturating_sub(1);
+    let release = &version[..end.min(version.len())];
+    release
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some(release)
+}
+
+/// Whether `version` satisfies a project's request: a partial version (`20`, `3.12`), a
+/// wildcard (`18.x`), or an npm-style range (`^18.2.0`, `~3.11`, `>=18 <21`, `^18 || ^20`)
+pub fn satisfies_version_request(version: &str, request: &str) -> bool {
+    request.split("||").any(|alternative| {
+        // `>= 18` is the same comparator as `>=18`
+        let mut comparators: Vec<String> = Vec::new();
+        for token in alternative.split_whitespace() {
+            match comparators.last_mut() {
+                Some(last)
+                    if matches!(last.as_str(), ">" | ">=" | "<" | "<=" | "=" | "^" | "~") =>
+                {
+                    last.push_str(token)
+                }
+                _ => comparators.push(token.to_string()),
+            }
+        }
+        !comparators.is_empty()
+            && comparators
+                .iter()
+                .all(|comparator| satisfies_comparator(version, comparator))
+    })
+}
+
+fn satisfies_comparator(version: &str, comparator: &str) -> bool {
+    let operator_len = comparator
+        .find(|c: char| !matches!(c, '>' | '<' | '=' | '^' | '~'))
+        .unwrap_or(comparator.len());
+    let (operator, bound) = comparator.split_at(operator_len);
+    let Some(bound) = requested_release(bound)
+        .or_else(|| matches!(bound.trim_start_matches('v'), "" | "*" | "x" | "X").then_some(""))
+    else {
+        return false;
+    };
+    if bound.is_empty() {
+        return true;
+    }
+    let ordering = compare_versions(version, bound);
+    let parts: Vec<&str> = bound.split('.').collect();
+    match operator {
+        ">=" => ordering != Ordering::Less,
+        ">" => ordering == Ordering::Greater && !matches_version_prefix(version, bound),
+        "<" => ordering == Ordering::Less && !matches_version_prefix(version, bound),
+        "<=" => ordering != Ordering::Greater || matches_version_prefix(version, bound),
+        // ^1.2.3 keeps the major version, ^0.2.3 the minor one
+        "^" => {
+            let fixed = if parts[0] == "0" { 2 } else { 1 };
+            ordering != Ordering::Less
+                && matches_version_prefix(version, &parts[..fixed.min(parts.len())].join("."))
+        }
+        "~" => {
+            let fixed = if parts.len() > 1 { 2 } else { 1 };
+            ordering != Ordering::Less && matches_version_prefix(version, &parts[..fixed].join("."))
+        }
+        "" | "=" => matches_version_prefix(version, bound),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_numerically() {
+        assert_eq!(compare_versions("5.4.0", "5.10.0"), Ordering::Less);
+        assert_eq!(compare_versions("v2.0", "2.0.0"), Ordering::Equal);
+        assert_eq!(
+            compare_versions("1.0.0-rc1", "1.0.0-beta"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn matches_partial_versions() {
+        assert!(matches_version_prefix("v20.11.1", "20"));
+        assert!(matches_version_prefix("20.11.1", "v20.11"));
+        assert!(!matches_version_prefix("20.11.1", "2"));
+        assert!(!matches_version_prefix("3.12", "3.12.1"));
+    }
+
+    #[test]
+    fn understands_project_version_requests() {
+        assert!(satisfies_version_request("v18.19.0", "18.x"));
+        assert!(satisfies_version_request("18.19.0", "^18.2.0"));
+        assert!(!satisfies_version_request("18.1.0", "^18.2.0"));
+        assert!(satisfies_version_request("3.11.7", "~3.11"));
+        assert!(!satisfies_version_request("20.11.1", ">=18 <20"));
+        assert!(satisfies_version_request("20.11.1", ">= 18 <21"));
+        assert!(satisfies_version_request("20.11.1", "^18 || ^20"));
+        assert!(!satisfies_version_request("20.11.1", "lts/*"));
+
+        assert_eq!(requested_release("^18.2.0"), Some("18.2.0"));
+        assert_eq!(requested_release("20.x"), Some("20"));
+        assert_eq!(requested_release(">=18"), None);
+        assert_eq!(requested_release("stable"), None);
+    }
+}
@@ -119,7 +119,7 @@ export function AiCleanupView() {
                 const failCount = results.filter(r => r.Err).length;
 
                 if (failCount > 0) {
-                    const errors = results.filter(r => r.Err).map(r => r.Err).slice(0, 3).join('\n');
+                    const errors = results.filter(r => r.Err).map(r => r.Err?.message).slice(0, 3).join('\n');
                     setError(t('ai_cleanup.partial_failed', { count: failCount, errors }));
                 }
 
@@ -127,10 +127,12 @@ export function AiCleanupView() {
                     setSuccess(t('ai_cleanup.success_deleted', { count: successCount }));
                 }
             } else {
-                await deleteAiJunk(action.file.path);
+                const result = await deleteAiJunk(action.file.path);
                 setSuccess(t('ai_cleanup.success_deleted_single', {
                     name: action.file.name,
-                    size: action.file.size_display,
+                    size: result.bytes_quarantined > 0
+                        ? result.bytes_quarantined_display
+                        : result.bytes_freed_display,
                 }));
             }
 
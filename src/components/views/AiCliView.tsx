@@ -1,6 +1,6 @@
 import { useState, useCallback } from 'react';
 import { useTranslation } from 'react-i18next';
-import { getAiCliTools, installAiTool, updateAiTool, uninstallAiTool } from '../../ipc/commands';
+import { getAiCliTools, installAiTool, updateAiTool, uninstallAiTool, OperationResult } from '../../ipc/commands';
 import { useAppStore, AiCliToolStore } from '../../store';
 import { ConfirmDialog } from '../shared/ConfirmDialog';
 
@@ -83,7 +83,7 @@ export function AiCliView() {
         setSuccess(null);
 
         try {
-            let result: string;
+            let result: OperationResult;
             let message: string;
             if (type === 'install') {
                 result = await installAiTool(tool.id);
@@ -97,7 +97,8 @@ export function AiCliView() {
                 result = await uninstallAiTool(tool.id);
                 message = t('ai_cli.success_uninstall', { name: toolName });
             }
-            setSuccess(result.trim() ? `${message}\n${result}` : message);
+            const output = result.output?.trim() ?? '';
+            setSuccess(output ? `${message}\n${output}` : message);
             await refreshToolsData({ preserveMessages: true });
         } catch (e) {
             setError(String(e));
@@ -163,7 +163,7 @@ export function CacheView() {
                 const failCount = results.filter(r => r.Err).length;
 
                 if (failCount > 0) {
-                    const errors = results.filter(r => r.Err).map(r => r.Err).join('\n');
+                    const errors = results.filter(r => r.Err).map(r => r.Err?.message).join('\n');
                     setError(t('cache.partial_failed', { count: failCount, errors }));
                 }
 
@@ -179,10 +179,12 @@ export function CacheView() {
                     await scanProjectCachesData({ preserveMessages: true });
                 }
             } else {
-                await cleanCache(action.cache.path);
+                const result = await cleanCache(action.cache.path);
                 setSuccess(t('cache.success_clean_single', {
                     name: action.displayName,
-                    size: action.cache.size_display,
+                    size: result.bytes_quarantined > 0
+                        ? result.bytes_quarantined_display
+                        : result.bytes_freed_display,
                 }));
 
                 if (action.tab === 'package') {
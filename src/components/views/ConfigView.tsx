@@ -219,6 +219,13 @@ export function ConfigView() {
                                                 <div className="issue-content">
                                                     <strong className="issue-category">{issue.category}</strong>
                                                     <p className="issue-message">{issue.message}</p>
+                                                    {issue.evidence?.length > 0 && (
+                                                        <ul className="issue-evidence">
+                                                            {issue.evidence.map((line) => (
+                                                                <li key={line}>{line}</li>
+                                                            ))}
+                                                        </ul>
+                                                    )}
                                                     {issue.suggestion && (
                                                         <p className="issue-suggestion">
                                                             {t('config.suggestion', { suggestion: issue.suggestion })}
@@ -92,6 +92,10 @@ export function PackagesView() {
         composer: t('packages.managers.composer'),
         homebrew: t('packages.managers.homebrew'),
         conda: t('packages.managers.conda'),
+        bun: t('packages.managers.bun'),
+        gem: t('packages.managers.gem'),
+        go: t('packages.managers.go'),
+        dotnet: t('packages.managers.dotnet'),
     }), [t]);
 
     const { managers, groupedPackages, outdatedCount } = useMemo(() => {
@@ -230,6 +234,9 @@ export function PackagesView() {
                                             <tr key={`${pkg.manager}-${pkg.name}`}>
                                                 <td>
                                                     <strong>{pkg.name}</strong>
+                                                    {pkg.scope && (
+                                                        <span className="badge badge-info ml-8">{t(`packages.scopes.${pkg.scope}`)}</span>
+                                                    )}
                                                     {pkg.description && (
                                                         <span className="pkg-description">{pkg.description}</span>
                                                     )}
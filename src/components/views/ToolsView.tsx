@@ -199,6 +199,17 @@ export function ToolsView() {
                                                         <span className={`badge ${tool.status === 'installed' ? 'badge-success' : tool.status === 'multiple_versions' ? 'badge-warning' : 'badge-danger'}`}>
                                                             {t(`tools.${tool.status}`)}
                                                         </span>
+                                                        {tool.health?.some((check) => !check.passed) && (
+                                                            <span
+                                                                className="badge badge-danger ml-8"
+                                                                title={tool.health
+                                                                    .filter((check) => !check.passed)
+                                                                    .map((check) => `${check.name}: ${check.detail}${check.hint ? `\n${check.hint}` : ''}`)
+                                                                    .join('\n\n')}
+                                                            >
+                                                                {t('tools.checks_failed', { count: tool.health.filter((check) => !check.passed).length })}
+                                                            </span>
+                                                        )}
                                                     </td>
                                                     <td onClick={(e) => e.stopPropagation()}>
                                                         <button
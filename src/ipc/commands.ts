@@ -1,19 +1,103 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+/** Structured error from cleanup, uninstall and other mutating commands */
+export interface OperationError {
+    /** Stable code, e.g. unsafe_path, not_a_target, tool_missing, timeout, permission_denied */
+    code: string;
+    message: string;
+}
+
+export class CommandError extends Error {
+    readonly code: string;
+
+    constructor(cmd: string, error: OperationError) {
+        super(`[${cmd}] ${error.message}`);
+        this.name = 'CommandError';
+        this.code = error.code;
+    }
+}
+
+function isOperationError(e: unknown): e is OperationError {
+    return typeof e === 'object' && e !== null && 'code' in e && 'message' in e;
+}
 
 async function safeInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
     try {
         return await invoke<T>(cmd, args);
     } catch (e) {
+        if (isOperationError(e)) {
+            throw new CommandError(cmd, e);
+        }
         const message = e instanceof Error ? e.message : String(e);
         throw new Error(`[${cmd}] ${message}`);
     }
 }
 
+/** What a cleanup, uninstall or kill did */
+export interface OperationResult {
+    target: string;
+    bytes_freed: number;
+    bytes_freed_display: string;
+    /** Moved into quarantine; freed only when the quarantine is purged */
+    bytes_quarantined: number;
+    bytes_quarantined_display: string;
+    items_removed: number;
+    command: string | null;
+    output: string | null;
+    warnings: string[];
+    message: string;
+}
+
+export type BatchResult = Array<{ Ok?: OperationResult; Err?: OperationError }>;
+
 // Types matching Rust backend
 export interface ToolVersion {
     version: string;
     path: string;
     is_active: boolean;
+    /** `path` with symlinks resolved */
+    real_path: string;
+    /** Binary that wins the PATH lookup over this one */
+    shadowed_by: string | null;
+    lifecycle: Lifecycle | null;
+    source: InstallSource;
+    /** Package, formula, toolchain or version directory within `source` */
+    source_package: string | null;
+}
+
+export type InstallSource =
+    | 'apt'
+    | 'rpm'
+    | 'pacman'
+    | 'homebrew'
+    | 'nvm'
+    | 'pyenv'
+    | 'rustup'
+    | 'snap'
+    | 'npm_global'
+    | 'pnpm_global'
+    | 'yarn_global'
+    | 'bun_global'
+    | 'pipx'
+    | 'uv_tool'
+    | 'manual'
+    | 'unknown';
+
+export interface Lifecycle {
+    status: 'supported' | 'nearing_eol' | 'eol';
+    /** Release line, e.g. `20` for Node.js or `3.12` for Python */
+    cycle: string;
+    release: string;
+    eol: string | null;
+}
+
+export interface HealthCheckResult {
+    name: string;
+    passed: boolean;
+    /** The checked value on success, what went wrong otherwise */
+    detail: string;
+    hint: string | null;
 }
 
 export interface ToolInfo {
@@ -21,7 +105,10 @@ export interface ToolInfo {
     name: string;
     category: string;
     versions: ToolVersion[];
+    /** installed, not_in_path, multiple_versions or path_conflict */
     status: string;
+    /** Catalog health checks run against the active binary */
+    health: HealthCheckResult[];
 }
 
 // Tool commands
@@ -33,8 +120,23 @@ export async function getToolInfo(toolId: string): Promise<ToolInfo | null> {
     return safeInvoke<ToolInfo | null>('get_tool_info', { toolId });
 }
 
-export async function uninstallTool(toolId: string, path: string): Promise<string> {
-    return safeInvoke<string>('uninstall_tool', { toolId, path });
+export async function uninstallTool(toolId: string, path: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('uninstall_tool', { toolId, path });
+}
+
+export interface SystemPackage {
+    manager: 'apt' | 'rpm' | 'pacman';
+    name: string;
+    version: string | null;
+    /** Installed packages that depend on this one */
+    reverse_dependencies: string[];
+    /** Removal command as root would run it */
+    removal_command: { program: string; args: string[]; command_line: string };
+}
+
+/** The distribution package owning a tool binary, or null outside one */
+export async function inspectSystemPackage(path: string): Promise<SystemPackage | null> {
+    return safeInvoke<SystemPackage | null>('inspect_system_package_cmd', { path });
 }
 
 // ============ Package Management ============
@@ -46,6 +148,23 @@ export interface PackageInfo {
     manager: string;
     is_outdated: boolean;
     description: string | null;
+    /** user or system, for managers that install to both */
+    scope: string | null;
+}
+
+/** Operations a package manager supports besides listing, updating and uninstalling */
+export interface PackageManagerCapabilities {
+    outdated_check: boolean;
+    install: boolean;
+    pin: boolean;
+    info: boolean;
+    batch_update: boolean;
+}
+
+export interface PackageManagerSummary {
+    name: string;
+    version: string | null;
+    capabilities: PackageManagerCapabilities;
 }
 
 // Package commands
@@ -53,12 +172,32 @@ export async function scanPackages(): Promise<PackageInfo[]> {
     return safeInvoke<PackageInfo[]>('scan_packages');
 }
 
-export async function updatePackage(manager: string, name: string): Promise<string> {
-    return safeInvoke<string>('update_package', { manager, name });
+export async function updatePackage(manager: string, name: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('update_package', { manager, name });
+}
+
+export async function uninstallPackage(manager: string, name: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('uninstall_package', { manager, name });
+}
+
+export async function listPackageManagers(): Promise<PackageManagerSummary[]> {
+    return safeInvoke<PackageManagerSummary[]>('list_package_managers_cmd');
+}
+
+export async function installPackage(manager: string, name: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('install_package_cmd', { manager, name });
+}
+
+export async function pinPackage(manager: string, name: string, version: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('pin_package_cmd', { manager, name, version });
+}
+
+export async function updateAllPackages(manager: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('update_all_packages_cmd', { manager });
 }
 
-export async function uninstallPackage(manager: string, name: string): Promise<string> {
-    return safeInvoke<string>('uninstall_package', { manager, name });
+export async function getPackageInfo(manager: string, name: string): Promise<string> {
+    return safeInvoke<string>('package_info_cmd', { manager, name });
 }
 
 // ============ Cache Management ============
@@ -70,6 +209,7 @@ export interface CacheInfo {
     size: number;
     size_display: string;
     cache_type: string;
+    allocated_size: number;
 }
 
 // Cache commands
@@ -77,22 +217,71 @@ export async function scanCaches(): Promise<CacheInfo[]> {
     return safeInvoke<CacheInfo[]>('scan_caches');
 }
 
-export async function scanProjectCaches(path: string, maxDepth: number): Promise<CacheInfo[]> {
+/** Omit `path` to scan the configured scan roots, `maxDepth` to use the configured default */
+export async function scanProjectCaches(path?: string, maxDepth?: number): Promise<CacheInfo[]> {
     return safeInvoke<CacheInfo[]>('scan_project_caches_cmd', { path, maxDepth });
 }
 
-export async function cleanCache(path: string): Promise<string> {
-    return safeInvoke<string>('clean_cache_cmd', { path });
+export async function cleanCache(path: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('clean_cache_cmd', { path });
 }
 
-export async function cleanMultipleCaches(paths: string[]): Promise<Array<{ Ok?: string; Err?: string }>> {
-    return safeInvoke<Array<{ Ok?: string; Err?: string }>>('clean_multiple_caches', { paths });
+export async function cleanMultipleCaches(paths: string[]): Promise<BatchResult> {
+    return safeInvoke<BatchResult>('clean_multiple_caches', { paths });
 }
 
 export async function getTotalCacheSize(paths: string[]): Promise<string> {
     return safeInvoke<string>('get_total_cache_size', { paths });
 }
 
+// ============ Runtime Versions ============
+
+export interface RuntimeVersion {
+    /** nvm, fnm, volta, mise, asdf, pyenv, sdkman or rustup */
+    manager: string;
+    runtime: string;
+    version: string;
+    path: string;
+    size: number;
+    size_display: string;
+    allocated_size: number;
+    is_default: boolean;
+    lifecycle: Lifecycle | null;
+}
+
+export interface VersionPin {
+    /** Directory holding the version file */
+    project: string;
+    file: string;
+    runtime: string;
+    /** Version or range as written, e.g. `20`, `3.12.1` or `>=18 <21` */
+    request: string;
+    installed: string | null;
+    lifecycle: Lifecycle | null;
+}
+
+export interface PinReport {
+    pins: VersionPin[];
+    /** Installed versions no scanned project pins, apart from each manager's default */
+    unused: RuntimeVersion[];
+    missing: VersionPin[];
+    end_of_life: VersionPin[];
+}
+
+// Runtime version commands
+export async function scanRuntimeVersions(): Promise<RuntimeVersion[]> {
+    return safeInvoke<RuntimeVersion[]>('scan_runtime_versions_cmd');
+}
+
+/** Refused for the manager's default version */
+export async function removeRuntimeVersion(path: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('remove_runtime_version_cmd', { path });
+}
+
+export async function scanVersionPins(path?: string, maxDepth?: number): Promise<PinReport> {
+    return safeInvoke<PinReport>('scan_version_pins_cmd', { path, maxDepth });
+}
+
 // ============ AI Cleanup ============
 
 export interface AiJunkFile {
@@ -106,16 +295,17 @@ export interface AiJunkFile {
 }
 
 // AI Cleanup commands
-export async function scanAiJunk(path: string, maxDepth: number): Promise<AiJunkFile[]> {
+/** Omit `path` to scan the configured scan roots, `maxDepth` to use the configured default */
+export async function scanAiJunk(path?: string, maxDepth?: number): Promise<AiJunkFile[]> {
     return safeInvoke<AiJunkFile[]>('scan_ai_junk_cmd', { path, maxDepth });
 }
 
-export async function deleteAiJunk(path: string): Promise<string> {
-    return safeInvoke<string>('delete_ai_junk_cmd', { path });
+export async function deleteAiJunk(path: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('delete_ai_junk_cmd', { path });
 }
 
-export async function deleteMultipleAiJunk(paths: string[]): Promise<Array<{ Ok?: string; Err?: string }>> {
-    return safeInvoke<Array<{ Ok?: string; Err?: string }>>('delete_multiple_ai_junk', { paths });
+export async function deleteMultipleAiJunk(paths: string[]): Promise<BatchResult> {
+    return safeInvoke<BatchResult>('delete_multiple_ai_junk', { paths });
 }
 
 // ============ AI Chat History ============
@@ -141,7 +331,8 @@ export interface ProjectChatHistory {
     ai_tools_detected: string[];
 }
 
-export async function scanChatHistory(path: string, maxDepth: number): Promise<ProjectChatHistory[]> {
+/** Omit `path` to scan the configured scan roots, `maxDepth` to use the configured default */
+export async function scanChatHistory(path?: string, maxDepth?: number): Promise<ProjectChatHistory[]> {
     return safeInvoke<ProjectChatHistory[]>('scan_chat_history_cmd', { path, maxDepth });
 }
 
@@ -149,8 +340,8 @@ export async function scanGlobalChatHistory(): Promise<ChatHistoryFile[]> {
     return safeInvoke<ChatHistoryFile[]>('scan_global_chat_history_cmd');
 }
 
-export async function deleteChatFile(path: string): Promise<string> {
-    return safeInvoke<string>('delete_chat_file_cmd', { path });
+export async function deleteChatFile(path: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('delete_chat_file_cmd', { path });
 }
 
 export async function deleteMultipleChatFiles(paths: string[]): Promise<[number, number, string[]]> {
@@ -188,8 +379,8 @@ export async function getAllProcesses(): Promise<ProcessInfo[]> {
     return safeInvoke<ProcessInfo[]>('get_all_processes_cmd');
 }
 
-export async function killProcess(pid: number): Promise<string> {
-    return safeInvoke<string>('kill_process_cmd', { pid });
+export async function killProcess(pid: number): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('kill_process_cmd', { pid });
 }
 
 export async function getPorts(): Promise<PortInfo[]> {
@@ -224,6 +415,7 @@ export interface DiagnosisIssue {
     category: string;
     message: string;
     suggestion: string | null;
+    evidence: string[];
 }
 
 export interface EnvDiagnosis {
@@ -281,14 +473,253 @@ export async function getAiCliTools(): Promise<AiCliTool[]> {
     return safeInvoke<AiCliTool[]>('get_ai_cli_tools_cmd');
 }
 
-export async function installAiTool(toolId: string): Promise<string> {
-    return safeInvoke<string>('install_ai_tool_cmd', { toolId });
+export async function installAiTool(toolId: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('install_ai_tool_cmd', { toolId });
+}
+
+export async function updateAiTool(toolId: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('update_ai_tool_cmd', { toolId });
+}
+
+export async function uninstallAiTool(toolId: string): Promise<OperationResult> {
+    return safeInvoke<OperationResult>('uninstall_ai_tool_cmd', { toolId });
+}
+
+// ============ Scan Jobs ============
+
+export type ScanJobKind = 'project_caches' | 'ai_junk' | 'chat_history' | 'tools';
+
+export interface ScanProgress {
+    job_id: string;
+    kind: ScanJobKind;
+    directories_visited: number;
+    current_path: string | null;
+    items_found: number;
+    bytes_counted: number;
+    finished: boolean;
+}
+
+export type ScanJobResult =
+    | { kind: 'project_caches'; items: CacheInfo[] }
+    | { kind: 'ai_junk'; items: AiJunkFile[] }
+    | { kind: 'chat_history'; items: ProjectChatHistory[] }
+    | { kind: 'tools'; items: ToolInfo[] };
+
+export interface ScanJobOutcome {
+    job_id: string;
+    cancelled: boolean;
+    result: ScanJobResult;
+    progress: ScanProgress;
+}
+
+// Scan job commands
+export async function startScanJob(
+    jobId: string,
+    kind: ScanJobKind,
+    path: string | null,
+    maxDepth: number | null,
+): Promise<ScanJobOutcome> {
+    return safeInvoke<ScanJobOutcome>('start_scan_job_cmd', { jobId, kind, path, maxDepth });
+}
+
+export async function cancelScanJob(jobId: string): Promise<boolean> {
+    return safeInvoke<boolean>('cancel_scan_job_cmd', { jobId });
+}
+
+/** Subscribe to progress of all scan jobs; call the returned function to unsubscribe */
+export async function onScanProgress(handler: (progress: ScanProgress) => void): Promise<UnlistenFn> {
+    return listen<ScanProgress>('scan-progress', (event) => handler(event.payload));
+}
+
+// ============ User Rules ============
+
+export interface RulesSummary {
+    path: string | null;
+    search_paths: string[];
+    rules_added: number;
+    builtins_disabled: number;
+}
+
+// Rule commands
+export async function reloadRules(): Promise<RulesSummary> {
+    return safeInvoke<RulesSummary>('reload_rules_cmd');
+}
+
+// ============ Settings ============
+
+export type DeletionMode = 'quarantine' | 'delete';
+
+export interface Settings {
+    scan_roots: string[];
+    excluded_paths: string[];
+    default_scan_depth: number;
+    max_scan_depth: number;
+    min_project_cache_size: number;
+    min_ai_junk_size: number;
+    min_chat_history_size: number;
+    deletion_mode: DeletionMode;
+    retention_policies: RetentionPolicy[];
+    retention_interval_hours: number;
+    /** Let tool uninstall remove distribution packages through pkexec or `sudo -n` */
+    allow_privileged_uninstall: boolean;
+}
+
+// Settings commands
+export async function getSettings(): Promise<Settings> {
+    return safeInvoke<Settings>('get_settings_cmd');
+}
+
+export async function saveSettings(settings: Settings): Promise<Settings> {
+    return safeInvoke<Settings>('save_settings_cmd', { settings });
+}
+
+// ============ Retention Policies ============
+
+export type RetentionPolicyKind = 'package_cache' | 'project_cache' | 'chat_history';
+
+export interface RetentionPolicy {
+    id: string;
+    enabled: boolean;
+    kind: RetentionPolicyKind;
+    filter: string | null;
+    min_size: number | null;
+    older_than_days: number | null;
+    roots: string[];
+}
+
+export interface RetentionAction {
+    policy_id: string;
+    target: string;
+    size: number;
+    size_display: string;
+    age_days: number | null;
+    success: boolean | null;
+    error: string | null;
+    error_code: string | null;
+}
+
+export interface RetentionRun {
+    started_at: string;
+    finished_at: string;
+    trigger: 'manual' | 'schedule';
+    dry_run: boolean;
+    actions: RetentionAction[];
+    errors: string[];
+    bytes_freed: number;
+    bytes_freed_display: string;
+    bytes_quarantined: number;
+    bytes_quarantined_display: string;
+}
+
+// Retention commands
+export async function previewRetention(): Promise<RetentionRun> {
+    return safeInvoke<RetentionRun>('preview_retention_cmd');
+}
+
+export async function runRetention(): Promise<RetentionRun> {
+    return safeInvoke<RetentionRun>('run_retention_cmd');
+}
+
+/** Subscribe to scheduled retention runs that were skipped or failed */
+export async function onRetentionProblem(handler: (error: OperationError) => void): Promise<UnlistenFn> {
+    return listen<OperationError>('retention-problem', (event) => handler(event.payload));
+}
+
+export async function listRetentionRuns(limit?: number): Promise<RetentionRun[]> {
+    return safeInvoke<RetentionRun[]>('list_retention_runs_cmd', { limit });
+}
+
+// ============ Inventory Report ============
+
+export type ReportFormat = 'json' | 'markdown' | 'html';
+
+// Report commands
+export async function generateReport(format: ReportFormat): Promise<string> {
+    return safeInvoke<string>('generate_report_cmd', { format });
+}
+
+// ============ Inventory Snapshots ============
+
+export interface SnapshotInfo {
+    id: string;
+    generated_at: string;
+    hostname: string | null;
+    tools: number;
+    packages: number;
+    path: string;
+}
+
+export interface ToolPresenceChange {
+    id: string;
+    name: string;
+    versions: string[];
+}
+
+export interface ToolVersionChange {
+    id: string;
+    name: string;
+    added: ToolVersion[];
+    removed: ToolVersion[];
+    active_before: string | null;
+    active_after: string | null;
+}
+
+export interface PackageChange {
+    manager: string;
+    name: string;
+    from: string | null;
+    to: string | null;
+    latest: string | null;
+}
+
+export interface CacheGrowth {
+    id: string;
+    name: string;
+    path: string;
+    size_before: number;
+    size_after: number;
+    growth_display: string;
+}
+
+export interface SecurityFinding {
+    tool_id: string;
+    tool_name: string;
+    issue: string;
+    description: string;
+    risk_level: 'Critical' | 'High' | 'Medium' | 'Low';
+    remediation: string;
+    details: string;
+}
+
+export interface InventoryDiff {
+    from_generated_at: string;
+    to_generated_at: string;
+    tools_added: ToolPresenceChange[];
+    tools_removed: ToolPresenceChange[];
+    tool_version_changes: ToolVersionChange[];
+    packages_added: PackageChange[];
+    packages_removed: PackageChange[];
+    packages_upgraded: PackageChange[];
+    packages_downgraded: PackageChange[];
+    packages_newly_outdated: PackageChange[];
+    caches_grown: CacheGrowth[];
+    new_security_findings: SecurityFinding[];
+    resolved_security_findings: SecurityFinding[];
+}
+
+// Snapshot commands
+export async function takeSnapshot(): Promise<SnapshotInfo> {
+    return safeInvoke<SnapshotInfo>('take_snapshot_cmd');
+}
+
+export async function listSnapshots(): Promise<SnapshotInfo[]> {
+    return safeInvoke<SnapshotInfo[]>('list_snapshots_cmd');
 }
 
-export async function updateAiTool(toolId: string): Promise<string> {
-    return safeInvoke<string>('update_ai_tool_cmd', { toolId });
+export async function deleteSnapshot(id: string): Promise<void> {
+    return safeInvoke<void>('delete_snapshot_cmd', { id });
 }
 
-export async function uninstallAiTool(toolId: string): Promise<string> {
-    return safeInvoke<string>('uninstall_ai_tool_cmd', { toolId });
+export async function diffSnapshots(from: string, to?: string): Promise<InventoryDiff> {
+    return safeInvoke<InventoryDiff>('diff_snapshots_cmd', { from, to });
 }